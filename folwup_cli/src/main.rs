@@ -1,3 +1,414 @@
-fn main() {
-    println!("Hello, world!");
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs,
+    io::{self, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use clap::{Parser, Subcommand};
+use folwup_core::{
+    encoding, feed,
+    opml::{self, Outline},
+};
+use folwup_http::{Fetcher, Validators};
+use folwup_sqlite::{RetentionPolicy, Search, Store, Subscription};
+
+/// Follow feeds.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    /// The path to the database.
+    #[arg(long, env = "FOLWUP_DATABASE", default_value = "folwup.db")]
+    database: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Subscribes to the feeds in an OPML file.
+    ImportOpml {
+        /// The OPML file to read or `-` for standard input.
+        file: PathBuf,
+    },
+    /// Writes the subscriptions as an OPML file.
+    ExportOpml {
+        /// The file to write instead of standard output.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Fetches the subscribed feeds and stores new items.
+    Fetch {
+        /// Only fetches the feed with the URL.
+        #[arg(long)]
+        feed: Option<String>,
+    },
+    /// Lists the subscriptions in their folders.
+    List,
+    /// Moves a subscription into a folder.
+    Move {
+        /// The URL of the feed.
+        url: String,
+        /// The `/` separated path of the folder which is created if
+        /// necessary. The subscription is moved to the top level if omitted.
+        folder: Option<String>,
+    },
+    /// Adds tags to a subscription.
+    Tag {
+        /// The URL of the feed.
+        url: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// Removes the tags instead.
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Shows or sets a retention policy.
+    ///
+    /// Without any rules, the current policy is shown.
+    Retention {
+        /// Sets the policy of the feed with the URL instead of the global
+        /// policy.
+        #[arg(long)]
+        feed: Option<String>,
+        /// Keeps only the newest items.
+        #[arg(long)]
+        max_items: Option<u32>,
+        /// Keeps only items newer than the number of days.
+        #[arg(long)]
+        max_age_days: Option<u32>,
+        /// Removes the content of items older than the number of days.
+        #[arg(long)]
+        content_max_age_days: Option<u32>,
+        /// Also removes starred items.
+        #[arg(long)]
+        delete_starred: bool,
+        /// Removes the policy.
+        #[arg(
+            long,
+            conflicts_with_all = ["max_items", "max_age_days", "content_max_age_days", "delete_starred"]
+        )]
+        clear: bool,
+    },
+    /// Removes items according to the retention policies.
+    Prune {
+        /// Reports what would be removed without changing anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Searches the stored items.
+    ///
+    /// Every term must match. Use double quotes for a phrase and a trailing
+    /// `*` for a prefix.
+    Search {
+        query: String,
+        /// Only search the items of the feed with the URL.
+        #[arg(long)]
+        feed: Option<String>,
+        /// The maximum number of results.
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+fn import_opml(store: &mut Store, file: &Path) -> Result<(), Box<dyn Error>> {
+    let bytes = if file.as_os_str() == "-" {
+        let mut bytes = Vec::new();
+        io::stdin().read_to_end(&mut bytes)?;
+        bytes
+    } else {
+        fs::read(file)?
+    };
+
+    let input = encoding::decode(&bytes, None);
+    let document = opml::parse(&input)?;
+    let added = store.import_opml(&document)?;
+    println!("Added {added} subscriptions");
+
+    Ok(())
+}
+
+fn export_opml(store: &Store, output: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let mut document = store.export_opml()?;
+    document.date_created = Some(Utc::now());
+
+    let mut value = String::new();
+    opml::write(&mut value, &document)?;
+
+    match output {
+        Some(path) => fs::write(path, value)?,
+        None => io::stdout().write_all(value.as_bytes())?,
+    }
+
+    Ok(())
+}
+
+fn subscription_id(store: &Store, url: &str) -> Result<i64, Box<dyn Error>> {
+    Ok(store
+        .subscription_by_url(url)?
+        .ok_or_else(|| format!("not subscribed to {url}"))?
+        .id)
+}
+
+fn fetch_subscription(
+    store: &mut Store,
+    fetcher: &Fetcher,
+    subscription: &Subscription,
+) -> Result<(), Box<dyn Error>> {
+    let name = subscription.title.as_ref().unwrap_or(&subscription.url);
+    let response = fetcher.fetch(
+        &subscription.url,
+        &Validators {
+            etag: subscription.etag.as_deref(),
+            last_modified: subscription.last_modified.as_deref(),
+        },
+    )?;
+
+    if let Some(url) = &response.moved_to {
+        store.set_subscription_url(subscription.id, url)?;
+        println!("{name}: moved to {url}");
+    }
+
+    match response.text() {
+        Some(text) => {
+            let document = feed::parse_feed_with_base(&text, &response.url)?;
+            let items = feed::Iter::with_str_and_base(&text, &response.url)?;
+            let upserted = store.upsert_feed(subscription.id, &document, items)?;
+            println!(
+                "{name}: {} new, {} updated",
+                upserted.inserted, upserted.updated
+            );
+        }
+        None => println!("{name}: not modified"),
+    }
+
+    // The validators are only stored once the response is stored.
+    store.set_fetched(
+        subscription.id,
+        response.etag.as_deref(),
+        response.last_modified.as_deref(),
+    )?;
+
+    Ok(())
+}
+
+fn fetch(store: &mut Store, feed: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let subscriptions = match feed {
+        Some(url) => vec![store
+            .subscription_by_url(url)?
+            .ok_or_else(|| format!("not subscribed to {url}"))?],
+        None => store.subscriptions()?,
+    };
+
+    let fetcher = Fetcher::default();
+    let mut failed = 0;
+    for subscription in &subscriptions {
+        if let Err(error) = fetch_subscription(store, &fetcher, subscription) {
+            let name = subscription.title.as_ref().unwrap_or(&subscription.url);
+            eprintln!("{name}: {error}");
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!(
+            "{failed} of {} feeds could not be fetched",
+            subscriptions.len()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+fn write_outlines(w: &mut impl Write, indent: usize, outlines: &[Outline]) -> io::Result<()> {
+    for outline in outlines {
+        let name = outline.name().unwrap_or_default();
+        match &outline.xml_url {
+            Some(url) => {
+                write!(w, "{:indent$}{name} <{url}>", "")?;
+                for tag in &outline.categories {
+                    write!(w, " #{tag}")?;
+                }
+                writeln!(w)?;
+            }
+            None => writeln!(w, "{:indent$}{name}/", "")?,
+        }
+        write_outlines(w, indent + 2, &outline.children)?;
+    }
+    Ok(())
+}
+
+fn list(store: &Store) -> Result<(), Box<dyn Error>> {
+    let document = store.export_opml()?;
+    write_outlines(&mut io::stdout().lock(), 0, &document.outlines)?;
+    Ok(())
+}
+
+fn move_subscription(store: &Store, url: &str, folder: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let id = subscription_id(store, url)?;
+    let mut folder_id = None;
+    for name in folder
+        .into_iter()
+        .flat_map(|path| path.split('/'))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        folder_id = Some(store.folder_id(folder_id, name)?);
+    }
+    store.move_subscription(id, folder_id)?;
+    Ok(())
+}
+
+fn tag(store: &mut Store, url: &str, tags: &[String], remove: bool) -> Result<(), Box<dyn Error>> {
+    let id = subscription_id(store, url)?;
+    for tag in tags {
+        if remove {
+            store.remove_tag(id, tag)?;
+        } else {
+            store.add_tag(id, tag)?;
+        }
+    }
+    Ok(())
+}
+
+fn retention(
+    store: &Store,
+    feed: Option<&str>,
+    policy: Option<RetentionPolicy>,
+    clear: bool,
+) -> Result<(), Box<dyn Error>> {
+    let subscription_id = feed.map(|url| subscription_id(store, url)).transpose()?;
+
+    if clear {
+        store.remove_retention_policy(subscription_id)?;
+        return Ok(());
+    }
+    if let Some(policy) = policy {
+        store.set_retention_policy(subscription_id, &policy)?;
+        return Ok(());
+    }
+
+    match store.retention_policy(subscription_id)? {
+        Some(policy) => {
+            let show = |value: Option<u32>| value.map_or(String::from("-"), |v| v.to_string());
+            println!("max items: {}", show(policy.max_items));
+            println!("max age days: {}", show(policy.max_age_days));
+            println!(
+                "content max age days: {}",
+                show(policy.content_max_age_days)
+            );
+            println!("keep starred: {}", policy.keep_starred);
+        }
+        None if subscription_id.is_some() => println!("Uses the global policy"),
+        None => println!("No policy"),
+    }
+
+    Ok(())
+}
+
+fn prune(store: &mut Store, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let subscriptions = store
+        .subscriptions()?
+        .into_iter()
+        .map(|s| (s.id, s.title.unwrap_or(s.url)))
+        .collect::<HashMap<_, _>>();
+
+    let pruned = store.prune(Utc::now(), dry_run)?;
+    let (verb, content_verb) = if dry_run {
+        ("Would delete", "would remove content of")
+    } else {
+        ("Deleted", "removed content of")
+    };
+    for p in &pruned {
+        let name = subscriptions
+            .get(&p.subscription_id)
+            .map_or("", String::as_str);
+        println!(
+            "{name}: {verb} {} items, {content_verb} {} items",
+            p.deleted, p.content_removed
+        );
+    }
+
+    if !dry_run {
+        let bytes = store.reclaim_space()?;
+        println!("Reclaimed {bytes} bytes");
+    }
+
+    Ok(())
+}
+
+fn search(
+    store: &Store,
+    query: &str,
+    feed: Option<&str>,
+    limit: usize,
+) -> Result<(), Box<dyn Error>> {
+    let subscription_id = feed.map(|url| subscription_id(store, url)).transpose()?;
+    let highlight = if io::stdout().is_terminal() {
+        ("\x1b[1m", "\x1b[0m")
+    } else {
+        ("[", "]")
+    };
+
+    let results = store.search(&Search {
+        subscription_id,
+        limit,
+        highlight,
+        ..Search::new(query)
+    })?;
+
+    let mut stdout = io::stdout().lock();
+    for result in results {
+        let item = &result.item.item;
+        let title = item
+            .title
+            .as_ref()
+            .map_or("(untitled)", |v| v.value.as_str());
+        writeln!(stdout, "{title}")?;
+        if let Some(url) = &item.url {
+            writeln!(stdout, "  {url}")?;
+        }
+        writeln!(stdout, "  {}", result.snippet)?;
+    }
+
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    let mut store = Store::open(&cli.database)?;
+
+    match &cli.command {
+        Command::ImportOpml { file } => import_opml(&mut store, file),
+        Command::ExportOpml { output } => export_opml(&store, output.as_deref()),
+        Command::Fetch { feed } => fetch(&mut store, feed.as_deref()),
+        Command::List => list(&store),
+        Command::Move { url, folder } => move_subscription(&store, url, folder.as_deref()),
+        Command::Tag { url, tags, remove } => tag(&mut store, url, tags, *remove),
+        Command::Retention {
+            feed,
+            max_items,
+            max_age_days,
+            content_max_age_days,
+            delete_starred,
+            clear,
+        } => {
+            let policy = (max_items.is_some()
+                || max_age_days.is_some()
+                || content_max_age_days.is_some()
+                || *delete_starred)
+                .then_some(RetentionPolicy {
+                    max_items: *max_items,
+                    max_age_days: *max_age_days,
+                    keep_starred: !delete_starred,
+                    content_max_age_days: *content_max_age_days,
+                });
+            retention(&store, feed.as_deref(), policy, *clear)
+        }
+        Command::Prune { dry_run } => prune(&mut store, *dry_run),
+        Command::Search { query, feed, limit } => search(&store, query, feed.as_deref(), *limit),
+    }
 }
@@ -0,0 +1,460 @@
+//! Discovery of the feeds for a website.
+//!
+//! When a user enters the URL of a website instead of a feed, the page's
+//! HTML is searched for [feed autodiscovery][autodiscovery] links such as:
+//!
+//! ```html
+//! <link rel="alternate" type="application/atom+xml" title="Posts" href="/atom.xml">
+//! ```
+//!
+//! Common feed locations are also suggested in case the page does not link
+//! to its feeds. Those candidates must be fetched to verify that they exist.
+//!
+//! ```
+//! use folwup_core::discovery::{self, Source};
+//!
+//! let html = r#"<!DOCTYPE html>
+//! <html>
+//!   <head>
+//!     <link rel="alternate" type="application/rss+xml" title="Blog" href="/blog/rss.xml">
+//!   </head>
+//! </html>"#;
+//!
+//! let candidates = discovery::discover(html, "https://example.com/blog/");
+//!
+//! assert_eq!("https://example.com/blog/rss.xml", candidates[0].url);
+//! assert_eq!(Some("Blog"), candidates[0].title.as_deref());
+//! assert_eq!(Source::Link, candidates[0].source);
+//! assert!(candidates[1..].iter().all(|c| c.source == Source::Fallback));
+//! ```
+//!
+//! [autodiscovery]: https://www.rssboard.org/rss-autodiscovery
+
+use alloc::{format, string::String, vec::Vec};
+
+use crate::{
+    feed::{Text, TextTy},
+    url,
+};
+
+/// Paths where feeds are commonly found, in order of preference.
+pub const FALLBACK_PATHS: &[&str] = &[
+    "/feed",
+    "/rss.xml",
+    "/atom.xml",
+    "/feed.xml",
+    "/index.xml",
+    "/feed.json",
+];
+
+/// Where a candidate feed URL was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Source {
+    /// A `<link rel="alternate">` element in the page.
+    Link,
+    /// A common feed location which is not linked from the page.
+    Fallback,
+}
+
+/// A URL which may be a feed for the website.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Candidate {
+    /// The absolute URL of the feed.
+    pub url: String,
+    /// The `title` of the link which usually describes the feed (e.g.
+    /// "Comments Feed").
+    pub title: Option<String>,
+    /// The feed type declared by the link.
+    ///
+    /// Fallback candidates do not have a type.
+    pub ty: Option<readfeed::Ty>,
+    pub source: Source,
+}
+
+impl Candidate {
+    /// Returns true if the candidate appears to be a feed of comments instead
+    /// of posts.
+    #[must_use]
+    fn is_comments(&self) -> bool {
+        fn contains_comments(value: &str) -> bool {
+            value.to_ascii_lowercase().contains("comments")
+        }
+
+        contains_comments(&self.url) || self.title.as_deref().is_some_and(contains_comments)
+    }
+}
+
+/// Returns the feed type for a link's `type` attribute.
+#[must_use]
+fn link_ty(value: &str) -> Option<readfeed::Ty> {
+    let mime_type = value.split(';').next().unwrap_or_default().trim();
+    if mime_type.eq_ignore_ascii_case("application/atom+xml") {
+        Some(readfeed::Ty::Atom)
+    } else if mime_type.eq_ignore_ascii_case("application/rss+xml")
+        || mime_type.eq_ignore_ascii_case("application/rdf+xml")
+    {
+        Some(readfeed::Ty::Rss)
+    } else if mime_type.eq_ignore_ascii_case("application/feed+json") {
+        Some(readfeed::Ty::Json)
+    } else {
+        None
+    }
+}
+
+/// An HTML start tag.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Tag<'a> {
+    name: &'a str,
+    /// The attribute names and values. A valueless attribute has an empty
+    /// value.
+    attributes: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Tag<'a> {
+    /// Finds an attribute value by its name.
+    #[must_use]
+    fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Returns the position of the needle ignoring ASCII case.
+#[must_use]
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+/// Parses the attributes of a tag.
+///
+/// Returns the attributes and the input after the end of the tag.
+fn parse_attributes(mut input: &str) -> (Vec<(&str, &str)>, &str) {
+    let mut attributes = Vec::new();
+
+    loop {
+        input = input.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        let Some(first) = input.chars().next() else {
+            return (attributes, "");
+        };
+        if first == '>' {
+            return (attributes, &input[1..]);
+        }
+
+        // A name may start with `=` but cannot contain one afterwards.
+        let name_end = input[first.len_utf8()..]
+            .find(|c: char| c.is_ascii_whitespace() || matches!(c, '/' | '>' | '='))
+            .map_or(input.len(), |end| end + first.len_utf8());
+        let name = &input[..name_end];
+        input = input[name_end..].trim_start_matches(|c: char| c.is_ascii_whitespace());
+
+        let mut value = "";
+        if let Some(rest) = input.strip_prefix('=') {
+            let rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace());
+            if let Some(quote) = rest.chars().next().filter(|c| matches!(c, '"' | '\'')) {
+                let rest = &rest[1..];
+                let end = rest.find(quote).unwrap_or(rest.len());
+                value = &rest[..end];
+                input = rest.get(end + 1..).unwrap_or_default();
+            } else {
+                let end = rest
+                    .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                    .unwrap_or(rest.len());
+                value = &rest[..end];
+                input = &rest[end..];
+            }
+        }
+
+        attributes.push((name, value));
+    }
+}
+
+/// Iterates over the start tags in an HTML document.
+///
+/// Comments and the raw text of `script` and `style` elements are skipped.
+/// Markup which is not a start tag is ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Tags<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Tags<'a> {
+    type Item = Tag<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.rest.find('<')?;
+            let rest = &self.rest[start + 1..];
+
+            if let Some(comment) = rest.strip_prefix("!--") {
+                self.rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+                continue;
+            }
+
+            if !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                self.rest = rest;
+                continue;
+            }
+            let name_end = rest
+                .find(|c: char| c.is_ascii_whitespace() || matches!(c, '/' | '>'))
+                .unwrap_or(rest.len());
+            let name = &rest[..name_end];
+            let (attributes, rest) = parse_attributes(&rest[name_end..]);
+            self.rest = rest;
+
+            if ["script", "style"]
+                .into_iter()
+                .any(|raw| name.eq_ignore_ascii_case(raw))
+            {
+                let close = format!("</{name}");
+                self.rest = find_ignore_ascii_case(rest, &close).map_or("", |end| &rest[end..]);
+            }
+
+            return Some(Tag { name, attributes });
+        }
+    }
+}
+
+/// Decodes an attribute value.
+///
+/// Only XML entity references are decoded.
+#[must_use]
+fn attribute_value(value: &str) -> String {
+    Text::xml(value, TextTy::Text).decode().trim().into()
+}
+
+/// Finds the feed candidates for a website.
+///
+/// The `html` is the content of the page at `page_url`. Relative URLs are
+/// resolved against the page's `<base href>` or else the `page_url`.
+///
+/// Linked feeds are returned first in document order, except that feeds
+/// which appear to contain comments are ranked after the other linked
+/// feeds. Then a candidate for each of the [`FALLBACK_PATHS`] which was not
+/// linked is returned.
+#[must_use]
+pub fn discover(html: &str, page_url: &str) -> Vec<Candidate> {
+    let mut base = String::from(page_url.trim());
+    let mut candidates: Vec<Candidate> = Vec::new();
+
+    for tag in (Tags { rest: html }) {
+        let name = tag.name;
+
+        if name.eq_ignore_ascii_case("base") {
+            if let Some(href) = tag
+                .attribute("href")
+                .and_then(|href| url::resolve(&base, &attribute_value(href)))
+            {
+                base = href;
+            }
+            continue;
+        }
+
+        if !name.eq_ignore_ascii_case("link") {
+            continue;
+        }
+
+        let is_alternate = tag.attribute("rel").is_some_and(|rel| {
+            rel.split_ascii_whitespace()
+                .any(|rel| rel.eq_ignore_ascii_case("alternate"))
+        });
+        if !is_alternate {
+            continue;
+        }
+        let Some(ty) = tag.attribute("type").and_then(link_ty) else {
+            continue;
+        };
+        let Some(url) = tag
+            .attribute("href")
+            .map(attribute_value)
+            .filter(|href| !href.is_empty())
+            .and_then(|href| url::resolve(&base, &href))
+        else {
+            continue;
+        };
+        if candidates.iter().any(|c| c.url == url) {
+            continue;
+        }
+
+        candidates.push(Candidate {
+            url,
+            title: tag
+                .attribute("title")
+                .map(attribute_value)
+                .filter(|title| !title.is_empty()),
+            ty: Some(ty),
+            source: Source::Link,
+        });
+    }
+
+    // The sort is stable so the document order is otherwise kept.
+    candidates.sort_by_key(Candidate::is_comments);
+
+    for path in FALLBACK_PATHS {
+        let Some(url) = url::resolve(page_url, path) else {
+            break;
+        };
+        if candidates.iter().any(|c| c.url == url) {
+            continue;
+        }
+        candidates.push(Candidate {
+            url,
+            title: None,
+            ty: None,
+            source: Source::Fallback,
+        });
+    }
+
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn links() {
+        let html = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>Example &amp; Co</title>
+  <link rel="stylesheet" href="/style.css">
+  <link rel="alternate" type="application/rss+xml" title="Example &amp; Co &#187; Comments Feed" href="https://example.com/comments/feed/" />
+  <link rel="alternate" type="application/rss+xml" title="Example &amp; Co &#187; Feed" href="https://example.com/feed/" />
+  <LINK REL="Alternate Home" TYPE="Application/Atom+XML; charset=utf-8" HREF="atom.xml">
+  <link rel="alternate" type="application/feed+json" title="JSON" href="/feed.json">
+  <link rel="alternate" type="text/html" hreflang="fr" href="/fr/">
+  <link rel="alternate" type="application/atom+xml" href="">
+  <link rel="alternate" type="application/rss+xml" href="/feed/">
+  <!-- <link rel="alternate" type="application/rss+xml" href="/hidden.xml"> -->
+</head>
+<body>
+  <p>1 < 2</p>
+</body>
+</html>
+"#;
+
+        let candidates = discover(html, "https://example.com/blog/post?id=1");
+        let links = candidates
+            .iter()
+            .filter(|c| c.source == Source::Link)
+            .map(|c| (c.url.as_str(), c.title.as_deref(), c.ty))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                (
+                    "https://example.com/feed/",
+                    Some("Example & Co \u{bb} Feed"),
+                    Some(readfeed::Ty::Rss)
+                ),
+                (
+                    "https://example.com/blog/atom.xml",
+                    None,
+                    Some(readfeed::Ty::Atom)
+                ),
+                (
+                    "https://example.com/feed.json",
+                    Some("JSON"),
+                    Some(readfeed::Ty::Json)
+                ),
+                (
+                    "https://example.com/comments/feed/",
+                    Some("Example & Co \u{bb} Comments Feed"),
+                    Some(readfeed::Ty::Rss)
+                ),
+            ],
+            links
+        );
+
+        let fallbacks = candidates
+            .iter()
+            .filter(|c| c.source == Source::Fallback)
+            .map(|c| c.url.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "https://example.com/feed",
+                "https://example.com/rss.xml",
+                "https://example.com/atom.xml",
+                "https://example.com/feed.xml",
+                "https://example.com/index.xml",
+            ],
+            fallbacks
+        );
+    }
+
+    #[test]
+    fn base() {
+        let html = r#"<html><head>
+<base href="https://cdn.example.com/site/">
+<link rel="alternate" type="application/atom+xml" href="atom.xml">
+</head></html>"#;
+
+        let candidates = discover(html, "https://example.com/");
+        assert_eq!("https://cdn.example.com/site/atom.xml", candidates[0].url);
+        assert_eq!("https://example.com/feed", candidates[1].url);
+    }
+
+    #[test]
+    fn html() {
+        for (head, expected) in [
+            (
+                r#"<meta name=viewport content=width=device-width>
+<link rel="alternate" type="application/rss+xml" href="/feed.xml">"#,
+                "https://example.com/feed.xml",
+            ),
+            (
+                "<link rel=alternate type=application/rss+xml href=/feed.xml>",
+                "https://example.com/feed.xml",
+            ),
+            (
+                r#"<link rel="stylesheet" href="/style.css" disabled>
+<link rel="alternate" type="application/rss+xml" href="/feed.xml">"#,
+                "https://example.com/feed.xml",
+            ),
+            (
+                r#"<link rel="alternate" type="application/rss+xml" href="/x.xml?a=1&b=2">"#,
+                "https://example.com/x.xml?a=1&b=2",
+            ),
+            (
+                r#"<script>if (a<b) { document.write("<link rel='alternate'>"); }</script>
+<STYLE>a > b { color: red }</Style>
+<link rel="alternate" type="application/rss+xml" href="/feed.xml">"#,
+                "https://example.com/feed.xml",
+            ),
+            (
+                "<link rel='alternate' type='application/rss+xml' href='/feed.xml'/>",
+                "https://example.com/feed.xml",
+            ),
+        ] {
+            let html = format!("<!doctype html><html><head>{head}</head><body></body></html>");
+            let candidates = discover(&html, "https://example.com/");
+            assert_eq!(Source::Link, candidates[0].source, "{head}");
+            assert_eq!(expected, candidates[0].url, "{head}");
+        }
+
+        let html =
+            r#"<script>x = "<link rel=alternate type=application/rss+xml href=/a.xml>"</script>"#;
+        assert_eq!(
+            Source::Fallback,
+            discover(html, "https://example.com/")[0].source
+        );
+    }
+
+    #[test]
+    fn no_links() {
+        let candidates = discover("<html><body>No feeds<!-", "https://example.com/a/b");
+        assert_eq!(FALLBACK_PATHS.len(), candidates.len());
+        assert_eq!("https://example.com/feed", candidates[0].url);
+
+        assert!(discover("<html></html>", "example.com").is_empty());
+    }
+}
@@ -0,0 +1,303 @@
+//! Detection of a document's character encoding and transcoding to UTF-8.
+//!
+//! The [`feed`][crate::feed] parsers accept `&str` input. A document which is
+//! retrieved as bytes is transcoded with [`decode()`] first. The encoding is
+//! determined by (in order of precedence):
+//!
+//! 1. A byte order mark
+//! 2. The `charset` parameter of the HTTP `Content-Type` header
+//! 3. The `encoding` in the XML declaration
+//!
+//! Otherwise, the document is assumed to be UTF-8.
+//!
+//! ```
+//! use folwup_core::{encoding, feed};
+//!
+//! let bytes = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\
+//!     <rss version=\"2.0\"><channel><title>Caf\xe9</title></channel></rss>";
+//!
+//! let input = encoding::decode(bytes, Some("application/rss+xml"));
+//! let feed = feed::parse_feed(&input)?;
+//!
+//! assert_eq!(Some("Café"), feed.title.map(|title| title.decode()).as_deref());
+//! # Ok::<(), feed::Error>(())
+//! ```
+
+use alloc::borrow::Cow;
+
+pub use encoding_rs::Encoding;
+
+/// The maximum number of bytes searched for the end of the XML declaration.
+pub(crate) const MAX_XML_DECLARATION_LEN: usize = 1024;
+
+/// Where a document's encoding was determined from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Source {
+    /// A byte order mark at the start of the document.
+    Bom,
+    /// The `charset` parameter of the `Content-Type` header.
+    ContentType,
+    /// The `encoding` in the XML declaration.
+    XmlDeclaration,
+    /// No encoding was declared, so UTF-8 is assumed.
+    Default,
+}
+
+/// The detected encoding of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Detected {
+    pub encoding: &'static Encoding,
+    pub source: Source,
+}
+
+/// Returns the value of the `charset` parameter in a `Content-Type` header
+/// value.
+///
+/// ```
+/// use folwup_core::encoding;
+///
+/// assert_eq!(
+///     Some("Shift_JIS"),
+///     encoding::charset("text/xml; charset=\"Shift_JIS\"")
+/// );
+/// assert_eq!(None, encoding::charset("application/atom+xml"));
+/// ```
+#[must_use]
+pub fn charset(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        if !name.trim().eq_ignore_ascii_case("charset") {
+            return None;
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        Some(value.trim()).filter(|v| !v.is_empty())
+    })
+}
+
+/// Returns the label in the `encoding` pseudo-attribute of the XML
+/// declaration.
+///
+/// Only ASCII compatible documents are searched.
+fn xml_declaration_label(bytes: &[u8]) -> Option<&[u8]> {
+    let bytes = bytes.trim_ascii_start();
+    let bytes = &bytes[..bytes.len().min(MAX_XML_DECLARATION_LEN)];
+    let decl = bytes.strip_prefix(b"<?xml")?;
+    if !decl.first().is_some_and(u8::is_ascii_whitespace) {
+        return None;
+    }
+    let end = decl.windows(2).position(|v| v == b"?>")?;
+    let decl = &decl[..end];
+
+    let start = decl.windows(8).position(|v| v == b"encoding")?;
+    let rest = decl[start + 8..].trim_ascii_start();
+    let rest = rest.strip_prefix(b"=")?.trim_ascii_start();
+    let (&quote, rest) = rest.split_first()?;
+    if quote != b'"' && quote != b'\'' {
+        return None;
+    }
+    let end = rest.iter().position(|&b| b == quote)?;
+    Some(rest[..end].trim_ascii())
+}
+
+/// Determines the encoding of a document.
+///
+/// The `content_type` is the value of the HTTP `Content-Type` header, if
+/// any. Unknown encoding labels are ignored.
+#[must_use]
+pub fn detect(bytes: &[u8], content_type: Option<&str>) -> Detected {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return Detected {
+            encoding,
+            source: Source::Bom,
+        };
+    }
+
+    if let Some(encoding) = content_type
+        .and_then(charset)
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+    {
+        return Detected {
+            encoding,
+            source: Source::ContentType,
+        };
+    }
+
+    if let Some(encoding) = xml_declaration_label(bytes).and_then(Encoding::for_label) {
+        // The declaration was read as ASCII, so the document cannot actually
+        // be UTF-16.
+        let encoding = if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+            encoding_rs::UTF_8
+        } else {
+            encoding
+        };
+        return Detected {
+            encoding,
+            source: Source::XmlDeclaration,
+        };
+    }
+
+    Detected {
+        encoding: encoding_rs::UTF_8,
+        source: Source::Default,
+    }
+}
+
+/// Transcodes a document to UTF-8.
+///
+/// The encoding is determined by [`detect()`]. A byte order mark is removed
+/// and malformed sequences are replaced with U+FFFD.
+///
+/// If the document is already valid UTF-8, the input is borrowed.
+#[must_use]
+pub fn decode<'a>(bytes: &'a [u8], content_type: Option<&str>) -> Cow<'a, str> {
+    let detected = detect(bytes, content_type);
+    let bytes = match detected.source {
+        Source::Bom => {
+            let bom_len = Encoding::for_bom(bytes).map_or(0, |(_, len)| len);
+            &bytes[bom_len..]
+        }
+        Source::ContentType | Source::XmlDeclaration | Source::Default => bytes,
+    };
+    detected.encoding.decode_without_bom_handling(bytes).0
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec::Vec,
+    };
+
+    use encoding_rs::{ISO_8859_2, SHIFT_JIS, UTF_16BE, UTF_8, WINDOWS_1251, WINDOWS_1252};
+
+    use crate::feed;
+
+    use super::*;
+
+    fn rss(encoding: &str, title: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="{encoding}"?>
+<rss version="2.0"><channel><title>{title}</title></channel></rss>"#
+        )
+    }
+
+    fn title(input: &str) -> Option<String> {
+        feed::parse_feed(input)
+            .unwrap()
+            .title
+            .map(|title| title.to_string())
+    }
+
+    #[test]
+    fn content_type_charset() {
+        assert_eq!(Some("utf-8"), charset("text/xml;charset=utf-8"));
+        assert_eq!(
+            Some("windows-1251"),
+            charset("application/rss+xml; Charset = \"windows-1251\"")
+        );
+        assert_eq!(None, charset("text/xml; charset="));
+        assert_eq!(None, charset("charset=utf-8"));
+        assert_eq!(None, charset("text/xml; format=flowed"));
+    }
+
+    #[test]
+    fn xml_declaration() {
+        assert_eq!(
+            Some(&b"Shift_JIS"[..]),
+            xml_declaration_label(b"<?xml version=\"1.0\" encoding=\"Shift_JIS\"?><rss/>")
+        );
+        assert_eq!(
+            Some(&b"koi8-r"[..]),
+            xml_declaration_label(b"\n<?xml version='1.0' encoding = 'koi8-r' ?>")
+        );
+        assert_eq!(None, xml_declaration_label(b"<?xml version=\"1.0\"?>"));
+        assert_eq!(
+            None,
+            xml_declaration_label(b"<?xml-stylesheet href=\"a\"?>")
+        );
+        assert_eq!(None, xml_declaration_label(b"<rss encoding=\"koi8-r\"/>"));
+    }
+
+    #[test]
+    fn precedence() {
+        let mut bytes = Vec::from(&b"\xEF\xBB\xBF"[..]);
+        bytes.extend_from_slice(rss("ISO-8859-1", "Caf\u{e9}").as_bytes());
+        assert_eq!(
+            Detected {
+                encoding: UTF_8,
+                source: Source::Bom
+            },
+            detect(&bytes, Some("text/xml; charset=windows-1251"))
+        );
+        assert_eq!(
+            Some("Caf\u{e9}"),
+            title(&decode(&bytes, Some("text/xml; charset=windows-1251"))).as_deref()
+        );
+
+        let bytes = rss("ISO-8859-2", "");
+        assert_eq!(
+            Detected {
+                encoding: WINDOWS_1251,
+                source: Source::ContentType
+            },
+            detect(bytes.as_bytes(), Some("text/xml; charset=windows-1251"))
+        );
+        assert_eq!(
+            Detected {
+                encoding: ISO_8859_2,
+                source: Source::XmlDeclaration
+            },
+            detect(bytes.as_bytes(), Some("text/xml; charset=unknown"))
+        );
+        assert_eq!(
+            Detected {
+                encoding: UTF_8,
+                source: Source::Default
+            },
+            detect(b"{\"version\": \"https://jsonfeed.org/version/1.1\"}", None)
+        );
+        assert_eq!(UTF_8, detect(rss("UTF-16", "").as_bytes(), None).encoding,);
+    }
+
+    #[test]
+    fn transcode() {
+        let input = rss("Shift_JIS", "\u{65e5}\u{672c}\u{8a9e}");
+        let (bytes, _, _) = SHIFT_JIS.encode(&input);
+        assert_eq!(
+            Some("\u{65e5}\u{672c}\u{8a9e}"),
+            title(&decode(&bytes, None)).as_deref()
+        );
+
+        let input = rss(
+            "windows-1251",
+            "\u{41d}\u{43e}\u{432}\u{43e}\u{441}\u{442}\u{438}",
+        );
+        let (bytes, _, _) = WINDOWS_1251.encode(&input);
+        assert_eq!(
+            Some("\u{41d}\u{43e}\u{432}\u{43e}\u{441}\u{442}\u{438}"),
+            title(&decode(&bytes, Some("application/rss+xml"))).as_deref()
+        );
+
+        let input = rss("ISO-8859-1", "Caf\u{e9}");
+        let (bytes, _, _) = WINDOWS_1252.encode(&input);
+        assert_eq!(Some("Caf\u{e9}"), title(&decode(&bytes, None)).as_deref());
+
+        let mut bytes = Vec::from(&b"\xFE\xFF"[..]);
+        for unit in rss("UTF-16", "\u{65e5}\u{672c}").encode_utf16() {
+            bytes.extend_from_slice(&unit.to_be_bytes());
+        }
+        assert_eq!(UTF_16BE, detect(&bytes, None).encoding);
+        assert_eq!(
+            Some("\u{65e5}\u{672c}"),
+            title(&decode(&bytes, None)).as_deref()
+        );
+
+        let input = rss("UTF-8", "Caf\u{e9}");
+        assert!(matches!(decode(input.as_bytes(), None), Cow::Borrowed(_)));
+    }
+}
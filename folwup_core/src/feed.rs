@@ -1,22 +1,570 @@
+use core::{fmt, time::Duration};
+
 use chrono::{DateTime, Utc};
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+use alloc::{string::String, vec::Vec};
+
+use crate::{url, xml};
+
+pub use key::ItemKey;
+pub use schedule::{SkipDays, SkipHours};
+pub use text::{Text, TextBuf, TextTy};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Feed<'a> {
-    pub title: Option<&'a str>,
-    pub description: Option<&'a str>,
-    pub home_page_url: Option<&'a str>,
+    pub title: Option<Text<'a>>,
+    pub description: Option<Text<'a>>,
+    pub home_page_url: Option<Text<'a>>,
+    /// The URL of the feed document itself (e.g. an Atom `rel="self"` link).
+    pub feed_url: Option<Text<'a>>,
+    /// The URL of an alternate version of the feed, usually the website (e.g.
+    /// an Atom `rel="alternate"` link).
+    pub alternate_url: Option<Text<'a>>,
+    pub authors: Vec<Author<'a>>,
+    pub contributors: Vec<Author<'a>>,
+    pub categories: Vec<Category<'a>>,
+    /// A language tag such as `en-US`.
+    pub language: Option<Text<'a>>,
+    /// The URL of a small, square image such as a favicon.
+    pub icon: Option<Text<'a>>,
+    /// The URL of a larger image such as an RSS channel `image`.
+    pub logo: Option<Text<'a>>,
+    /// When the feed was last updated (e.g. an RSS `lastBuildDate`).
+    pub modified_at: Option<DateTime<Utc>>,
+    pub generator: Option<Generator<'a>>,
+    /// Copyright or licensing information.
+    pub rights: Option<Text<'a>>,
+    /// How long the feed may be cached before it is fetched again.
+    pub ttl: Option<Duration>,
+    /// Hours when the feed does not need to be fetched.
+    pub skip_hours: SkipHours,
+    /// Days when the feed does not need to be fetched.
+    pub skip_days: SkipDays,
+    /// The `xml:base` of the feed element which relative URLs are resolved
+    /// against.
+    pub xml_base: Option<Text<'a>>,
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Item<'a> {
-    pub id: Option<&'a str>,
-    pub title: Option<&'a str>,
-    pub content: Option<&'a str>,
-    pub summary: Option<&'a str>,
-    pub url: Option<&'a str>,
-    pub external_url: Option<&'a str>,
+    pub id: Option<Text<'a>>,
+    /// Whether the `id` is also the URL of the item such as an RSS `guid`
+    /// without `isPermaLink="false"`.
+    pub id_is_permalink: bool,
+    pub title: Option<Text<'a>>,
+    pub content: Option<Text<'a>>,
+    pub summary: Option<Text<'a>>,
+    pub url: Option<Text<'a>>,
+    pub external_url: Option<Text<'a>>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub modified_at: Option<DateTime<Utc>>,
+    /// The authors of the item.
+    ///
+    /// If the item does not have any authors, the authors of an Atom entry's
+    /// `source` element or else the authors of the feed are used.
+    pub authors: Vec<Author<'a>>,
+    pub contributors: Vec<Author<'a>>,
+    pub categories: Vec<Category<'a>>,
+    /// Related resources such as audio or video files (e.g. an RSS
+    /// `enclosure`).
+    pub attachments: Vec<Attachment<'a>>,
+    /// [iTunes podcast][itunes] metadata.
+    ///
+    /// [itunes]: https://podcasters.apple.com/support/823-podcast-requirements
+    pub podcast: Podcast<'a>,
+    /// The `xml:base` of the item element which relative URLs are resolved
+    /// against.
+    ///
+    /// The value may itself be relative to the feed's
+    /// [`xml_base`][Feed::xml_base].
+    pub xml_base: Option<Text<'a>>,
+}
+
+/// A person such as the author of an item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Author<'a> {
+    pub name: Option<Text<'a>>,
+    pub email: Option<Text<'a>>,
+    pub url: Option<Text<'a>>,
+    /// The URL of an image of the author.
+    pub avatar: Option<Text<'a>>,
+}
+
+impl Author<'_> {
+    #[must_use]
+    fn is_empty(&self) -> bool {
+        self.name.is_none() && self.email.is_none() && self.url.is_none() && self.avatar.is_none()
+    }
+}
+
+/// The software which generated the feed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Generator<'a> {
+    pub name: Text<'a>,
+    pub url: Option<Text<'a>>,
+    pub version: Option<Text<'a>>,
+}
+
+/// A category or tag.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Category<'a> {
+    pub term: Text<'a>,
+    /// The categorization scheme such as an Atom `scheme` or an RSS `domain`.
+    pub scheme: Option<Text<'a>>,
+    /// A human readable label.
+    pub label: Option<Text<'a>>,
+}
+
+/// A resource related to an item such as a podcast episode's audio file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Attachment<'a> {
+    pub url: Text<'a>,
+    pub mime_type: Option<Text<'a>>,
+    pub title: Option<Text<'a>>,
+    pub size_in_bytes: Option<u64>,
+    pub duration: Option<Duration>,
+}
+
+/// Podcast episode metadata from the iTunes namespace.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Podcast<'a> {
+    pub duration: Option<Duration>,
+    pub episode: Option<u32>,
+    pub season: Option<u32>,
+    pub explicit: Option<bool>,
+    pub image: Option<Text<'a>>,
+}
+
+impl Feed<'_> {
+    /// Copies the borrowed values into an owned [`FeedBuf`].
+    #[must_use]
+    pub fn to_feed_buf(&self) -> FeedBuf {
+        FeedBuf::from(self.clone())
+    }
+}
+
+impl Item<'_> {
+    /// Copies the borrowed values into an owned [`ItemBuf`].
+    #[must_use]
+    pub fn to_item_buf(&self) -> ItemBuf {
+        ItemBuf::from(self.clone())
+    }
+}
+
+fn decode_to_string(text: Text<'_>) -> String {
+    text.decode().into_owned()
+}
+
+/// Resolves the `xml:base` value against the base URL.
+#[must_use]
+fn effective_base(base: &str, xml_base: Option<&str>) -> String {
+    xml_base
+        .and_then(|xml_base| url::resolve(base, xml_base))
+        .unwrap_or_else(|| String::from(base))
+}
+
+/// Replaces a relative URL with the URL resolved against the base URL.
+fn resolve_url(base: &str, value: &mut String) {
+    if let Some(resolved) = url::resolve(base, value) {
+        *value = resolved;
+    }
+}
+
+fn resolve_author_urls(base: &str, author: &mut AuthorBuf) {
+    author
+        .url
+        .iter_mut()
+        .chain(author.avatar.iter_mut())
+        .for_each(|value| resolve_url(base, value));
+}
+
+/// An owned [`Feed`] with decoded values.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FeedBuf {
+    pub title: Option<TextBuf>,
+    pub description: Option<TextBuf>,
+    pub home_page_url: Option<String>,
+    pub feed_url: Option<String>,
+    pub alternate_url: Option<String>,
+    pub authors: Vec<AuthorBuf>,
+    pub contributors: Vec<AuthorBuf>,
+    pub categories: Vec<CategoryBuf>,
+    pub language: Option<String>,
+    pub icon: Option<String>,
+    pub logo: Option<String>,
+    pub modified_at: Option<DateTime<Utc>>,
+    pub generator: Option<GeneratorBuf>,
+    pub rights: Option<TextBuf>,
+    pub ttl: Option<Duration>,
+    pub skip_hours: SkipHours,
+    pub skip_days: SkipDays,
+    pub xml_base: Option<String>,
+}
+
+impl FeedBuf {
+    /// Borrows the values as a [`Feed`].
+    #[must_use]
+    pub fn as_feed(&self) -> Feed<'_> {
+        Feed {
+            title: self.title.as_ref().map(TextBuf::as_text),
+            description: self.description.as_ref().map(TextBuf::as_text),
+            home_page_url: self.home_page_url.as_deref().map(Text::plain),
+            feed_url: self.feed_url.as_deref().map(Text::plain),
+            alternate_url: self.alternate_url.as_deref().map(Text::plain),
+            authors: self.authors.iter().map(AuthorBuf::as_author).collect(),
+            contributors: self.contributors.iter().map(AuthorBuf::as_author).collect(),
+            categories: self
+                .categories
+                .iter()
+                .map(CategoryBuf::as_category)
+                .collect(),
+            language: self.language.as_deref().map(Text::plain),
+            icon: self.icon.as_deref().map(Text::plain),
+            logo: self.logo.as_deref().map(Text::plain),
+            modified_at: self.modified_at,
+            generator: self.generator.as_ref().map(GeneratorBuf::as_generator),
+            rights: self.rights.as_ref().map(TextBuf::as_text),
+            ttl: self.ttl,
+            skip_hours: self.skip_hours,
+            skip_days: self.skip_days,
+            xml_base: self.xml_base.as_deref().map(Text::plain),
+        }
+    }
+}
+
+impl FeedBuf {
+    /// Resolves relative URLs against the feed's `xml:base` and the URL the
+    /// document was retrieved from.
+    pub fn resolve_urls(&mut self, base: &str) {
+        let base = effective_base(base, self.xml_base.as_deref());
+
+        self.home_page_url
+            .iter_mut()
+            .chain(self.feed_url.iter_mut())
+            .chain(self.alternate_url.iter_mut())
+            .chain(self.icon.iter_mut())
+            .chain(self.logo.iter_mut())
+            .chain(self.generator.iter_mut().flat_map(|g| g.url.iter_mut()))
+            .for_each(|value| resolve_url(&base, value));
+
+        self.authors
+            .iter_mut()
+            .chain(self.contributors.iter_mut())
+            .for_each(|author| resolve_author_urls(&base, author));
+    }
+}
+
+impl From<Feed<'_>> for FeedBuf {
+    fn from(value: Feed<'_>) -> Self {
+        Self {
+            title: value.title.map(TextBuf::from),
+            description: value.description.map(TextBuf::from),
+            home_page_url: value.home_page_url.map(decode_to_string),
+            feed_url: value.feed_url.map(decode_to_string),
+            alternate_url: value.alternate_url.map(decode_to_string),
+            authors: value.authors.into_iter().map(AuthorBuf::from).collect(),
+            contributors: value
+                .contributors
+                .into_iter()
+                .map(AuthorBuf::from)
+                .collect(),
+            categories: value
+                .categories
+                .into_iter()
+                .map(CategoryBuf::from)
+                .collect(),
+            language: value.language.map(decode_to_string),
+            icon: value.icon.map(decode_to_string),
+            logo: value.logo.map(decode_to_string),
+            modified_at: value.modified_at,
+            generator: value.generator.map(GeneratorBuf::from),
+            rights: value.rights.map(TextBuf::from),
+            ttl: value.ttl,
+            skip_hours: value.skip_hours,
+            skip_days: value.skip_days,
+            xml_base: value.xml_base.map(decode_to_string),
+        }
+    }
+}
+
+/// An owned [`Item`] with decoded values.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ItemBuf {
+    pub id: Option<String>,
+    pub id_is_permalink: bool,
+    pub title: Option<TextBuf>,
+    pub content: Option<TextBuf>,
+    pub summary: Option<TextBuf>,
+    pub url: Option<String>,
+    pub external_url: Option<String>,
     pub published_at: Option<DateTime<Utc>>,
     pub modified_at: Option<DateTime<Utc>>,
+    pub authors: Vec<AuthorBuf>,
+    pub contributors: Vec<AuthorBuf>,
+    pub categories: Vec<CategoryBuf>,
+    pub attachments: Vec<AttachmentBuf>,
+    pub podcast: PodcastBuf,
+    pub xml_base: Option<String>,
+}
+
+impl ItemBuf {
+    /// Borrows the values as an [`Item`].
+    #[must_use]
+    pub fn as_item(&self) -> Item<'_> {
+        Item {
+            id: self.id.as_deref().map(Text::plain),
+            id_is_permalink: self.id_is_permalink,
+            title: self.title.as_ref().map(TextBuf::as_text),
+            content: self.content.as_ref().map(TextBuf::as_text),
+            summary: self.summary.as_ref().map(TextBuf::as_text),
+            url: self.url.as_deref().map(Text::plain),
+            external_url: self.external_url.as_deref().map(Text::plain),
+            published_at: self.published_at,
+            modified_at: self.modified_at,
+            authors: self.authors.iter().map(AuthorBuf::as_author).collect(),
+            contributors: self.contributors.iter().map(AuthorBuf::as_author).collect(),
+            categories: self
+                .categories
+                .iter()
+                .map(CategoryBuf::as_category)
+                .collect(),
+            attachments: self
+                .attachments
+                .iter()
+                .map(AttachmentBuf::as_attachment)
+                .collect(),
+            podcast: self.podcast.as_podcast(),
+            xml_base: self.xml_base.as_deref().map(Text::plain),
+        }
+    }
+}
+
+impl ItemBuf {
+    /// Resolves relative URLs against the item's `xml:base` and a base URL.
+    ///
+    /// The base URL should already be resolved against the feed's `xml:base`
+    /// (see [`Iter::with_str_and_base()`]).
+    pub fn resolve_urls(&mut self, base: &str) {
+        let base = effective_base(base, self.xml_base.as_deref());
+
+        self.url
+            .iter_mut()
+            .chain(self.external_url.iter_mut())
+            .chain(self.attachments.iter_mut().map(|a| &mut a.url))
+            .chain(self.podcast.image.iter_mut())
+            .for_each(|value| resolve_url(&base, value));
+
+        self.authors
+            .iter_mut()
+            .chain(self.contributors.iter_mut())
+            .for_each(|author| resolve_author_urls(&base, author));
+    }
+}
+
+impl From<Item<'_>> for ItemBuf {
+    fn from(value: Item<'_>) -> Self {
+        Self {
+            id: value.id.map(decode_to_string),
+            id_is_permalink: value.id_is_permalink,
+            title: value.title.map(TextBuf::from),
+            content: value.content.map(TextBuf::from),
+            summary: value.summary.map(TextBuf::from),
+            url: value.url.map(decode_to_string),
+            external_url: value.external_url.map(decode_to_string),
+            published_at: value.published_at,
+            modified_at: value.modified_at,
+            authors: value.authors.into_iter().map(AuthorBuf::from).collect(),
+            contributors: value
+                .contributors
+                .into_iter()
+                .map(AuthorBuf::from)
+                .collect(),
+            categories: value
+                .categories
+                .into_iter()
+                .map(CategoryBuf::from)
+                .collect(),
+            attachments: value
+                .attachments
+                .into_iter()
+                .map(AttachmentBuf::from)
+                .collect(),
+            podcast: PodcastBuf::from(value.podcast),
+            xml_base: value.xml_base.map(decode_to_string),
+        }
+    }
+}
+
+/// An owned [`Author`] with decoded values.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AuthorBuf {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub url: Option<String>,
+    pub avatar: Option<String>,
+}
+
+impl AuthorBuf {
+    /// Borrows the values as an [`Author`].
+    #[must_use]
+    pub fn as_author(&self) -> Author<'_> {
+        Author {
+            name: self.name.as_deref().map(Text::plain),
+            email: self.email.as_deref().map(Text::plain),
+            url: self.url.as_deref().map(Text::plain),
+            avatar: self.avatar.as_deref().map(Text::plain),
+        }
+    }
+}
+
+impl From<Author<'_>> for AuthorBuf {
+    fn from(value: Author<'_>) -> Self {
+        Self {
+            name: value.name.map(decode_to_string),
+            email: value.email.map(decode_to_string),
+            url: value.url.map(decode_to_string),
+            avatar: value.avatar.map(decode_to_string),
+        }
+    }
+}
+
+/// An owned [`Generator`] with decoded values.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GeneratorBuf {
+    pub name: String,
+    pub url: Option<String>,
+    pub version: Option<String>,
+}
+
+impl GeneratorBuf {
+    /// Borrows the values as a [`Generator`].
+    #[must_use]
+    pub fn as_generator(&self) -> Generator<'_> {
+        Generator {
+            name: Text::plain(&self.name),
+            url: self.url.as_deref().map(Text::plain),
+            version: self.version.as_deref().map(Text::plain),
+        }
+    }
+}
+
+impl From<Generator<'_>> for GeneratorBuf {
+    fn from(value: Generator<'_>) -> Self {
+        Self {
+            name: decode_to_string(value.name),
+            url: value.url.map(decode_to_string),
+            version: value.version.map(decode_to_string),
+        }
+    }
+}
+
+/// An owned [`Category`] with decoded values.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CategoryBuf {
+    pub term: String,
+    pub scheme: Option<String>,
+    pub label: Option<String>,
+}
+
+impl CategoryBuf {
+    /// Borrows the values as a [`Category`].
+    #[must_use]
+    pub fn as_category(&self) -> Category<'_> {
+        Category {
+            term: Text::plain(&self.term),
+            scheme: self.scheme.as_deref().map(Text::plain),
+            label: self.label.as_deref().map(Text::plain),
+        }
+    }
+}
+
+impl From<Category<'_>> for CategoryBuf {
+    fn from(value: Category<'_>) -> Self {
+        Self {
+            term: decode_to_string(value.term),
+            scheme: value.scheme.map(decode_to_string),
+            label: value.label.map(decode_to_string),
+        }
+    }
+}
+
+/// An owned [`Attachment`] with decoded values.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AttachmentBuf {
+    pub url: String,
+    pub mime_type: Option<String>,
+    pub title: Option<String>,
+    pub size_in_bytes: Option<u64>,
+    pub duration: Option<Duration>,
+}
+
+impl AttachmentBuf {
+    /// Borrows the values as an [`Attachment`].
+    #[must_use]
+    pub fn as_attachment(&self) -> Attachment<'_> {
+        Attachment {
+            url: Text::plain(&self.url),
+            mime_type: self.mime_type.as_deref().map(Text::plain),
+            title: self.title.as_deref().map(Text::plain),
+            size_in_bytes: self.size_in_bytes,
+            duration: self.duration,
+        }
+    }
+}
+
+impl From<Attachment<'_>> for AttachmentBuf {
+    fn from(value: Attachment<'_>) -> Self {
+        Self {
+            url: decode_to_string(value.url),
+            mime_type: value.mime_type.map(decode_to_string),
+            title: value.title.map(decode_to_string),
+            size_in_bytes: value.size_in_bytes,
+            duration: value.duration,
+        }
+    }
+}
+
+/// An owned [`Podcast`] with decoded values.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PodcastBuf {
+    pub duration: Option<Duration>,
+    pub episode: Option<u32>,
+    pub season: Option<u32>,
+    pub explicit: Option<bool>,
+    pub image: Option<String>,
+}
+
+impl PodcastBuf {
+    /// Borrows the values as a [`Podcast`].
+    #[must_use]
+    pub fn as_podcast(&self) -> Podcast<'_> {
+        Podcast {
+            duration: self.duration,
+            episode: self.episode,
+            season: self.season,
+            explicit: self.explicit,
+            image: self.image.as_deref().map(Text::plain),
+        }
+    }
+}
+
+impl From<Podcast<'_>> for PodcastBuf {
+    fn from(value: Podcast<'_>) -> Self {
+        Self {
+            duration: value.duration,
+            episode: value.episode,
+            season: value.season,
+            explicit: value.explicit,
+            image: value.image.map(decode_to_string),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -24,35 +572,183 @@ pub struct Iter<T> {
     feed: T,
 }
 
+/// Iterates over owned items with resolved URLs.
+#[derive(Debug)]
+pub struct ResolveIter<'a> {
+    iter: Iter<IterFeedTy<'a>>,
+    base: String,
+}
+
 #[derive(Debug)]
 pub enum IterFeedTy<'a> {
-    Atom(Iter<readfeed::atom::FeedIter<'a>>),
-    Rss(Iter<readfeed::rss::ChannelIter<'a>>),
+    Atom(Iter<atom::EntriesIter<'a>>),
+    Rss(Iter<rss::ItemsIter<'a>>),
+    Json(Iter<json::ItemsIter<'a>>),
+    Rdf(Iter<rdf::RdfIter<'a>>),
 }
 
 pub mod atom;
+pub mod date;
+pub mod json;
+pub mod key;
+pub mod rdf;
 pub mod rss;
+pub mod schedule;
+pub mod stream;
+pub mod text;
+pub mod write;
 
+/// Error when a document cannot be parsed as a feed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Error {
+    /// The document is not a supported feed format such as an HTML page.
+    Unsupported(readfeed::Ty),
+    /// A required element is missing from the document.
+    MissingElement {
+        ty: readfeed::Ty,
+        name: &'static str,
+    },
+    /// The document could not be parsed.
+    Malformed(readfeed::Ty),
+    /// The buffered input exceeded the limit while parsing incrementally.
+    LimitExceeded(readfeed::Ty),
+}
+
+impl Error {
+    /// The detected type of the document.
+    #[must_use]
+    pub const fn ty(&self) -> readfeed::Ty {
+        match self {
+            Error::Unsupported(ty)
+            | Error::MissingElement { ty, .. }
+            | Error::Malformed(ty)
+            | Error::LimitExceeded(ty) => *ty,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn ty_name(ty: readfeed::Ty) -> &'static str {
+            match ty {
+                readfeed::Ty::Atom => "Atom",
+                readfeed::Ty::Json => "JSON",
+                readfeed::Ty::Rss => "RSS",
+                readfeed::Ty::Unknown => "unknown",
+                readfeed::Ty::XmlOrHtml => "XML or HTML",
+            }
+        }
+
+        match self {
+            Error::Unsupported(ty) => write!(f, "unsupported document type: {}", ty_name(*ty)),
+            Error::MissingElement { ty, name } => {
+                write!(f, "{} document is missing a `{name}` element", ty_name(*ty))
+            }
+            Error::Malformed(ty) => write!(f, "malformed {} document", ty_name(*ty)),
+            Error::LimitExceeded(ty) => {
+                write!(f, "{} document exceeded the buffer limit", ty_name(*ty))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Detects the type of document and parses the feed level metadata.
+///
+/// # Errors
+///
+/// Returns an error if the document is not a supported feed type or if the
+/// document is missing required elements.
 #[allow(clippy::module_name_repetitions)]
-#[must_use]
-pub fn parse_feed(input: &str) -> Option<Feed<'_>> {
-    match readfeed::detect_type(input) {
+pub fn parse_feed(input: &str) -> Result<Feed<'_>, Error> {
+    match readfeed::detect_type(xml::trim_truncated(input)) {
         readfeed::Ty::Atom => atom::parse_feed(input),
         readfeed::Ty::Rss => rss::parse_feed(input),
-        readfeed::Ty::Json | readfeed::Ty::Unknown | readfeed::Ty::XmlOrHtml => None,
+        readfeed::Ty::Json => json::parse_feed(input),
+        readfeed::Ty::XmlOrHtml => rdf::parse_feed(input),
+        readfeed::Ty::Unknown => Err(Error::Unsupported(readfeed::Ty::Unknown)),
     }
 }
 
 impl<'a> Iter<IterFeedTy<'a>> {
-    #[must_use]
-    pub fn with_str(input: &'a str) -> Option<Self> {
-        let feed = match readfeed::detect_type(input) {
+    /// Detects the type of document and iterates over the items.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not a supported feed type or if
+    /// the document is missing required elements.
+    pub fn with_str(input: &'a str) -> Result<Self, Error> {
+        let feed = match readfeed::detect_type(xml::trim_truncated(input)) {
             readfeed::Ty::Atom => IterFeedTy::Atom(Iter::with_atom(input)?),
             readfeed::Ty::Rss => IterFeedTy::Rss(Iter::with_rss(input)?),
-            readfeed::Ty::Json | readfeed::Ty::Unknown | readfeed::Ty::XmlOrHtml => return None,
+            readfeed::Ty::Json => IterFeedTy::Json(Iter::with_json(input)?),
+            readfeed::Ty::XmlOrHtml => IterFeedTy::Rdf(Iter::with_rdf(input)?),
+            readfeed::Ty::Unknown => return Err(Error::Unsupported(readfeed::Ty::Unknown)),
         };
 
-        Some(Self { feed })
+        Ok(Self { feed })
+    }
+}
+
+/// Detects the type of document, parses the feed level metadata, and
+/// resolves relative URLs.
+///
+/// The `base` is the URL which the document was retrieved from.
+///
+/// # Errors
+///
+/// Returns an error if the document is not a supported feed type or if the
+/// document is missing required elements.
+pub fn parse_feed_with_base(input: &str, base: &str) -> Result<FeedBuf, Error> {
+    let mut feed = FeedBuf::from(parse_feed(input)?);
+    feed.resolve_urls(base);
+    Ok(feed)
+}
+
+impl<'a> Iter<ResolveIter<'a>> {
+    /// Detects the type of document and iterates over owned items with
+    /// relative URLs resolved.
+    ///
+    /// The `base` is the URL which the document was retrieved from. URLs are
+    /// resolved against the `xml:base` of the feed and item elements as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not a supported feed type or if
+    /// the document is missing required elements.
+    pub fn with_str_and_base(input: &'a str, base: &str) -> Result<Self, Error> {
+        let iter = Iter::with_str(input)?;
+        let xml_base = iter.xml_base().map(|v| v.decode());
+        let base = effective_base(base, xml_base.as_deref());
+
+        Ok(Self {
+            feed: ResolveIter { iter, base },
+        })
+    }
+}
+
+impl Iterator for Iter<ResolveIter<'_>> {
+    type Item = ItemBuf;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut item = ItemBuf::from(self.feed.iter.next()?);
+        item.resolve_urls(&self.feed.base);
+        Some(item)
+    }
+}
+
+impl<'a> Iter<IterFeedTy<'a>> {
+    /// The `xml:base` of the feed level element.
+    #[must_use]
+    fn xml_base(&self) -> Option<Text<'a>> {
+        match &self.feed {
+            IterFeedTy::Atom(iter) => iter.xml_base(),
+            IterFeedTy::Rss(iter) => iter.xml_base(),
+            IterFeedTy::Json(_) => None,
+            IterFeedTy::Rdf(iter) => iter.xml_base(),
+        }
     }
 }
 
@@ -63,6 +759,92 @@ impl<'a> Iterator for Iter<IterFeedTy<'a>> {
         match &mut self.feed {
             IterFeedTy::Atom(iter) => iter.next(),
             IterFeedTy::Rss(iter) => iter.next(),
+            IterFeedTy::Json(iter) => iter.next(),
+            IterFeedTy::Rdf(iter) => iter.next(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_atom_xml_base() {
+        let input = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:base="/blog/">
+    <title>Lorem ipsum dolor sit amet.</title>
+    <link href="./"/>
+    <link rel="self" href="feed.xml"/>
+    <logo>/logo.png</logo>
+    <entry xml:base="2021/">
+        <id>urn:uuid:1</id>
+        <link href="post-1"/>
+        <link rel="enclosure" type="audio/mpeg" href="../media/1.mp3"/>
+        <author><name>Jane Doe</name><uri>/about</uri></author>
+    </entry>
+    <entry>
+        <id>urn:uuid:2</id>
+        <link href="https://example.org/2"/>
+    </entry>
+</feed>
+"#;
+
+        let feed = parse_feed_with_base(input, "https://example.com/feeds/atom").unwrap();
+        assert_eq!(
+            Some("https://example.com/blog/"),
+            feed.home_page_url.as_deref()
+        );
+        assert_eq!(
+            Some("https://example.com/blog/feed.xml"),
+            feed.feed_url.as_deref()
+        );
+        assert_eq!(Some("https://example.com/logo.png"), feed.logo.as_deref());
+
+        let mut iter = Iter::with_str_and_base(input, "https://example.com/feeds/atom").unwrap();
+
+        let item = iter.next().unwrap();
+        assert_eq!(
+            Some("https://example.com/blog/2021/post-1"),
+            item.url.as_deref()
+        );
+        assert_eq!(
+            "https://example.com/blog/media/1.mp3",
+            item.attachments[0].url
+        );
+        assert_eq!(
+            Some("https://example.com/about"),
+            item.authors[0].url.as_deref()
+        );
+
+        let item = iter.next().unwrap();
+        assert_eq!(Some("https://example.org/2"), item.url.as_deref());
+
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn resolve_rss_links() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Lorem ipsum</title>
+    <link>/</link>
+    <item>
+      <title>Item 1</title>
+      <link>/posts/1</link>
+      <enclosure url="media/1.mp3" length="1" type="audio/mpeg" />
+    </item>
+  </channel>
+</rss>
+"#;
+
+        let feed = parse_feed_with_base(input, "https://example.com/rss.xml").unwrap();
+        assert_eq!(Some("https://example.com/"), feed.home_page_url.as_deref());
+
+        let mut iter = Iter::with_str_and_base(input, "https://example.com/rss.xml").unwrap();
+        let item = iter.next().unwrap();
+        assert_eq!(Some("https://example.com/posts/1"), item.url.as_deref());
+        assert_eq!("https://example.com/media/1.mp3", item.attachments[0].url);
+    }
+}
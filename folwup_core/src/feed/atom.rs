@@ -1,10 +1,86 @@
-use chrono::{DateTime, Utc};
+use maybe_xml::token::prop::AttributeValue;
 use readfeed::atom;
 
-use super::{Feed, Item, Iter};
+use alloc::vec::Vec;
+
+use crate::xml;
+
+use super::{date, Attachment, Author, Category, Error, Feed, Generator, Item, Iter, Text, TextTy};
+
+#[must_use]
+fn link_href(link: atom::Link<'_>) -> Option<Text<'_>> {
+    link.href().map(|v| Text::xml(v.as_str(), TextTy::Text))
+}
+
+#[must_use]
+fn is_rel(link: atom::Link<'_>, rel: &str) -> bool {
+    link.rel()
+        .is_some_and(|v| v.as_str().trim().eq_ignore_ascii_case(rel))
+}
+
+#[must_use]
+fn link_attachment(link: atom::Link<'_>) -> Option<Attachment<'_>> {
+    Some(Attachment {
+        url: link_href(link)?,
+        mime_type: link.ty().map(|v| Text::xml(v.as_str(), TextTy::Text)),
+        title: link.title().map(|v| Text::xml(v.as_str(), TextTy::Text)),
+        size_in_bytes: link.length().and_then(|v| v.as_str().trim().parse().ok()),
+        duration: None,
+    })
+}
 
 #[must_use]
-pub fn parse_feed(input: &str) -> Option<Feed<'_>> {
+fn category(category: atom::Category<'_>) -> Option<Category<'_>> {
+    Some(Category {
+        term: Text::xml(category.term()?.as_str(), TextTy::Text),
+        scheme: category
+            .scheme()
+            .map(|v| Text::xml(v.as_str(), TextTy::Text)),
+        label: category
+            .label()
+            .map(|v| Text::xml(v.as_str(), TextTy::Text)),
+    })
+}
+
+#[must_use]
+fn person(person_iter: atom::PersonIter<'_>) -> Author<'_> {
+    let mut author = Author::default();
+    for elem in person_iter {
+        match elem {
+            atom::PersonElem::Name(name) => {
+                author.name = author
+                    .name
+                    .or(Some(Text::xml(name.content(), TextTy::Text)));
+            }
+            atom::PersonElem::Email(email) => {
+                author.email = author
+                    .email
+                    .or(Some(Text::xml(email.content(), TextTy::Text)));
+            }
+            atom::PersonElem::Uri(uri) => {
+                author.url = author.url.or(Some(Text::xml(uri.content(), TextTy::Text)));
+            }
+            atom::PersonElem::Unknown(_) | atom::PersonElem::Raw(_) => {}
+        }
+    }
+    author
+}
+
+#[must_use]
+fn text_construct<'a>(content: &'a str, ty: Option<AttributeValue<'a>>) -> Text<'a> {
+    Text::xml(
+        content,
+        ty.map_or(TextTy::Text, |ty| TextTy::from_atom_ty(ty.as_str())),
+    )
+}
+
+/// Parses the feed level metadata from an Atom document.
+///
+/// # Errors
+///
+/// Returns an error if the document does not contain a `feed` element.
+#[allow(clippy::too_many_lines)]
+pub fn parse_feed(input: &str) -> Result<Feed<'_>, Error> {
     fn capture_internal_link<'a>(
         existing_link: atom::Link<'a>,
         new_link: atom::Link<'a>,
@@ -62,21 +138,48 @@ pub fn parse_feed(input: &str) -> Option<Feed<'_>> {
         existing_link
     }
 
-    let xml = atom::Iter::new(input);
+    let xml = atom::Iter::new(xml::trim_truncated(input));
     for item in xml {
         match item {
             atom::Elem::Feed(feed_iter) => {
-                let mut feed = Feed::default();
+                let mut feed = Feed {
+                    language: xml::find_attribute(feed_iter.attributes(), "lang")
+                        .map(|lang| Text::xml(lang, TextTy::Text)),
+                    xml_base: xml::find_attribute(feed_iter.attributes(), "base")
+                        .map(|base| Text::xml(base, TextTy::Text)),
+                    ..Feed::default()
+                };
                 let mut existing_internal_link: Option<atom::Link<'_>> = None;
+                let mut alternate_link: Option<atom::Link<'_>> = None;
                 for feed_elem in feed_iter {
                     match feed_elem {
                         atom::FeedElem::Title(title) => {
-                            feed.title = feed.title.or(Some(title.content()));
+                            feed.title = feed
+                                .title
+                                .or_else(|| Some(text_construct(title.content(), title.ty())));
                         }
                         atom::FeedElem::Subtitle(subtitle) => {
-                            feed.description = feed.description.or(Some(subtitle.content()));
+                            feed.description = feed.description.or_else(|| {
+                                Some(text_construct(subtitle.content(), subtitle.ty()))
+                            });
                         }
                         atom::FeedElem::Link(link) => {
+                            if is_rel(link, "self") {
+                                feed.feed_url = feed.feed_url.or_else(|| link_href(link));
+                            } else if link.rel().is_none() || is_rel(link, "alternate") {
+                                // Prefer an HTML alternate link
+                                let is_html = |l: atom::Link<'_>| {
+                                    l.ty().is_some_and(|ty| {
+                                        ty.as_str().trim().eq_ignore_ascii_case("text/html")
+                                    })
+                                };
+                                if alternate_link
+                                    .is_none_or(|existing| !is_html(existing) && is_html(link))
+                                {
+                                    alternate_link = Some(link);
+                                }
+                            }
+
                             if let Some(existing_link) = existing_internal_link {
                                 existing_internal_link =
                                     Some(capture_internal_link(existing_link, link));
@@ -84,66 +187,124 @@ pub fn parse_feed(input: &str) -> Option<Feed<'_>> {
                                 existing_internal_link = Some(link);
                             }
                         }
-                        atom::FeedElem::Author(_)
-                        | atom::FeedElem::Category(_)
-                        | atom::FeedElem::Contributor(_)
-                        | atom::FeedElem::Generator(_)
-                        | atom::FeedElem::Icon(_)
-                        | atom::FeedElem::Id(_)
-                        | atom::FeedElem::Logo(_)
-                        | atom::FeedElem::Rights(_)
-                        | atom::FeedElem::Updated(_)
+                        atom::FeedElem::Author(person_iter) => {
+                            feed.authors.push(person(person_iter));
+                        }
+                        atom::FeedElem::Contributor(person_iter) => {
+                            feed.contributors.push(person(person_iter));
+                        }
+                        atom::FeedElem::Category(c) => feed.categories.extend(category(c)),
+                        atom::FeedElem::Generator(generator) => {
+                            feed.generator = feed.generator.or_else(|| {
+                                Some(Generator {
+                                    name: Text::xml(generator.content(), TextTy::Text),
+                                    url: generator
+                                        .uri()
+                                        .map(|v| Text::xml(v.as_str(), TextTy::Text)),
+                                    version: generator
+                                        .version()
+                                        .map(|v| Text::xml(v.as_str(), TextTy::Text)),
+                                })
+                            });
+                        }
+                        atom::FeedElem::Icon(icon) => {
+                            feed.icon = feed.icon.or(Some(Text::xml(icon.content(), TextTy::Text)));
+                        }
+                        atom::FeedElem::Logo(logo) => {
+                            feed.logo = feed.logo.or(Some(Text::xml(logo.content(), TextTy::Text)));
+                        }
+                        atom::FeedElem::Rights(rights) => {
+                            feed.rights = feed
+                                .rights
+                                .or_else(|| Some(text_construct(rights.content(), rights.ty())));
+                        }
+                        atom::FeedElem::Updated(updated) => {
+                            feed.modified_at = feed
+                                .modified_at
+                                .or_else(|| date::parse_utc(updated.content()));
+                        }
+                        atom::FeedElem::Id(_)
                         | atom::FeedElem::Entry(_)
                         | atom::FeedElem::Unknown(_)
                         | atom::FeedElem::Raw(_) => {}
                     }
                 }
 
-                return Some(feed);
+                feed.alternate_url = alternate_link.and_then(link_href);
+                // The best internal link is usually the `self` link which is
+                // the feed's URL and not the home page.
+                feed.home_page_url = feed.alternate_url.or_else(|| {
+                    existing_internal_link
+                        .filter(|link| !is_rel(*link, "self"))
+                        .and_then(link_href)
+                });
+
+                return Ok(feed);
             }
             atom::Elem::Unknown(_) | atom::Elem::Raw(_) => {}
         }
     }
 
-    None
+    Err(Error::MissingElement {
+        ty: readfeed::Ty::Atom,
+        name: "feed",
+    })
 }
 
-impl<'a> Iter<atom::FeedIter<'a>> {
-    #[must_use]
-    pub fn with_atom(input: &'a str) -> Option<Self> {
-        let xml = atom::Iter::new(input);
+/// Iterates over the entries of an Atom `feed` element.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EntriesIter<'a> {
+    iter: atom::FeedIter<'a>,
+    /// The feed's authors which apply to entries without authors.
+    authors: Vec<Author<'a>>,
+}
+
+impl<'a> Iter<EntriesIter<'a>> {
+    /// Iterates over the entries in an Atom document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document does not contain a `feed` element.
+    pub fn with_atom(input: &'a str) -> Result<Self, Error> {
+        let xml = atom::Iter::new(xml::trim_truncated(input));
         for item in xml {
             match item {
-                atom::Elem::Feed(feed) => return Some(Self { feed }),
+                atom::Elem::Feed(iter) => {
+                    // Feed authors may appear after the entries.
+                    let authors = iter
+                        .clone()
+                        .filter_map(|elem| match elem {
+                            atom::FeedElem::Author(person_iter) => Some(person(person_iter)),
+                            _ => None,
+                        })
+                        .collect();
+                    return Ok(Self {
+                        feed: EntriesIter { iter, authors },
+                    });
+                }
                 atom::Elem::Unknown(_) | atom::Elem::Raw(_) => {}
             }
         }
 
-        None
+        Err(Error::MissingElement {
+            ty: readfeed::Ty::Atom,
+            name: "feed",
+        })
+    }
+
+    /// The `xml:base` of the `feed` element.
+    #[must_use]
+    pub(super) fn xml_base(&self) -> Option<Text<'a>> {
+        xml::find_attribute(self.feed.iter.attributes(), "base")
+            .map(|base| Text::xml(base, TextTy::Text))
     }
 }
 
-impl<'a> Iterator for Iter<atom::FeedIter<'a>> {
+impl<'a> Iterator for Iter<EntriesIter<'a>> {
     type Item = Item<'a>;
 
     #[allow(clippy::too_many_lines)]
     fn next(&mut self) -> Option<Self::Item> {
-        fn convert_datetime(datetime: &str) -> Option<DateTime<Utc>> {
-            if let Ok(updated) = datetime.parse::<DateTime<Utc>>() {
-                return Some(updated);
-            }
-
-            if let Ok(updated) = DateTime::parse_from_rfc3339(datetime) {
-                return Some(updated.into());
-            }
-
-            if let Ok(updated) = DateTime::parse_from_rfc2822(datetime) {
-                return Some(updated.into());
-            }
-
-            None
-        }
-
         fn capture_internal_link<'a>(
             existing_link: atom::Link<'a>,
             new_link: atom::Link<'a>,
@@ -260,33 +421,49 @@ impl<'a> Iterator for Iter<atom::FeedIter<'a>> {
             existing_link
         }
 
-        for elem in self.feed.by_ref() {
+        for elem in self.feed.iter.by_ref() {
             match elem {
                 atom::FeedElem::Entry(entry_iter) => {
-                    let mut item = Item::default();
+                    let mut item = Item {
+                        xml_base: xml::find_attribute(entry_iter.attributes(), "base")
+                            .map(|base| Text::xml(base, TextTy::Text)),
+                        ..Item::default()
+                    };
+                    let mut source_authors = Vec::new();
                     let mut existing_internal_link: Option<atom::Link<'a>> = None;
                     let mut existing_external_link: Option<atom::Link<'a>> = None;
 
                     for entry_elem in entry_iter {
                         match entry_elem {
                             atom::EntryElem::Content(content) => {
-                                item.content = item.content.or(Some(content.content()));
+                                item.content = item.content.or_else(|| {
+                                    Some(text_construct(content.content(), content.ty()))
+                                });
                             }
                             atom::EntryElem::Id(id) => {
-                                item.id = item.id.or(Some(id.content()));
+                                item.id = item.id.or(Some(Text::xml(id.content(), TextTy::Text)));
                             }
                             atom::EntryElem::Summary(summary) => {
-                                item.summary = item.summary.or(Some(summary.content()));
+                                item.summary = item.summary.or_else(|| {
+                                    Some(text_construct(summary.content(), summary.ty()))
+                                });
                             }
                             atom::EntryElem::Title(title) => {
-                                item.title = item.title.or(Some(title.content()));
+                                item.title = item
+                                    .title
+                                    .or_else(|| Some(text_construct(title.content(), title.ty())));
                             }
                             atom::EntryElem::Updated(updated) => {
                                 item.modified_at = item
                                     .modified_at
-                                    .or_else(|| convert_datetime(updated.content()));
+                                    .or_else(|| date::parse_utc(updated.content()));
                             }
                             atom::EntryElem::Link(link) => {
+                                if is_rel(link, "enclosure") {
+                                    item.attachments.extend(link_attachment(link));
+                                    continue;
+                                }
+
                                 if let Some(existing_link) = existing_internal_link {
                                     existing_internal_link =
                                         Some(capture_internal_link(existing_link, link));
@@ -304,7 +481,7 @@ impl<'a> Iterator for Iter<atom::FeedIter<'a>> {
                             atom::EntryElem::Published(published) => {
                                 item.published_at = item
                                     .published_at
-                                    .or_else(|| convert_datetime(published.content()));
+                                    .or_else(|| date::parse_utc(published.content()));
                             }
                             atom::EntryElem::Source(source_iter) => {
                                 for source_elem in source_iter {
@@ -318,8 +495,10 @@ impl<'a> Iterator for Iter<atom::FeedIter<'a>> {
                                                 existing_external_link = Some(link);
                                             }
                                         }
-                                        atom::SourceElem::Author(_)
-                                        | atom::SourceElem::Category(_)
+                                        atom::SourceElem::Author(person_iter) => {
+                                            source_authors.push(person(person_iter));
+                                        }
+                                        atom::SourceElem::Category(_)
                                         | atom::SourceElem::Contributor(_)
                                         | atom::SourceElem::Generator(_)
                                         | atom::SourceElem::Icon(_)
@@ -334,21 +513,29 @@ impl<'a> Iterator for Iter<atom::FeedIter<'a>> {
                                     }
                                 }
                             }
-                            atom::EntryElem::Author(_)
-                            | atom::EntryElem::Category(_)
-                            | atom::EntryElem::Contributor(_)
-                            | atom::EntryElem::Rights(_)
+                            atom::EntryElem::Author(person_iter) => {
+                                item.authors.push(person(person_iter));
+                            }
+                            atom::EntryElem::Contributor(person_iter) => {
+                                item.contributors.push(person(person_iter));
+                            }
+                            atom::EntryElem::Category(c) => item.categories.extend(category(c)),
+                            atom::EntryElem::Rights(_)
                             | atom::EntryElem::Unknown(_)
                             | atom::EntryElem::Raw(_) => {}
                         }
                     }
 
-                    item.url = existing_internal_link
-                        .and_then(|l| l.href())
-                        .map(|v| v.as_str());
-                    item.external_url = existing_external_link
-                        .and_then(|l| l.href())
-                        .map(|v| v.as_str());
+                    item.url = existing_internal_link.and_then(link_href);
+                    item.external_url = existing_external_link.and_then(link_href);
+
+                    if item.authors.is_empty() {
+                        item.authors = if source_authors.is_empty() {
+                            self.feed.authors.clone()
+                        } else {
+                            source_authors
+                        };
+                    }
 
                     return Some(item);
                 }
@@ -372,3 +559,155 @@ impl<'a> Iterator for Iter<atom::FeedIter<'a>> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+
+    #[test]
+    fn parse_feed_links() {
+        let input = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en-US">
+    <updated>2003-12-13T18:30:02Z</updated>
+    <generator uri="https://example.com/generator" version="1.0">Example</generator>
+    <icon>https://example.com/favicon.ico</icon>
+    <logo>https://example.com/logo.png</logo>
+    <rights type="html">&amp;copy; 2003 Example</rights>
+    <title>Lorem ipsum dolor sit amet.</title>
+    <link rel="self" type="application/atom+xml" href="https://example.com/feed.xml"/>
+    <link rel="alternate" type="application/json" href="https://example.com/feed.json"/>
+    <link rel="alternate" type="text/html" href="https://example.com/"/>
+    <id>urn:uuid:ba9192e8-9e34-4c23-8445-94b67ba316ee</id>
+</feed>
+"#;
+
+        let feed = parse_feed(input).unwrap();
+        assert_eq!(
+            Some("https://example.com/feed.xml"),
+            feed.feed_url.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("https://example.com/"),
+            feed.alternate_url.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("https://example.com/"),
+            feed.home_page_url.map(|v| v.as_raw())
+        );
+        assert_eq!(Some("en-US"), feed.language.map(|v| v.as_raw()));
+        assert_eq!(
+            Some("https://example.com/favicon.ico"),
+            feed.icon.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("https://example.com/logo.png"),
+            feed.logo.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("2003-12-13T18:30:02Z".parse::<DateTime<Utc>>().unwrap()),
+            feed.modified_at
+        );
+        let generator = feed.generator.unwrap();
+        assert_eq!("Example", generator.name.as_raw());
+        assert_eq!(
+            Some("https://example.com/generator"),
+            generator.url.map(|v| v.as_raw())
+        );
+        assert_eq!(Some("1.0"), generator.version.map(|v| v.as_raw()));
+        let rights = feed.rights.unwrap();
+        assert_eq!(TextTy::Html, rights.ty());
+        assert_eq!("&copy; 2003 Example", rights.decode());
+    }
+
+    #[test]
+    fn iter_enclosure() {
+        let input = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+    <title>Lorem ipsum dolor sit amet.</title>
+    <entry>
+        <id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a</id>
+        <link rel="enclosure" type="audio/mpeg" length="1337" href="https://example.com/1.mp3"/>
+        <link href="https://example.com/1"/>
+    </entry>
+</feed>
+"#;
+
+        let mut iter = Iter::with_atom(input).unwrap();
+        let item = iter.next().unwrap();
+        assert_eq!(Some("https://example.com/1"), item.url.map(|v| v.as_raw()));
+        assert_eq!(
+            alloc::vec![Attachment {
+                url: Text::xml("https://example.com/1.mp3", TextTy::Text),
+                mime_type: Some(Text::xml("audio/mpeg", TextTy::Text)),
+                title: None,
+                size_in_bytes: Some(1337),
+                duration: None,
+            }],
+            item.attachments
+        );
+    }
+
+    #[test]
+    fn iter_authors() {
+        let input = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+    <title>Lorem ipsum dolor sit amet.</title>
+    <entry>
+        <id>urn:uuid:1</id>
+        <author><name>John Doe</name><email>john@example.com</email></author>
+        <contributor><name>Alice</name></contributor>
+        <category term="rust" scheme="https://example.com/tags" label="Rust"/>
+    </entry>
+    <entry>
+        <id>urn:uuid:2</id>
+        <source>
+            <author><name>Bob</name><uri>https://example.org/</uri></author>
+        </source>
+    </entry>
+    <entry>
+        <id>urn:uuid:3</id>
+    </entry>
+    <author><name>Jane Doe</name></author>
+</feed>
+"#;
+
+        let feed = parse_feed(input).unwrap();
+        assert_eq!(1, feed.authors.len());
+        assert_eq!(Some("Jane Doe"), feed.authors[0].name.map(|v| v.as_raw()));
+
+        let mut iter = Iter::with_atom(input).unwrap();
+
+        let item = iter.next().unwrap();
+        assert_eq!(1, item.authors.len());
+        assert_eq!(Some("John Doe"), item.authors[0].name.map(|v| v.as_raw()));
+        assert_eq!(
+            Some("john@example.com"),
+            item.authors[0].email.map(|v| v.as_raw())
+        );
+        assert_eq!(1, item.contributors.len());
+        assert_eq!(Some("Alice"), item.contributors[0].name.map(|v| v.as_raw()));
+        assert_eq!(
+            alloc::vec![Category {
+                term: Text::xml("rust", TextTy::Text),
+                scheme: Some(Text::xml("https://example.com/tags", TextTy::Text)),
+                label: Some(Text::xml("Rust", TextTy::Text)),
+            }],
+            item.categories
+        );
+
+        let item = iter.next().unwrap();
+        assert_eq!(1, item.authors.len());
+        assert_eq!(Some("Bob"), item.authors[0].name.map(|v| v.as_raw()));
+        assert_eq!(
+            Some("https://example.org/"),
+            item.authors[0].url.map(|v| v.as_raw())
+        );
+
+        let item = iter.next().unwrap();
+        assert_eq!(feed.authors, item.authors);
+
+        assert_eq!(None, iter.next());
+    }
+}
@@ -0,0 +1,769 @@
+//! Lenient parsing of the timestamps found in feeds.
+//!
+//! Atom and JSON Feed use [RFC 3339][rfc3339] and RSS uses [RFC
+//! 822][rfc822], but many publishers deviate from the specifications. The
+//! parser accepts common variations and reports the [`Heuristics`] which were
+//! needed to interpret the value.
+//!
+//! [rfc3339]: https://datatracker.ietf.org/doc/html/rfc3339
+//! [rfc822]: https://datatracker.ietf.org/doc/html/rfc822#section-5
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+/// A set of assumptions made while parsing a timestamp.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Heuristics(u16);
+
+impl Heuristics {
+    /// The value conformed to RFC 3339 or RFC 2822.
+    pub const NONE: Self = Self(0);
+    /// A time zone abbreviation which is not defined by RFC 2822 such as
+    /// `CEST` was converted into an offset.
+    pub const ZONE_ABBREVIATION: Self = Self(1 << 0);
+    /// The time zone was missing and UTC was assumed.
+    pub const MISSING_ZONE: Self = Self(1 << 1);
+    /// The seconds were missing and zero was assumed.
+    pub const MISSING_SECONDS: Self = Self(1 << 2);
+    /// The time was missing and midnight was assumed.
+    pub const MISSING_TIME: Self = Self(1 << 3);
+    /// An ISO 8601 form which is not valid RFC 3339 such as a space between the
+    /// date and time, the basic format without separators, or an offset
+    /// without a colon.
+    pub const ISO_8601_VARIANT: Self = Self(1 << 4);
+    /// A two digit year was expanded in a value which is otherwise not valid
+    /// RFC 2822.
+    pub const TWO_DIGIT_YEAR: Self = Self(1 << 5);
+    /// A non-English month or day of the week name was used.
+    pub const LOCALIZED_NAMES: Self = Self(1 << 6);
+    /// The value was in a different order than RFC 2822 such as `Jan 5, 2023`.
+    pub const REORDERED: Self = Self(1 << 7);
+
+    #[inline]
+    #[must_use]
+    pub const fn bits(self) -> u16 {
+        self.0
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    #[inline]
+    fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+/// A parsed timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Parsed {
+    pub datetime: DateTime<Utc>,
+    pub heuristics: Heuristics,
+}
+
+/// Parses a timestamp.
+///
+/// Returns `None` if the value could not be interpreted.
+#[must_use]
+pub fn parse(value: &str) -> Option<Parsed> {
+    let value = value.trim();
+
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Some(Parsed {
+            datetime: datetime.with_timezone(&Utc),
+            heuristics: Heuristics::NONE,
+        });
+    }
+
+    if let Ok(datetime) = DateTime::parse_from_rfc2822(value) {
+        return Some(Parsed {
+            datetime: datetime.with_timezone(&Utc),
+            heuristics: Heuristics::NONE,
+        });
+    }
+
+    if value.len() >= 8 && value.as_bytes()[..4].iter().all(u8::is_ascii_digit) {
+        parse_iso_8601(value)
+    } else {
+        parse_rfc_2822(value)
+    }
+}
+
+/// Parses a timestamp and discards the applied heuristics.
+#[inline]
+#[must_use]
+pub fn parse_utc(value: &str) -> Option<DateTime<Utc>> {
+    parse(value).map(|parsed| parsed.datetime)
+}
+
+/// Parses an unsigned number with exactly `len` digits from the start of the
+/// value.
+#[must_use]
+fn take_digits(value: &str, len: usize) -> Option<(u32, &str)> {
+    let digits = value.get(..len)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((digits.parse().ok()?, &value[len..]))
+}
+
+#[must_use]
+fn resolve(
+    date: NaiveDate,
+    time: NaiveTime,
+    offset_secs: Option<i32>,
+    mut heuristics: Heuristics,
+) -> Option<Parsed> {
+    let offset_secs = offset_secs.unwrap_or_else(|| {
+        heuristics.insert(Heuristics::MISSING_ZONE);
+        0
+    });
+    let offset = FixedOffset::east_opt(offset_secs)?;
+    let datetime = offset
+        .from_local_datetime(&NaiveDateTime::new(date, time))
+        .single()?;
+
+    Some(Parsed {
+        datetime: datetime.with_timezone(&Utc),
+        heuristics,
+    })
+}
+
+/// Parses a numeric offset such as `+07:00`, `-0700` or `+07`.
+#[must_use]
+fn parse_numeric_offset(value: &str, heuristics: &mut Heuristics) -> Option<i32> {
+    let (sign, rest) = match value.as_bytes().first()? {
+        b'+' => (1, &value[1..]),
+        b'-' => (-1, &value[1..]),
+        _ => return None,
+    };
+
+    let (hours, rest) = take_digits(rest, 2)?;
+    let minutes = match rest.len() {
+        0 => {
+            heuristics.insert(Heuristics::ISO_8601_VARIANT);
+            0
+        }
+        3 => take_digits(rest.strip_prefix(':')?, 2)?.0,
+        2 => take_digits(rest, 2)?.0,
+        _ => return None,
+    };
+    if hours > 23 || minutes > 59 {
+        return None;
+    }
+
+    let secs = i32::try_from(hours * 3600 + minutes * 60).ok()?;
+    Some(sign * secs)
+}
+
+/// Converts a time zone name into an offset in seconds.
+///
+/// Besides the universal time names, common North American, European, Asian
+/// and Australian abbreviations are recognized. Some abbreviations are
+/// ambiguous (e.g. `CST`, `AST` and `BST`), so the North American and
+/// British meanings are assumed.
+#[must_use]
+fn zone_offset(name: &str, heuristics: &mut Heuristics) -> Option<i32> {
+    const UNIVERSAL: [&str; 4] = ["GMT", "UT", "UTC", "Z"];
+    const ABBREVIATIONS: [(&str, i32); 24] = [
+        ("EST", -5),
+        ("EDT", -4),
+        ("CST", -6),
+        ("CDT", -5),
+        ("MST", -7),
+        ("MDT", -6),
+        ("PST", -8),
+        ("PDT", -7),
+        ("AKST", -9),
+        ("AKDT", -8),
+        ("HST", -10),
+        ("AST", -4),
+        ("ADT", -3),
+        ("WET", 0),
+        ("WEST", 1),
+        ("BST", 1),
+        ("CET", 1),
+        ("CEST", 2),
+        ("EET", 2),
+        ("EEST", 3),
+        ("MSK", 3),
+        ("JST", 9),
+        ("AEST", 10),
+        ("AEDT", 11),
+    ];
+
+    if UNIVERSAL.iter().any(|v| name.eq_ignore_ascii_case(v)) {
+        return Some(0);
+    }
+
+    let (_, hours) = ABBREVIATIONS
+        .iter()
+        .find(|(abbr, _)| name.eq_ignore_ascii_case(abbr))?;
+    heuristics.insert(Heuristics::ZONE_ABBREVIATION);
+    Some(hours * 3600)
+}
+
+/// Parses a time such as `10:00`, `10:00:00` or `10:00:00.123`.
+#[must_use]
+fn parse_extended_time(value: &str, heuristics: &mut Heuristics) -> Option<NaiveTime> {
+    let (hours, rest) = take_digits(value, 2).or_else(|| take_digits(value, 1))?;
+    let (minutes, rest) = take_digits(rest.strip_prefix(':')?, 2)?;
+    let (seconds, rest) = if let Some(rest) = rest.strip_prefix(':') {
+        take_digits(rest, 2)?
+    } else {
+        heuristics.insert(Heuristics::MISSING_SECONDS);
+        (0, rest)
+    };
+    let nanos = parse_fraction(rest)?;
+
+    NaiveTime::from_hms_nano_opt(hours, minutes, seconds, nanos)
+}
+
+/// Parses optional fractional seconds such as `.123`.
+#[must_use]
+fn parse_fraction(value: &str) -> Option<u32> {
+    let Some(fraction) = value.strip_prefix(['.', ',']) else {
+        return value.is_empty().then_some(0);
+    };
+    if fraction.is_empty() || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let mut nanos = 0;
+    for (idx, digit) in fraction.bytes().take(9).enumerate() {
+        nanos += u32::from(digit - b'0') * 10u32.pow(8 - u32::try_from(idx).ok()?);
+    }
+    Some(nanos)
+}
+
+/// Parses ISO 8601 values which are not valid RFC 3339.
+#[must_use]
+fn parse_iso_8601(value: &str) -> Option<Parsed> {
+    let mut heuristics = Heuristics::NONE;
+
+    let (year, rest) = take_digits(value, 4)?;
+    let (month, day, rest) = if let Some(rest) = rest.strip_prefix('-') {
+        let (month, rest) = take_digits(rest, 2)?;
+        let (day, rest) = take_digits(rest.strip_prefix('-')?, 2)?;
+        (month, day, rest)
+    } else {
+        heuristics.insert(Heuristics::ISO_8601_VARIANT);
+        let (month, rest) = take_digits(rest, 2)?;
+        let (day, rest) = take_digits(rest, 2)?;
+        (month, day, rest)
+    };
+    let date = NaiveDate::from_ymd_opt(i32::try_from(year).ok()?, month, day)?;
+
+    let rest = rest.trim_start();
+    if rest.is_empty() {
+        heuristics.insert(Heuristics::MISSING_TIME);
+        return resolve(date, NaiveTime::MIN, None, heuristics);
+    }
+
+    let rest = if let Some(rest) = rest.strip_prefix(['T', 't']) {
+        rest
+    } else {
+        heuristics.insert(Heuristics::ISO_8601_VARIANT);
+        rest
+    };
+
+    // The time ends where the zone begins.
+    let zone_start = rest
+        .find(|c: char| matches!(c, '+' | '-' | 'Z' | 'z' | ' ') || c.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+    let (time, zone) = rest.split_at(zone_start);
+
+    let time = if time.contains(':') {
+        parse_extended_time(time, &mut heuristics)?
+    } else {
+        heuristics.insert(Heuristics::ISO_8601_VARIANT);
+        let (hours, time) = take_digits(time, 2)?;
+        let (minutes, time) = take_digits(time, 2)?;
+        let (seconds, time) = take_digits(time, 2).unwrap_or_else(|| {
+            heuristics.insert(Heuristics::MISSING_SECONDS);
+            (0, time)
+        });
+        NaiveTime::from_hms_nano_opt(hours, minutes, seconds, parse_fraction(time)?)?
+    };
+
+    let zone = zone.trim();
+    let offset = if zone.is_empty() {
+        None
+    } else if zone.starts_with(['+', '-']) {
+        let offset = parse_numeric_offset(zone, &mut heuristics)?;
+        if !zone.contains(':') {
+            heuristics.insert(Heuristics::ISO_8601_VARIANT);
+        }
+        Some(offset)
+    } else {
+        if !zone.eq_ignore_ascii_case("Z") {
+            heuristics.insert(Heuristics::ISO_8601_VARIANT);
+        }
+        Some(zone_offset(zone, &mut heuristics)?)
+    };
+
+    resolve(date, time, offset, heuristics)
+}
+
+/// Compares two strings for equality ignoring case, including non-ASCII
+/// letters.
+#[must_use]
+fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .eq(b.chars().flat_map(char::to_lowercase))
+}
+
+/// Returns true if `name` is the full name or an abbreviation (at least three
+/// letters) of the lowercase `full_name`.
+#[must_use]
+fn is_abbreviation_of(name: &str, full_name: &str) -> bool {
+    let name = name.trim_end_matches('.');
+    if name.chars().count() < 3 {
+        return false;
+    }
+
+    let prefix_len = full_name
+        .char_indices()
+        .nth(name.chars().count())
+        .map_or(full_name.len(), |(idx, _)| idx);
+    eq_ignore_case(name, &full_name[..prefix_len])
+}
+
+const ENGLISH_MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Month names in French, German, Spanish, Italian, Dutch and Portuguese.
+const LOCALIZED_MONTHS: [[&str; 12]; 6] = [
+    [
+        "janvier",
+        "février",
+        "mars",
+        "avril",
+        "mai",
+        "juin",
+        "juillet",
+        "août",
+        "septembre",
+        "octobre",
+        "novembre",
+        "décembre",
+    ],
+    [
+        "januar",
+        "februar",
+        "märz",
+        "april",
+        "mai",
+        "juni",
+        "juli",
+        "august",
+        "september",
+        "oktober",
+        "november",
+        "dezember",
+    ],
+    [
+        "enero",
+        "febrero",
+        "marzo",
+        "abril",
+        "mayo",
+        "junio",
+        "julio",
+        "agosto",
+        "septiembre",
+        "octubre",
+        "noviembre",
+        "diciembre",
+    ],
+    [
+        "gennaio",
+        "febbraio",
+        "marzo",
+        "aprile",
+        "maggio",
+        "giugno",
+        "luglio",
+        "agosto",
+        "settembre",
+        "ottobre",
+        "novembre",
+        "dicembre",
+    ],
+    [
+        "januari",
+        "februari",
+        "maart",
+        "april",
+        "mei",
+        "juni",
+        "juli",
+        "augustus",
+        "september",
+        "oktober",
+        "november",
+        "december",
+    ],
+    [
+        "janeiro",
+        "fevereiro",
+        "março",
+        "abril",
+        "maio",
+        "junho",
+        "julho",
+        "agosto",
+        "setembro",
+        "outubro",
+        "novembro",
+        "dezembro",
+    ],
+];
+
+const ENGLISH_DAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// Returns the month number and whether the name is English.
+#[must_use]
+fn month(name: &str) -> Option<(u32, bool)> {
+    let find = |months: &[&str; 12]| {
+        months
+            .iter()
+            .position(|month| is_abbreviation_of(name, month))
+            .and_then(|idx| u32::try_from(idx + 1).ok())
+    };
+
+    if let Some(month) = find(&ENGLISH_MONTHS) {
+        return Some((month, true));
+    }
+    LOCALIZED_MONTHS
+        .iter()
+        .find_map(find)
+        .map(|month| (month, false))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    Number(&'a str),
+    Word(&'a str),
+    Time(&'a str),
+    Offset(&'a str),
+}
+
+/// Parses RFC 2822 values and similar forms such as `Jan 5, 2023 10:00`.
+#[must_use]
+#[allow(clippy::too_many_lines)]
+fn parse_rfc_2822(value: &str) -> Option<Parsed> {
+    let mut heuristics = Heuristics::NONE;
+
+    let mut value = value;
+    if let Some((first, rest)) = value.split_once(',') {
+        let first = first.trim().trim_end_matches('.');
+        if !first.is_empty() && first.chars().all(char::is_alphabetic) {
+            // A day of the week which is otherwise ignored.
+            if !ENGLISH_DAYS
+                .iter()
+                .any(|day| is_abbreviation_of(first, day))
+            {
+                heuristics.insert(Heuristics::LOCALIZED_NAMES);
+            }
+            value = rest;
+        }
+    }
+
+    let mut day = None;
+    let mut month_num = None;
+    let mut year = None;
+    let mut time = None;
+    let mut offset = None;
+
+    let tokens = value
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            if token.contains(':') && token.starts_with(|c: char| c.is_ascii_digit()) {
+                Token::Time(token)
+            } else if token.starts_with(['+', '-']) {
+                Token::Offset(token)
+            } else if token.bytes().all(|b| b.is_ascii_digit()) {
+                Token::Number(token)
+            } else {
+                Token::Word(token.trim_end_matches('.'))
+            }
+        });
+
+    for (idx, token) in tokens.enumerate() {
+        match token {
+            Token::Number(number) => {
+                if day.is_none() && number.len() <= 2 {
+                    day = Some(number.parse::<u32>().ok()?);
+                } else if year.is_none() {
+                    let value = number.parse::<i32>().ok()?;
+                    year = Some(match number.len() {
+                        2 => {
+                            // RFC 2822 section 4.3
+                            heuristics.insert(Heuristics::TWO_DIGIT_YEAR);
+                            if value < 50 {
+                                2000 + value
+                            } else {
+                                1900 + value
+                            }
+                        }
+                        3 => 1900 + value,
+                        _ => value,
+                    });
+                } else {
+                    return None;
+                }
+            }
+            Token::Time(value) => {
+                if time.is_some() {
+                    return None;
+                }
+                time = Some(parse_extended_time(value, &mut heuristics)?);
+            }
+            Token::Offset(value) => {
+                if offset.is_some() {
+                    return None;
+                }
+                let parsed = parse_numeric_offset(value, &mut heuristics)?;
+                if value.contains(':') {
+                    heuristics.insert(Heuristics::ISO_8601_VARIANT);
+                }
+                offset = Some(parsed);
+            }
+            Token::Word(word) => {
+                if time.is_some() && offset.is_none() {
+                    if let Some(zone) = zone_offset(word, &mut heuristics) {
+                        offset = Some(zone);
+                        continue;
+                    }
+                }
+
+                if month_num.is_none() {
+                    if let Some((month, is_english)) = month(word) {
+                        if day.is_none() {
+                            // `Jan 5, 2023` or `Tue Jan 5 2023`
+                            heuristics.insert(Heuristics::REORDERED);
+                        }
+                        if !is_english {
+                            heuristics.insert(Heuristics::LOCALIZED_NAMES);
+                        }
+                        month_num = Some(month);
+                        continue;
+                    }
+                }
+
+                if day.is_none() && month_num.is_none() && idx == 0 {
+                    // A day of the week without a comma such as `Tue Jan 5`.
+                    if !ENGLISH_DAYS.iter().any(|day| is_abbreviation_of(word, day)) {
+                        heuristics.insert(Heuristics::LOCALIZED_NAMES);
+                    }
+                    continue;
+                }
+
+                return None;
+            }
+        }
+    }
+
+    let date = NaiveDate::from_ymd_opt(year?, month_num?, day?)?;
+    let time = time.unwrap_or_else(|| {
+        heuristics.insert(Heuristics::MISSING_TIME);
+        NaiveTime::MIN
+    });
+
+    resolve(date, time, offset, heuristics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    #[track_caller]
+    fn assert_parsed(value: &str, expected: &str, heuristics: Heuristics) {
+        assert_eq!(
+            Some(Parsed {
+                datetime: utc(expected),
+                heuristics,
+            }),
+            parse(value),
+            "{value}"
+        );
+    }
+
+    #[test]
+    fn rfc_3339() {
+        assert_parsed(
+            "2003-12-13T18:30:02Z",
+            "2003-12-13T18:30:02Z",
+            Heuristics::NONE,
+        );
+        assert_parsed(
+            " 2003-12-13T18:30:02.25+01:00 ",
+            "2003-12-13T17:30:02.25Z",
+            Heuristics::NONE,
+        );
+    }
+
+    #[test]
+    fn rfc_2822() {
+        assert_parsed(
+            "Tue, 10 Jun 2003 04:00:00 GMT",
+            "2003-06-10T04:00:00Z",
+            Heuristics::NONE,
+        );
+        assert_parsed(
+            "Tue, 10 Jun 2003 04:00:00 +0000 (UTC)",
+            "2003-06-10T04:00:00Z",
+            Heuristics::NONE,
+        );
+        assert_parsed(
+            "10 Jun 2003 04:00:00 -0700",
+            "2003-06-10T11:00:00Z",
+            Heuristics::NONE,
+        );
+        assert_parsed(
+            "Tue, 10 Jun 2003 04:00:00 PDT",
+            "2003-06-10T11:00:00Z",
+            Heuristics::NONE,
+        );
+        assert_parsed(
+            "Tue, 10 Jun 2003 04:00:00 CEST",
+            "2003-06-10T02:00:00Z",
+            Heuristics::ZONE_ABBREVIATION,
+        );
+        assert_parsed(
+            "Mon, 5 Jan 2023 10:00 EST",
+            "2023-01-05T15:00:00Z",
+            Heuristics::ZONE_ABBREVIATION.union(Heuristics::MISSING_SECONDS),
+        );
+        assert_parsed(
+            "Tue, 10 Jun 03 04:00:00 GMT",
+            "2003-06-10T04:00:00Z",
+            Heuristics::NONE,
+        );
+        assert_parsed(
+            "Tue, 10 Jun 03 04:00:00 CEST",
+            "2003-06-10T02:00:00Z",
+            Heuristics::ZONE_ABBREVIATION.union(Heuristics::TWO_DIGIT_YEAR),
+        );
+        assert_parsed(
+            "Tuesday, 10 June 2003 04:00:00",
+            "2003-06-10T04:00:00Z",
+            Heuristics::MISSING_ZONE,
+        );
+        assert_parsed(
+            "Jan 5, 2023 10:00:00 +0100",
+            "2023-01-05T09:00:00Z",
+            Heuristics::REORDERED,
+        );
+    }
+
+    #[test]
+    fn localized() {
+        assert_parsed(
+            "Di, 10 Jun 2003 04:00:00 +0200",
+            "2003-06-10T02:00:00Z",
+            Heuristics::LOCALIZED_NAMES,
+        );
+        assert_parsed(
+            "mar., 10 juin 2003 04:00:00 +0200",
+            "2003-06-10T02:00:00Z",
+            Heuristics::LOCALIZED_NAMES,
+        );
+        assert_parsed(
+            "10 März 2003 04:00:00 CET",
+            "2003-03-10T03:00:00Z",
+            Heuristics::LOCALIZED_NAMES.union(Heuristics::ZONE_ABBREVIATION),
+        );
+        assert_parsed(
+            "10 dic 2003 04:00:00 +0000",
+            "2003-12-10T04:00:00Z",
+            Heuristics::LOCALIZED_NAMES,
+        );
+    }
+
+    #[test]
+    fn iso_8601() {
+        assert_parsed(
+            "2023-01-05 10:00",
+            "2023-01-05T10:00:00Z",
+            Heuristics::ISO_8601_VARIANT
+                .union(Heuristics::MISSING_SECONDS)
+                .union(Heuristics::MISSING_ZONE),
+        );
+        assert_parsed(
+            "2023-01-05T10:00:00",
+            "2023-01-05T10:00:00Z",
+            Heuristics::MISSING_ZONE,
+        );
+        assert_parsed(
+            "2023-01-05T10:00:00+0100",
+            "2023-01-05T09:00:00Z",
+            Heuristics::ISO_8601_VARIANT,
+        );
+        assert_parsed(
+            "20230105T100000Z",
+            "2023-01-05T10:00:00Z",
+            Heuristics::ISO_8601_VARIANT,
+        );
+        assert_parsed(
+            "2023-01-05",
+            "2023-01-05T00:00:00Z",
+            Heuristics::MISSING_TIME.union(Heuristics::MISSING_ZONE),
+        );
+        assert_parsed(
+            "2023-01-05 10:00:00 PST",
+            "2023-01-05T18:00:00Z",
+            Heuristics::ISO_8601_VARIANT.union(Heuristics::ZONE_ABBREVIATION),
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(None, parse(""));
+        assert_eq!(None, parse("yesterday"));
+        assert_eq!(None, parse("2023-02-30T10:00:00Z"));
+        assert_eq!(None, parse("Tue, 10 Foo 2003 04:00:00 GMT"));
+        assert_eq!(None, parse("Tue, 10 Jun 2003 25:00:00 GMT"));
+    }
+}
@@ -0,0 +1,563 @@
+use core::time::Duration;
+
+use alloc::vec::Vec;
+
+use super::{date, Attachment, Author, Category, Error, Feed, Item, Iter, Text, TextTy};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Value<'a> {
+    Null,
+    Bool(bool),
+    Number(&'a str),
+    /// String content without the surrounding quotes. Escape sequences are
+    /// not decoded.
+    String(&'a str),
+    Array(ArrayIter<'a>),
+    Object(ObjectIter<'a>),
+}
+
+impl<'a> Value<'a> {
+    /// Returns the raw content for scalar values which can be represented as a
+    /// string.
+    #[must_use]
+    fn as_str(&self) -> Option<&'a str> {
+        match self {
+            Value::String(value) | Value::Number(value) => Some(value),
+            Value::Null | Value::Bool(_) | Value::Array(_) | Value::Object(_) => None,
+        }
+    }
+
+    #[must_use]
+    fn as_text(&self, ty: TextTy) -> Option<Text<'a>> {
+        self.as_str().map(|value| Text::json(value, ty))
+    }
+}
+
+#[must_use]
+fn skip_whitespace(bytes: &[u8], mut pos: usize) -> usize {
+    while pos < bytes.len() && matches!(bytes[pos], b' ' | b'\t' | b'\n' | b'\r') {
+        pos += 1;
+    }
+    pos
+}
+
+/// Returns the index after the closing quote of the string which begins at
+/// `pos`.
+#[must_use]
+fn scan_string(bytes: &[u8], pos: usize) -> Option<usize> {
+    debug_assert_eq!(bytes[pos], b'"');
+
+    let mut pos = pos + 1;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'"' => return Some(pos + 1),
+            b'\\' => pos += 2,
+            _ => pos += 1,
+        }
+    }
+
+    None
+}
+
+/// Returns the index after the closing bracket of the array or object which
+/// begins at `pos`.
+#[must_use]
+fn scan_container(bytes: &[u8], pos: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut pos = pos;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'"' => {
+                pos = scan_string(bytes, pos)?;
+                continue;
+            }
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos + 1);
+                }
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+
+    None
+}
+
+#[must_use]
+fn scan_value(input: &str, pos: usize) -> Option<(Value<'_>, usize)> {
+    let bytes = input.as_bytes();
+    match *bytes.get(pos)? {
+        b'"' => {
+            let end = scan_string(bytes, pos)?;
+            Some((Value::String(&input[pos + 1..end - 1]), end))
+        }
+        b'{' => {
+            let end = scan_container(bytes, pos)?;
+            Some((Value::Object(ObjectIter::new(&input[pos..end])), end))
+        }
+        b'[' => {
+            let end = scan_container(bytes, pos)?;
+            Some((Value::Array(ArrayIter::new(&input[pos..end])), end))
+        }
+        b't' if input[pos..].starts_with("true") => Some((Value::Bool(true), pos + 4)),
+        b'f' if input[pos..].starts_with("false") => Some((Value::Bool(false), pos + 5)),
+        b'n' if input[pos..].starts_with("null") => Some((Value::Null, pos + 4)),
+        b'-' | b'0'..=b'9' => {
+            let end = bytes[pos..]
+                .iter()
+                .position(|b| !matches!(b, b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'))
+                .map_or(bytes.len(), |len| pos + len);
+            Some((Value::Number(&input[pos..end]), end))
+        }
+        _ => None,
+    }
+}
+
+/// Iterates over the members of a JSON object.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ObjectIter<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> ObjectIter<'a> {
+    /// The input should begin with `{` and end with `}`.
+    #[must_use]
+    const fn new(input: &'a str) -> Self {
+        Self { input, pos: 1 }
+    }
+}
+
+impl<'a> Iterator for ObjectIter<'a> {
+    type Item = (&'a str, Value<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.input.as_bytes();
+
+        let mut pos = skip_whitespace(bytes, self.pos);
+        if bytes.get(pos) == Some(&b',') {
+            pos = skip_whitespace(bytes, pos + 1);
+        }
+
+        let member = (|| {
+            if bytes.get(pos) != Some(&b'"') {
+                return None;
+            }
+            let key_end = scan_string(bytes, pos)?;
+            let key = &self.input[pos + 1..key_end - 1];
+
+            let colon = skip_whitespace(bytes, key_end);
+            if bytes.get(colon) != Some(&b':') {
+                return None;
+            }
+
+            let value_begin = skip_whitespace(bytes, colon + 1);
+            let (value, end) = scan_value(self.input, value_begin)?;
+            Some(((key, value), end))
+        })();
+
+        if let Some((member, end)) = member {
+            self.pos = end;
+            Some(member)
+        } else {
+            self.pos = bytes.len();
+            None
+        }
+    }
+}
+
+/// Iterates over the values of a JSON array.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ArrayIter<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> ArrayIter<'a> {
+    /// The input should begin with `[` and end with `]`.
+    #[must_use]
+    const fn new(input: &'a str) -> Self {
+        Self { input, pos: 1 }
+    }
+}
+
+impl<'a> Iterator for ArrayIter<'a> {
+    type Item = Value<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.input.as_bytes();
+
+        let mut pos = skip_whitespace(bytes, self.pos);
+        if bytes.get(pos) == Some(&b',') {
+            pos = skip_whitespace(bytes, pos + 1);
+        }
+
+        if let Some((value, end)) = scan_value(self.input, pos) {
+            self.pos = end;
+            Some(value)
+        } else {
+            self.pos = bytes.len();
+            None
+        }
+    }
+}
+
+#[must_use]
+fn parse_author(object: ObjectIter<'_>) -> Author<'_> {
+    let mut author = Author::default();
+    for (key, value) in object {
+        match key {
+            "name" => author.name = author.name.or(value.as_text(TextTy::Text)),
+            "url" => author.url = author.url.or(value.as_text(TextTy::Text)),
+            "avatar" => author.avatar = author.avatar.or(value.as_text(TextTy::Text)),
+            _ => {}
+        }
+    }
+    author
+}
+
+/// Parses the value of an `authors` array or a JSON Feed 1.0 `author` object.
+fn parse_authors<'a>(authors: &mut Vec<Author<'a>>, key: &str, value: Value<'a>) {
+    match (key, value) {
+        ("authors", Value::Array(iter)) => {
+            // The deprecated `author` is replaced by `authors`.
+            authors.clear();
+            authors.extend(iter.filter_map(|value| match value {
+                Value::Object(object) => Some(parse_author(object)).filter(|a| !a.is_empty()),
+                _ => None,
+            }));
+        }
+        ("author", Value::Object(object)) if authors.is_empty() => {
+            authors.extend(Some(parse_author(object)).filter(|a| !a.is_empty()));
+        }
+        _ => {}
+    }
+}
+
+#[must_use]
+fn parse_attachment(object: ObjectIter<'_>) -> Option<Attachment<'_>> {
+    let mut url = None;
+    let mut attachment = Attachment::default();
+    for (key, value) in object {
+        match key {
+            "url" => url = url.or(value.as_text(TextTy::Text)),
+            "mime_type" => {
+                attachment.mime_type = attachment.mime_type.or(value.as_text(TextTy::Text));
+            }
+            "title" => attachment.title = attachment.title.or(value.as_text(TextTy::Text)),
+            "size_in_bytes" => {
+                attachment.size_in_bytes = attachment
+                    .size_in_bytes
+                    .or_else(|| value.as_str().and_then(|v| v.parse().ok()));
+            }
+            "duration_in_seconds" => {
+                attachment.duration = attachment.duration.or_else(|| {
+                    value
+                        .as_str()
+                        .and_then(|v| v.parse::<f64>().ok())
+                        .and_then(|v| Duration::try_from_secs_f64(v).ok())
+                });
+            }
+            _ => {}
+        }
+    }
+
+    attachment.url = url?;
+    Some(attachment)
+}
+
+fn parse_document(input: &str) -> Result<ObjectIter<'_>, Error> {
+    let pos = skip_whitespace(input.as_bytes(), 0);
+    match scan_value(input, pos) {
+        Some((Value::Object(object), _)) => Ok(object),
+        _ => Err(Error::Malformed(readfeed::Ty::Json)),
+    }
+}
+
+/// Parses the top-level metadata from a JSON Feed document.
+///
+/// # Errors
+///
+/// Returns an error if the document is not a JSON object.
+pub fn parse_feed(input: &str) -> Result<Feed<'_>, Error> {
+    let object = parse_document(input)?;
+
+    let mut feed = Feed::default();
+    for (key, value) in object {
+        match key {
+            "title" => feed.title = feed.title.or(value.as_text(TextTy::Text)),
+            "description" => {
+                feed.description = feed.description.or(value.as_text(TextTy::Text));
+            }
+            "home_page_url" => {
+                feed.home_page_url = feed.home_page_url.or(value.as_text(TextTy::Text));
+                feed.alternate_url = feed.alternate_url.or(value.as_text(TextTy::Text));
+            }
+            "feed_url" => feed.feed_url = feed.feed_url.or(value.as_text(TextTy::Text)),
+            "authors" | "author" => parse_authors(&mut feed.authors, key, value),
+            "language" => feed.language = feed.language.or(value.as_text(TextTy::Text)),
+            "icon" => feed.logo = feed.logo.or(value.as_text(TextTy::Text)),
+            "favicon" => feed.icon = feed.icon.or(value.as_text(TextTy::Text)),
+            _ => {}
+        }
+    }
+
+    Ok(feed)
+}
+
+/// Iterates over the values in a [JSON Feed][json_feed]'s `items` array.
+///
+/// [json_feed]: https://www.jsonfeed.org/version/1.1/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ItemsIter<'a> {
+    iter: ArrayIter<'a>,
+    /// The feed's authors which apply to items without authors.
+    authors: Vec<Author<'a>>,
+}
+
+impl<'a> Iter<ItemsIter<'a>> {
+    /// Iterates over the items in a JSON Feed document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not a JSON object.
+    pub fn with_json(input: &'a str) -> Result<Self, Error> {
+        let object = parse_document(input)?;
+
+        let mut iter = None;
+        let mut authors = Vec::new();
+        for (key, value) in object {
+            match (key, value) {
+                ("items", Value::Array(items)) => iter = iter.or(Some(items)),
+                (key, value) => parse_authors(&mut authors, key, value),
+            }
+        }
+
+        Ok(Self {
+            feed: ItemsIter {
+                iter: iter.unwrap_or(ArrayIter::new("[]")),
+                authors,
+            },
+        })
+    }
+}
+
+impl<'a> Iterator for Iter<ItemsIter<'a>> {
+    type Item = Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for value in self.feed.iter.by_ref() {
+            let Value::Object(object) = value else {
+                continue;
+            };
+
+            let mut item = Item::default();
+            let mut content_text = None;
+
+            for (key, value) in object {
+                match key {
+                    "id" => item.id = item.id.or(value.as_text(TextTy::Text)),
+                    "url" => item.url = item.url.or(value.as_text(TextTy::Text)),
+                    "external_url" => {
+                        item.external_url = item.external_url.or(value.as_text(TextTy::Text));
+                    }
+                    "title" => item.title = item.title.or(value.as_text(TextTy::Text)),
+                    "content_html" => {
+                        item.content = item.content.or(value.as_text(TextTy::Html));
+                    }
+                    "content_text" => {
+                        content_text = content_text.or(value.as_text(TextTy::Text));
+                    }
+                    "summary" => item.summary = item.summary.or(value.as_text(TextTy::Text)),
+                    "date_published" => {
+                        item.published_at = item
+                            .published_at
+                            .or_else(|| value.as_str().and_then(date::parse_utc));
+                    }
+                    "date_modified" => {
+                        item.modified_at = item
+                            .modified_at
+                            .or_else(|| value.as_str().and_then(date::parse_utc));
+                    }
+                    "authors" | "author" => parse_authors(&mut item.authors, key, value),
+                    "tags" => {
+                        let Value::Array(tags) = value else {
+                            continue;
+                        };
+                        item.categories.extend(tags.filter_map(|tag| {
+                            Some(Category {
+                                term: tag.as_text(TextTy::Text)?,
+                                ..Category::default()
+                            })
+                        }));
+                    }
+                    "attachments" => {
+                        let Value::Array(attachments) = value else {
+                            continue;
+                        };
+                        item.attachments.extend(attachments.filter_map(
+                            |attachment| match attachment {
+                                Value::Object(object) => parse_attachment(object),
+                                _ => None,
+                            },
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+
+            item.content = item.content.or(content_text);
+            if item.authors.is_empty() {
+                item.authors.clone_from(&self.feed.authors);
+            }
+
+            return Some(item);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+
+    const INPUT: &str = r#"{
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": "Lorem ipsum dolor sit amet.",
+        "home_page_url": "https://example.com/",
+        "feed_url": "https://example.com/feed.json",
+        "description": "Consectetur adipiscing elit.",
+        "icon": "https://example.com/icon.png",
+        "favicon": "https://example.com/favicon.ico",
+        "language": "en-US",
+        "authors": [{ "name": "Jane Doe", "url": "https://example.com/jane" }],
+        "items": [
+            {
+                "id": "2",
+                "content_text": "Text {with} \"brackets\" [and] quotes.",
+                "url": "https://example.com/2",
+                "date_published": "2021-02-24T09:08:10Z"
+            },
+            {
+                "id": 1,
+                "title": "Lorem ipsum",
+                "content_html": "<p>Hello, world!</p>",
+                "author": { "name": "John Doe", "avatar": "https://example.com/john.png" },
+                "content_text": "Hello, world!",
+                "external_url": "https://example.org/1",
+                "tags": ["a", { "nested": [1, 2] }],
+                "attachments": [
+                    {
+                        "url": "https://example.com/1.m4a",
+                        "mime_type": "audio/x-m4a",
+                        "size_in_bytes": 89970236,
+                        "duration_in_seconds": 6629.5
+                    }
+                ],
+                "date_modified": "2021-02-25T10:00:00+01:00"
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn parse_feed_json() {
+        let feed = parse_feed(INPUT).unwrap();
+        assert_eq!(
+            Some("Lorem ipsum dolor sit amet."),
+            feed.title.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("Consectetur adipiscing elit."),
+            feed.description.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("https://example.com/"),
+            feed.home_page_url.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("https://example.com/feed.json"),
+            feed.feed_url.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("https://example.com/icon.png"),
+            feed.logo.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("https://example.com/favicon.ico"),
+            feed.icon.map(|v| v.as_raw())
+        );
+        assert_eq!(Some("en-US"), feed.language.map(|v| v.as_raw()));
+    }
+
+    #[test]
+    fn iter_json() {
+        let mut iter = Iter::with_json(INPUT).unwrap();
+
+        let item = iter.next().unwrap();
+        assert_eq!(Some("2"), item.id.map(|v| v.as_raw()));
+        let content = item.content.unwrap();
+        assert_eq!(
+            r#"Text {with} \"brackets\" [and] quotes."#,
+            content.as_raw()
+        );
+        assert_eq!(TextTy::Text, content.ty());
+        assert_eq!(r#"Text {with} "brackets" [and] quotes."#, content.decode());
+        assert_eq!(Some("https://example.com/2"), item.url.map(|v| v.as_raw()));
+        assert_eq!(1, item.authors.len());
+        assert_eq!(Some("Jane Doe"), item.authors[0].name.map(|v| v.as_raw()));
+        assert_eq!(
+            Some("https://example.com/jane"),
+            item.authors[0].url.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("2021-02-24T09:08:10Z".parse::<DateTime<Utc>>().unwrap()),
+            item.published_at
+        );
+
+        let item = iter.next().unwrap();
+        assert_eq!(Some("1"), item.id.map(|v| v.as_raw()));
+        assert_eq!(Some("Lorem ipsum"), item.title.map(|v| v.as_raw()));
+        assert_eq!(
+            Some("<p>Hello, world!</p>"),
+            item.content.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("https://example.org/1"),
+            item.external_url.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("2021-02-25T09:00:00Z".parse::<DateTime<Utc>>().unwrap()),
+            item.modified_at
+        );
+        assert_eq!(1, item.authors.len());
+        assert_eq!(Some("John Doe"), item.authors[0].name.map(|v| v.as_raw()));
+        assert_eq!(
+            Some("https://example.com/john.png"),
+            item.authors[0].avatar.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            alloc::vec![Category {
+                term: Text::json("a", TextTy::Text),
+                ..Category::default()
+            }],
+            item.categories
+        );
+        assert_eq!(1, item.attachments.len());
+        let attachment = item.attachments[0];
+        assert_eq!("https://example.com/1.m4a", attachment.url.as_raw());
+        assert_eq!(
+            Some("audio/x-m4a"),
+            attachment.mime_type.map(|v| v.as_raw())
+        );
+        assert_eq!(Some(89_970_236), attachment.size_in_bytes);
+        assert_eq!(Some(Duration::from_millis(6_629_500)), attachment.duration);
+
+        assert_eq!(None, iter.next());
+    }
+}
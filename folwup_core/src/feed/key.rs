@@ -0,0 +1,263 @@
+//! Stable keys to identify items across fetches.
+//!
+//! Many feeds omit item identifiers, so a key is derived from the best
+//! available value:
+//!
+//! 1. The item's `id` (e.g. an RSS `guid` or an Atom `id`).
+//! 2. The item's normalized URL.
+//! 3. A hash of the item's title, publication date and summary.
+
+use core::fmt::{self, Write as _};
+
+use alloc::{borrow::Cow, string::String};
+
+use super::{Item, ItemBuf, Text};
+
+/// A stable key for an item.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ItemKey {
+    /// The item's trimmed identifier.
+    Id(String),
+    /// The item's normalized URL.
+    Url(String),
+    /// A 64-bit FNV-1a hash of the item's title, publication date and summary
+    /// (or content if there is no summary).
+    Hash(u64),
+}
+
+impl fmt::Display for ItemKey {
+    /// Formats the key with a prefix for the source (e.g. `id:`, `url:`, or
+    /// `hash:`) so keys from different sources do not collide.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemKey::Id(id) => write!(f, "id:{id}"),
+            ItemKey::Url(url) => write!(f, "url:{url}"),
+            ItemKey::Hash(hash) => write!(f, "hash:{hash:016x}"),
+        }
+    }
+}
+
+/// Decodes a value which is not blank.
+#[must_use]
+fn decode(text: Option<Text<'_>>) -> Option<Cow<'_, str>> {
+    text.map(|v| v.decode()).filter(|v| !v.trim().is_empty())
+}
+
+impl Item<'_> {
+    /// Derives a stable key for the item.
+    #[must_use]
+    pub fn key(&self) -> ItemKey {
+        if let Some(id) = decode(self.id) {
+            return ItemKey::Id(String::from(id.trim()));
+        }
+
+        if let Some(url) = decode(self.url).and_then(|url| normalize_url(&url)) {
+            return ItemKey::Url(url);
+        }
+
+        let mut hasher = Fnv1a::new();
+        if let Some(title) = decode(self.title) {
+            hasher.write(title.trim().as_bytes());
+        }
+        hasher.write(&[0]);
+        if let Some(published_at) = self.published_at {
+            let _ = write!(hasher, "{}", published_at.timestamp());
+        }
+        hasher.write(&[0]);
+        if let Some(summary) = decode(self.summary).or_else(|| decode(self.content)) {
+            hasher.write(summary.trim().as_bytes());
+        }
+
+        ItemKey::Hash(hasher.finish())
+    }
+}
+
+impl ItemBuf {
+    /// Derives a stable key for the item.
+    ///
+    /// See [`Item::key()`].
+    #[must_use]
+    pub fn key(&self) -> ItemKey {
+        self.as_item().key()
+    }
+}
+
+/// 64-bit [FNV-1a][fnv] hasher.
+///
+/// The hash is stable across platforms and releases unlike
+/// `core::hash::Hash` implementations.
+///
+/// [fnv]: http://www.isthe.com/chongo/tech/comp/fnv/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct Fnv1a(u64);
+
+impl Fnv1a {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    #[must_use]
+    pub(super) const fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    pub(super) fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= u64::from(*b);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    #[must_use]
+    pub(super) const fn finish(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Write for Fnv1a {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Normalizes an absolute URL so trivially different URLs for the same
+/// resource are equal.
+///
+/// The scheme and host are lowercased, `http` is treated as `https`, default
+/// ports, fragments, `utm_*` query parameters and a trailing `/` in the path
+/// are removed.
+///
+/// Returns `None` if the URL is not absolute.
+#[must_use]
+pub fn normalize_url(url: &str) -> Option<String> {
+    let url = url.trim();
+    let (scheme, rest) = url.split_once("://")?;
+    if scheme.is_empty()
+        || !scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    {
+        return None;
+    }
+
+    let rest = rest.split_once('#').map_or(rest, |(rest, _)| rest);
+    let (rest, query) = match rest.split_once('?') {
+        Some((rest, query)) => (rest, Some(query)),
+        None => (rest, None),
+    };
+    let (authority, path) = rest.find('/').map_or((rest, ""), |idx| rest.split_at(idx));
+
+    let mut output = String::with_capacity(url.len());
+
+    let scheme_is_http =
+        scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https");
+    if scheme_is_http {
+        output.push_str("https");
+    } else {
+        output.extend(scheme.chars().map(|c| c.to_ascii_lowercase()));
+    }
+    output.push_str("://");
+
+    // Keep any user info as is.
+    let (user_info, host) = authority
+        .rsplit_once('@')
+        .map_or(("", authority), |(user_info, host)| (user_info, host));
+    if !user_info.is_empty() {
+        output.push_str(user_info);
+        output.push('@');
+    }
+    let host = if scheme_is_http {
+        host.strip_suffix(":80")
+            .or_else(|| host.strip_suffix(":443"))
+            .unwrap_or(host)
+    } else {
+        host
+    };
+    output.extend(host.chars().map(|c| c.to_ascii_lowercase()));
+
+    let path = path.strip_suffix('/').unwrap_or(path);
+    output.push_str(if path.is_empty() { "/" } else { path });
+
+    if let Some(query) = query {
+        let mut first = true;
+        for pair in query.split('&').filter(|pair| {
+            !pair.is_empty()
+                && !pair
+                    .get(..4)
+                    .is_some_and(|prefix| prefix.eq_ignore_ascii_case("utm_"))
+        }) {
+            output.push(if first { '?' } else { '&' });
+            output.push_str(pair);
+            first = false;
+        }
+    }
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::super::TextTy;
+    use super::*;
+
+    #[test]
+    fn key_from_id() {
+        let item = Item {
+            id: Some(Text::xml("  urn:uuid:1  ", TextTy::Text)),
+            url: Some(Text::plain("https://example.com/1")),
+            ..Item::default()
+        };
+        assert_eq!(ItemKey::Id(String::from("urn:uuid:1")), item.key());
+        assert_eq!("id:urn:uuid:1", alloc::format!("{}", item.key()));
+    }
+
+    #[test]
+    fn key_from_url() {
+        let item = Item {
+            url: Some(Text::plain(
+                "HTTP://Example.COM:80/posts/1/?utm_source=rss&page=2&utm_medium=feed#comments",
+            )),
+            ..Item::default()
+        };
+        assert_eq!(
+            ItemKey::Url(String::from("https://example.com/posts/1?page=2")),
+            item.key()
+        );
+    }
+
+    #[test]
+    fn key_from_hash() {
+        let published_at = "2021-02-24T09:08:10Z".parse::<DateTime<Utc>>().ok();
+        let item = Item {
+            title: Some(Text::xml("Lorem &amp; ipsum", TextTy::Text)),
+            summary: Some(Text::plain("Summary")),
+            published_at,
+            ..Item::default()
+        };
+        let same = Item {
+            title: Some(Text::json("Lorem & ipsum", TextTy::Text)),
+            summary: Some(Text::plain(" Summary ")),
+            published_at,
+            ..Item::default()
+        };
+        let edited = Item {
+            title: Some(Text::plain("Lorem ipsum (updated)")),
+            ..same.clone()
+        };
+
+        assert!(matches!(item.key(), ItemKey::Hash(_)));
+        assert_eq!(item.key(), same.key());
+        assert_ne!(item.key(), edited.key());
+    }
+
+    #[test]
+    fn fnv1a() {
+        let mut hasher = Fnv1a::new();
+        hasher.write(b"a");
+        assert_eq!(0xaf63_dc4c_8601_ec8c, hasher.finish());
+    }
+}
@@ -0,0 +1,325 @@
+//! [RDF Site Summary (RSS) 1.0][rss_1_0] feeds.
+//!
+//! Unlike RSS 2.0, the `item` elements are siblings of the `channel` element
+//! under the `rdf:RDF` root element.
+//!
+//! [rss_1_0]: https://web.resource.org/rss/1.0/spec
+
+use alloc::vec::Vec;
+
+use crate::xml;
+
+use super::{date, Author, Category, Error, Feed, Item, Iter, Text, TextTy};
+
+/// Finds the `rdf:RDF` root element.
+///
+/// `readfeed` detects RSS 1.0 documents as generic XML, so a document without
+/// the root element is assumed to be unsupported (e.g. an HTML page).
+fn find_root(input: &str) -> Result<xml::Element<'_>, Error> {
+    xml::Iter::new(input)
+        .find(|elem| elem.local_name().eq_ignore_ascii_case("RDF"))
+        .ok_or(Error::Unsupported(readfeed::Ty::XmlOrHtml))
+}
+
+/// A Dublin Core `dc:creator` element.
+#[must_use]
+fn dc_creator<'a>(elem: &xml::Element<'a>) -> Option<Author<'a>> {
+    let name = elem.content();
+    (elem.local_name().eq_ignore_ascii_case("creator") && !name.trim().is_empty()).then(|| Author {
+        name: Some(Text::xml(name, TextTy::Text)),
+        ..Author::default()
+    })
+}
+
+/// A Dublin Core `dc:subject` element.
+#[must_use]
+fn dc_subject<'a>(elem: &xml::Element<'a>) -> Option<Category<'a>> {
+    let term = elem.content();
+    (elem.local_name().eq_ignore_ascii_case("subject") && !term.trim().is_empty()).then(|| {
+        Category {
+            term: Text::xml(term, TextTy::Text),
+            ..Category::default()
+        }
+    })
+}
+
+/// Parses the channel level metadata from an RSS 1.0 document.
+///
+/// # Errors
+///
+/// Returns an error if the document does not contain an `rdf:RDF` or a
+/// `channel` element.
+pub fn parse_feed(input: &str) -> Result<Feed<'_>, Error> {
+    let root = find_root(input)?;
+    let channel = root
+        .children()
+        .find(|elem| elem.local_name().eq_ignore_ascii_case("channel"))
+        .ok_or(Error::MissingElement {
+            ty: readfeed::Ty::XmlOrHtml,
+            name: "channel",
+        })?;
+
+    let mut feed = Feed {
+        // The `image` element is a sibling of the `channel` element.
+        logo: root
+            .children()
+            .filter(|elem| elem.local_name().eq_ignore_ascii_case("image"))
+            .flat_map(|image| image.children())
+            .find(|elem| elem.local_name().eq_ignore_ascii_case("url"))
+            .map(|url| Text::xml(url.content(), TextTy::Text)),
+        xml_base: root
+            .find_attribute("base")
+            .map(|base| Text::xml(base, TextTy::Text)),
+        ..Feed::default()
+    };
+    for elem in channel.children() {
+        let local_name = elem.local_name();
+        if let Some(author) = dc_creator(&elem) {
+            feed.authors.push(author);
+        } else if let Some(category) = dc_subject(&elem) {
+            feed.categories.push(category);
+        } else if local_name.eq_ignore_ascii_case("title") {
+            feed.title = feed.title.or(Some(Text::xml(elem.content(), TextTy::Text)));
+        } else if local_name.eq_ignore_ascii_case("description") {
+            feed.description = feed
+                .description
+                .or(Some(Text::xml(elem.content(), TextTy::Text)));
+        } else if local_name.eq_ignore_ascii_case("link") {
+            if let Some(href) = elem.find_attribute("href") {
+                let is_self = elem
+                    .find_attribute("rel")
+                    .is_some_and(|rel| rel.trim().eq_ignore_ascii_case("self"));
+                if is_self {
+                    feed.feed_url = feed.feed_url.or(Some(Text::xml(href, TextTy::Text)));
+                }
+                continue;
+            }
+
+            let link = Text::xml(elem.content(), TextTy::Text);
+            feed.home_page_url = feed.home_page_url.or(Some(link));
+            feed.alternate_url = feed.alternate_url.or(Some(link));
+        } else if local_name.eq_ignore_ascii_case("language") {
+            feed.language = feed
+                .language
+                .or(Some(Text::xml(elem.content(), TextTy::Text)));
+        } else if local_name.eq_ignore_ascii_case("rights") {
+            feed.rights = feed
+                .rights
+                .or(Some(Text::xml(elem.content(), TextTy::Text)));
+        } else if local_name.eq_ignore_ascii_case("date") {
+            feed.modified_at = feed
+                .modified_at
+                .or_else(|| date::parse_utc(elem.content().trim()));
+        }
+    }
+
+    Ok(feed)
+}
+
+/// Iterates over the children of the `rdf:RDF` root element.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RdfIter<'a> {
+    iter: xml::Iter<'a>,
+    /// The `xml:base` of the root element.
+    xml_base: Option<Text<'a>>,
+    /// The channel's authors which apply to items without authors.
+    authors: Vec<Author<'a>>,
+}
+
+impl<'a> Iter<RdfIter<'a>> {
+    /// Iterates over the items in an RSS 1.0 document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document does not contain an `rdf:RDF` element.
+    pub fn with_rdf(input: &'a str) -> Result<Self, Error> {
+        let root = find_root(input)?;
+        let authors = root
+            .children()
+            .filter(|elem| elem.local_name().eq_ignore_ascii_case("channel"))
+            .flat_map(|channel| channel.children())
+            .filter_map(|elem| dc_creator(&elem))
+            .collect();
+
+        Ok(Self {
+            feed: RdfIter {
+                iter: root.children(),
+                xml_base: root
+                    .find_attribute("base")
+                    .map(|base| Text::xml(base, TextTy::Text)),
+                authors,
+            },
+        })
+    }
+
+    /// The `xml:base` of the root element.
+    #[must_use]
+    pub(super) fn xml_base(&self) -> Option<Text<'a>> {
+        self.feed.xml_base
+    }
+}
+
+impl<'a> Iterator for Iter<RdfIter<'a>> {
+    type Item = Item<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for elem in self.feed.iter.by_ref() {
+            if !elem.local_name().eq_ignore_ascii_case("item") {
+                continue;
+            }
+
+            let mut item = Item {
+                id: elem
+                    .find_attribute("about")
+                    .map(|about| Text::xml(about, TextTy::Text)),
+                xml_base: elem
+                    .find_attribute("base")
+                    .map(|base| Text::xml(base, TextTy::Text)),
+                ..Item::default()
+            };
+
+            for item_elem in elem.children() {
+                let local_name = item_elem.local_name();
+                if let Some(author) = dc_creator(&item_elem) {
+                    item.authors.push(author);
+                } else if let Some(category) = dc_subject(&item_elem) {
+                    item.categories.push(category);
+                } else if local_name.eq_ignore_ascii_case("title") {
+                    item.title = item
+                        .title
+                        .or(Some(Text::xml(item_elem.content(), TextTy::Text)));
+                } else if local_name.eq_ignore_ascii_case("link") {
+                    item.url = item
+                        .url
+                        .or(Some(Text::xml(item_elem.content(), TextTy::Text)));
+                } else if local_name.eq_ignore_ascii_case("description") {
+                    item.summary = item
+                        .summary
+                        .or(Some(Text::xml(item_elem.content(), TextTy::Html)));
+                } else if local_name.eq_ignore_ascii_case("date") {
+                    item.published_at = item
+                        .published_at
+                        .or_else(|| date::parse_utc(item_elem.content().trim()));
+                }
+            }
+
+            if item.authors.is_empty() {
+                item.authors.clone_from(&self.feed.authors);
+            }
+
+            return Some(item);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::*;
+
+    const INPUT: &str = r#"<?xml version="1.0"?>
+<rdf:RDF
+  xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#"
+  xmlns:dc="http://purl.org/dc/elements/1.1/"
+  xmlns="http://purl.org/rss/1.0/">
+  <channel rdf:about="https://example.com/rss.rdf">
+    <title>Lorem ipsum dolor sit amet.</title>
+    <link>https://example.com/</link>
+    <description>Consectetur adipiscing elit.</description>
+    <dc:creator>Jane Doe</dc:creator>
+    <dc:language>en</dc:language>
+    <dc:date>2021-02-24T10:00:00Z</dc:date>
+    <image rdf:resource="https://example.com/logo.png" />
+    <items>
+      <rdf:Seq>
+        <rdf:li resource="https://example.com/1" />
+        <rdf:li resource="https://example.com/2" />
+      </rdf:Seq>
+    </items>
+  </channel>
+  <image rdf:about="https://example.com/logo.png">
+    <title>Lorem ipsum</title>
+    <link>https://example.com/</link>
+    <url>https://example.com/logo.png</url>
+  </image>
+  <item rdf:about="https://example.com/1">
+    <title>Item 1</title>
+    <link>https://example.com/1</link>
+    <description>Item description 1</description>
+    <dc:date>2021-02-24T09:08:10Z</dc:date>
+    <dc:creator>John Doe</dc:creator>
+  </item>
+  <item rdf:about="https://example.com/2">
+    <title>Item 2</title>
+    <link>https://example.com/2</link>
+  </item>
+</rdf:RDF>
+"#;
+
+    #[test]
+    fn parse_feed_rdf() {
+        assert_eq!(readfeed::Ty::XmlOrHtml, readfeed::detect_type(INPUT));
+
+        let feed = parse_feed(INPUT).unwrap();
+        assert_eq!(
+            Some("Lorem ipsum dolor sit amet."),
+            feed.title.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("Consectetur adipiscing elit."),
+            feed.description.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("https://example.com/"),
+            feed.home_page_url.map(|v| v.as_raw())
+        );
+        assert_eq!(Some("en"), feed.language.map(|v| v.as_raw()));
+        assert_eq!(
+            Some("2021-02-24T10:00:00Z".parse::<DateTime<Utc>>().unwrap()),
+            feed.modified_at
+        );
+        assert_eq!(
+            Some("https://example.com/logo.png"),
+            feed.logo.map(|v| v.as_raw())
+        );
+    }
+
+    #[test]
+    fn parse_feed_html() {
+        let input = "<html><head><title>Lorem ipsum</title></head></html>";
+        assert_eq!(
+            Err(Error::Unsupported(readfeed::Ty::XmlOrHtml)),
+            parse_feed(input)
+        );
+    }
+
+    #[test]
+    fn iter_rdf() {
+        let mut iter = Iter::with_rdf(INPUT).unwrap();
+
+        let item = iter.next().unwrap();
+        assert_eq!(Some("https://example.com/1"), item.id.map(|v| v.as_raw()));
+        assert_eq!(Some("Item 1"), item.title.map(|v| v.as_raw()));
+        assert_eq!(Some("https://example.com/1"), item.url.map(|v| v.as_raw()));
+        assert_eq!(Some("Item description 1"), item.summary.map(|v| v.as_raw()));
+        assert_eq!(
+            Some("2021-02-24T09:08:10Z".parse::<DateTime<Utc>>().unwrap()),
+            item.published_at
+        );
+        assert_eq!(1, item.authors.len());
+        assert_eq!(Some("John Doe"), item.authors[0].name.map(|v| v.as_raw()));
+
+        let item = iter.next().unwrap();
+        assert_eq!(Some("https://example.com/2"), item.id.map(|v| v.as_raw()));
+        assert_eq!(Some("Item 2"), item.title.map(|v| v.as_raw()));
+        assert_eq!(None, item.published_at);
+        assert_eq!(1, item.authors.len());
+        assert_eq!(Some("Jane Doe"), item.authors[0].name.map(|v| v.as_raw()));
+
+        assert_eq!(None, iter.next());
+    }
+}
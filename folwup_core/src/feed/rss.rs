@@ -1,77 +1,371 @@
-use chrono::{DateTime, Utc};
+use core::time::Duration;
+
+use chrono::Weekday;
 use readfeed::rss;
 
-use super::{Feed, Item, Iter};
+use alloc::vec::Vec;
+
+use crate::{url, xml};
+
+use super::{
+    date, Attachment, Author, Category, Error, Feed, Generator, Item, Iter, Podcast, SkipDays,
+    SkipHours, Text, TextTy,
+};
 
+/// Parses a person in the RSS form of `email (Name)`.
+///
+/// A value without an `@` is assumed to be only a name such as the value of
+/// an `itunes:author` element.
 #[must_use]
-pub fn parse_feed(input: &str) -> Option<Feed<'_>> {
-    let xml = rss::Iter::new(input);
-
-    for item in xml {
-        match item {
-            rss::Elem::Rss(rss_iter) => {
-                for rss_item in rss_iter {
-                    match rss_item {
-                        rss::RssElem::Channel(channel_iter) => {
-                            let mut feed = Feed::default();
-
-                            for elem in channel_iter {
-                                match elem {
-                                    rss::ChannelElem::Title(title) => {
-                                        feed.title = feed.title.or(Some(title.content()));
-                                    }
-                                    rss::ChannelElem::Description(desc) => {
-                                        feed.description =
-                                            feed.description.or(Some(desc.content()));
-                                    }
-                                    rss::ChannelElem::Link(link) => {
-                                        feed.home_page_url =
-                                            feed.home_page_url.or(Some(link.content()));
-                                    }
-                                    rss::ChannelElem::PubDate(_)
-                                    | rss::ChannelElem::LastBuildDate(_)
-                                    | rss::ChannelElem::Image(_)
-                                    | rss::ChannelElem::Item(_)
-                                    | rss::ChannelElem::Language(_)
-                                    | rss::ChannelElem::Copyright(_)
-                                    | rss::ChannelElem::ManagingEditor(_)
-                                    | rss::ChannelElem::Webmaster(_)
-                                    | rss::ChannelElem::Category(_)
-                                    | rss::ChannelElem::Generator(_)
-                                    | rss::ChannelElem::Docs(_)
-                                    | rss::ChannelElem::Ttl(_)
-                                    | rss::ChannelElem::Rating(_)
-                                    | rss::ChannelElem::SkipHours(_)
-                                    | rss::ChannelElem::SkipDays(_)
-                                    | rss::ChannelElem::Unknown(_)
-                                    | rss::ChannelElem::Raw(_) => {}
-                                }
-                            }
+fn person(value: &str) -> Option<Author<'_>> {
+    let value = value.trim();
 
-                            return Some(feed);
-                        }
-                        rss::RssElem::Unknown(_) | rss::RssElem::Raw(_) => {}
-                    }
+    let author = if let Some((email, name)) = value
+        .strip_suffix(')')
+        .and_then(|value| value.split_once('('))
+    {
+        Author {
+            name: Some(Text::xml(name, TextTy::Text)).filter(|v| !v.as_raw().trim().is_empty()),
+            email: Some(Text::xml(email, TextTy::Text)).filter(|v| !v.as_raw().trim().is_empty()),
+            ..Author::default()
+        }
+    } else if value.contains('@') && !value.contains(char::is_whitespace) {
+        Author {
+            email: Some(Text::xml(value, TextTy::Text)),
+            ..Author::default()
+        }
+    } else {
+        Author {
+            name: Some(Text::xml(value, TextTy::Text)),
+            ..Author::default()
+        }
+    };
+
+    Some(author).filter(|author| !value.is_empty() && !author.is_empty())
+}
+
+#[must_use]
+fn category<'a>(term: &'a str, domain: Option<&'a str>) -> Option<Category<'a>> {
+    (!term.trim().is_empty()).then(|| Category {
+        term: Text::xml(term, TextTy::Text),
+        scheme: domain.map(|v| Text::xml(v, TextTy::Text)),
+        label: None,
+    })
+}
+
+/// A Dublin Core `dc:creator` element.
+#[must_use]
+fn dc_creator<'a>(elem: &rss::Unknown<'a>) -> Option<Author<'a>> {
+    if !elem
+        .tag_name()
+        .local()
+        .as_str()
+        .eq_ignore_ascii_case("creator")
+    {
+        return None;
+    }
+
+    let name = elem.content();
+    (!name.trim().is_empty()).then(|| Author {
+        name: Some(Text::xml(name, TextTy::Text)),
+        ..Author::default()
+    })
+}
+
+/// The full content of an item from a `content:encoded` element.
+///
+/// See the [RSS content module][content].
+///
+/// [content]: https://web.resource.org/rss/1.0/modules/content/
+#[must_use]
+fn content_encoded<'a>(elem: &rss::Unknown<'a>) -> Option<Text<'a>> {
+    let name = elem.tag_name();
+    let is_content_encoded = name
+        .namespace_prefix()
+        .is_some_and(|prefix| prefix.as_str().eq_ignore_ascii_case("content"))
+        && name.local().as_str().eq_ignore_ascii_case("encoded");
+    is_content_encoded.then(|| Text::xml(elem.content(), TextTy::Html))
+}
+
+/// The author of the channel from a `managingEditor` or `dc:creator` element.
+#[must_use]
+fn channel_author<'a>(elem: &rss::ChannelElem<'a>) -> Option<Author<'a>> {
+    match elem {
+        rss::ChannelElem::ManagingEditor(editor) => person(editor.content()),
+        rss::ChannelElem::Unknown(unknown) => dc_creator(unknown),
+        _ => None,
+    }
+}
+
+/// Parses an `itunes:duration` value.
+///
+/// The value is either a number of seconds or in the form of `MM:SS` or
+/// `HH:MM:SS`. Fractional seconds are ignored.
+#[must_use]
+fn parse_duration(value: &str) -> Option<Duration> {
+    let mut secs = 0u64;
+    for (idx, part) in value.trim().split(':').enumerate() {
+        if idx > 2 {
+            return None;
+        }
+        let part = part.split_once('.').map_or(part, |(whole, _)| whole);
+        secs = secs
+            .checked_mul(60)?
+            .checked_add(part.trim().parse().ok()?)?;
+    }
+    Some(Duration::from_secs(secs))
+}
+
+/// Parses an `itunes:explicit` value.
+#[must_use]
+fn parse_explicit(value: &str) -> Option<bool> {
+    let value = value.trim();
+    if ["yes", "true", "explicit"]
+        .iter()
+        .any(|v| value.eq_ignore_ascii_case(v))
+    {
+        Some(true)
+    } else if ["no", "false", "clean"]
+        .iter()
+        .any(|v| value.eq_ignore_ascii_case(v))
+    {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Reads the iTunes podcast namespace elements which are not known to
+/// `readfeed`.
+fn read_podcast_elem<'a>(podcast: &mut Podcast<'a>, elem: &rss::Unknown<'a>) {
+    let local_name = elem.tag_name().local().as_str();
+    let content = elem.content();
+    if local_name.eq_ignore_ascii_case("duration") {
+        podcast.duration = podcast.duration.or_else(|| parse_duration(content));
+    } else if local_name.eq_ignore_ascii_case("episode") {
+        podcast.episode = podcast.episode.or_else(|| content.trim().parse().ok());
+    } else if local_name.eq_ignore_ascii_case("season") {
+        podcast.season = podcast.season.or_else(|| content.trim().parse().ok());
+    } else if local_name.eq_ignore_ascii_case("explicit") {
+        podcast.explicit = podcast.explicit.or_else(|| parse_explicit(content));
+    } else if local_name.eq_ignore_ascii_case("image") {
+        podcast.image = podcast.image.or_else(|| {
+            xml::find_attribute(elem.attributes(), "href").map(|href| Text::xml(href, TextTy::Text))
+        });
+    }
+}
+
+#[must_use]
+fn skip_hours(iter: rss::ChannelSkipHoursIter<'_>) -> SkipHours {
+    let mut hours = SkipHours::new();
+    for elem in iter {
+        match elem {
+            rss::SkipHoursElem::Hour(hour) => {
+                if let Ok(hour) = hour.content().trim().parse::<u32>() {
+                    // Some publishers use 24 for midnight.
+                    hours.insert(hour % 24);
+                }
+            }
+            rss::SkipHoursElem::Unknown(_) | rss::SkipHoursElem::Raw(_) => {}
+        }
+    }
+    hours
+}
+
+#[must_use]
+fn skip_days(iter: rss::ChannelSkipDaysIter<'_>) -> SkipDays {
+    let mut days = SkipDays::new();
+    for elem in iter {
+        match elem {
+            rss::SkipDaysElem::Day(day) => {
+                if let Ok(day) = day.content().trim().parse::<Weekday>() {
+                    days.insert(day);
                 }
             }
-            rss::Elem::Unknown(_) | rss::Elem::Raw(_) => {}
+            rss::SkipDaysElem::Unknown(_) | rss::SkipDaysElem::Raw(_) => {}
         }
     }
+    days
+}
 
+#[must_use]
+fn image_url(iter: rss::ChannelImageIter<'_>) -> Option<Text<'_>> {
+    for elem in iter {
+        match elem {
+            rss::ImageElem::Url(url) => return Some(Text::xml(url.content(), TextTy::Text)),
+            rss::ImageElem::Title(_)
+            | rss::ImageElem::Link(_)
+            | rss::ImageElem::Width(_)
+            | rss::ImageElem::Height(_)
+            | rss::ImageElem::Description(_)
+            | rss::ImageElem::Unknown(_)
+            | rss::ImageElem::Raw(_) => {}
+        }
+    }
     None
 }
 
-impl<'a> Iter<rss::ChannelIter<'a>> {
-    #[must_use]
-    pub fn with_rss(input: &'a str) -> Option<Self> {
-        let xml = rss::Iter::new(input);
+/// Parses the channel level metadata from an RSS document.
+///
+/// # Errors
+///
+/// Returns an error if the document does not contain an `rss` or a `channel`
+/// element.
+#[allow(clippy::too_many_lines)]
+pub fn parse_feed(input: &str) -> Result<Feed<'_>, Error> {
+    let iter = Iter::with_rss(input)?;
+
+    let mut feed = Feed {
+        xml_base: iter.xml_base(),
+        ..Feed::default()
+    };
+    let channel_iter = iter.feed.iter;
+    let mut published_at = None;
+    let mut itunes_image = None;
+
+    for elem in channel_iter {
+        if let Some(author) = channel_author(&elem) {
+            feed.authors.push(author);
+            continue;
+        }
+
+        match elem {
+            rss::ChannelElem::Title(title) => {
+                feed.title = feed
+                    .title
+                    .or(Some(Text::xml(title.content(), TextTy::Text)));
+            }
+            rss::ChannelElem::Description(desc) => {
+                feed.description = feed
+                    .description
+                    .or(Some(Text::xml(desc.content(), TextTy::Text)));
+            }
+            rss::ChannelElem::Link(link) => {
+                // Atom links such as `<atom:link rel="self" href="..." />` are
+                // also matched by the local name.
+                if let Some(href) = xml::find_attribute(link.attributes(), "href") {
+                    let is_self = xml::find_attribute(link.attributes(), "rel")
+                        .is_some_and(|rel| rel.trim().eq_ignore_ascii_case("self"));
+                    if is_self {
+                        feed.feed_url = feed.feed_url.or(Some(Text::xml(href, TextTy::Text)));
+                    }
+                    continue;
+                }
+
+                if link.content().trim().is_empty() {
+                    continue;
+                }
+
+                let link = Text::xml(link.content(), TextTy::Text);
+                feed.home_page_url = feed.home_page_url.or(Some(link));
+                feed.alternate_url = feed.alternate_url.or(Some(link));
+            }
+            rss::ChannelElem::Category(c) => {
+                let domain = xml::find_attribute(c.attributes(), "domain");
+                feed.categories.extend(category(c.content(), domain));
+            }
+            rss::ChannelElem::Language(language) => {
+                feed.language = feed
+                    .language
+                    .or(Some(Text::xml(language.content(), TextTy::Text)));
+            }
+            rss::ChannelElem::Copyright(copyright) => {
+                feed.rights = feed
+                    .rights
+                    .or(Some(Text::xml(copyright.content(), TextTy::Text)));
+            }
+            rss::ChannelElem::LastBuildDate(last_build_date) => {
+                feed.modified_at = feed
+                    .modified_at
+                    .or_else(|| date::parse_utc(last_build_date.content().trim()));
+            }
+            rss::ChannelElem::PubDate(pub_date) => {
+                published_at = published_at.or_else(|| date::parse_utc(pub_date.content().trim()));
+            }
+            rss::ChannelElem::Generator(generator) => {
+                feed.generator = feed.generator.or(Some(Generator {
+                    name: Text::xml(generator.content(), TextTy::Text),
+                    ..Generator::default()
+                }));
+            }
+            rss::ChannelElem::Ttl(ttl) => {
+                feed.ttl = feed.ttl.or_else(|| {
+                    let minutes = ttl.content().trim().parse::<u64>().ok()?;
+                    Some(Duration::from_secs(minutes.checked_mul(60)?))
+                });
+            }
+            rss::ChannelElem::Image(image_iter) => {
+                feed.logo = feed.logo.or_else(|| image_url(image_iter));
+            }
+            rss::ChannelElem::SkipHours(hours_iter) => {
+                feed.skip_hours = skip_hours(hours_iter);
+            }
+            rss::ChannelElem::SkipDays(days_iter) => {
+                feed.skip_days = skip_days(days_iter);
+            }
+            rss::ChannelElem::Unknown(unknown) => {
+                if unknown
+                    .tag_name()
+                    .local()
+                    .as_str()
+                    .eq_ignore_ascii_case("image")
+                {
+                    itunes_image = itunes_image.or_else(|| {
+                        xml::find_attribute(unknown.attributes(), "href")
+                            .map(|href| Text::xml(href, TextTy::Text))
+                    });
+                }
+            }
+            rss::ChannelElem::Item(_)
+            | rss::ChannelElem::ManagingEditor(_)
+            | rss::ChannelElem::Webmaster(_)
+            | rss::ChannelElem::Docs(_)
+            | rss::ChannelElem::Rating(_)
+            | rss::ChannelElem::Raw(_) => {}
+        }
+    }
+
+    // The publication date is used if the content has not changed since.
+    feed.modified_at = feed.modified_at.or(published_at);
+    feed.logo = feed.logo.or(itunes_image);
+
+    Ok(feed)
+}
 
+/// Iterates over the items of an RSS `channel` element.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ItemsIter<'a> {
+    iter: rss::ChannelIter<'a>,
+    /// The channel's authors which apply to items without authors.
+    authors: Vec<Author<'a>>,
+}
+
+impl<'a> Iter<ItemsIter<'a>> {
+    /// Iterates over the items in an RSS document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document does not contain an `rss` or a
+    /// `channel` element.
+    pub fn with_rss(input: &'a str) -> Result<Self, Error> {
+        let xml = rss::Iter::new(xml::trim_truncated(input));
+
+        let mut name = "rss";
         for item in xml {
             match item {
                 rss::Elem::Rss(rss_iter) => {
+                    name = "channel";
                     for rss_item in rss_iter {
                         match rss_item {
-                            rss::RssElem::Channel(feed) => return Some(Self { feed }),
+                            rss::RssElem::Channel(iter) => {
+                                let authors = iter
+                                    .clone()
+                                    .filter_map(|elem| channel_author(&elem))
+                                    .collect();
+                                return Ok(Self {
+                                    feed: ItemsIter { iter, authors },
+                                });
+                            }
                             rss::RssElem::Unknown(_) | rss::RssElem::Raw(_) => {}
                         }
                     }
@@ -80,64 +374,114 @@ impl<'a> Iter<rss::ChannelIter<'a>> {
             }
         }
 
-        None
+        Err(Error::MissingElement {
+            ty: readfeed::Ty::Rss,
+            name,
+        })
+    }
+
+    /// The `xml:base` of the `channel` element.
+    #[must_use]
+    pub(super) fn xml_base(&self) -> Option<Text<'a>> {
+        xml::find_attribute(self.feed.iter.attributes(), "base")
+            .map(|base| Text::xml(base, TextTy::Text))
     }
 }
 
-impl<'a> Iterator for Iter<rss::ChannelIter<'a>> {
+impl<'a> Iterator for Iter<ItemsIter<'a>> {
     type Item = Item<'a>;
 
+    #[allow(clippy::too_many_lines)]
     fn next(&mut self) -> Option<Self::Item> {
-        fn convert_datetime(datetime: &str) -> Option<DateTime<Utc>> {
-            if let Ok(updated) = datetime.parse::<DateTime<Utc>>() {
-                return Some(updated);
-            }
-
-            if let Ok(updated) = DateTime::parse_from_rfc3339(datetime) {
-                return Some(updated.into());
-            }
-
-            if let Ok(updated) = DateTime::parse_from_rfc2822(datetime) {
-                return Some(updated.into());
-            }
-
-            None
-        }
-
-        for elem in self.feed.by_ref() {
+        for elem in self.feed.iter.by_ref() {
             match elem {
                 rss::ChannelElem::Item(item_iter) => {
-                    let mut item = Item::default();
+                    let mut item = Item {
+                        xml_base: xml::find_attribute(item_iter.attributes(), "base")
+                            .map(|base| Text::xml(base, TextTy::Text)),
+                        ..Item::default()
+                    };
 
                     for item_elem in item_iter {
                         match item_elem {
                             rss::ItemElem::Title(title) => {
-                                item.title = item.title.or(Some(title.content()));
+                                item.title = item
+                                    .title
+                                    .or(Some(Text::xml(title.content(), TextTy::Text)));
                             }
                             rss::ItemElem::Link(link) => {
-                                item.url = item.url.or(Some(link.content()));
+                                item.url =
+                                    item.url.or(Some(Text::xml(link.content(), TextTy::Text)));
                             }
                             rss::ItemElem::Description(description) => {
-                                item.summary = item.summary.or(Some(description.content()));
+                                item.summary = item
+                                    .summary
+                                    .or(Some(Text::xml(description.content(), TextTy::Html)));
                             }
                             rss::ItemElem::Guid(guid) => {
-                                item.id = item.id.or(Some(guid.content()));
+                                if item.id.is_none() {
+                                    item.id = Some(Text::xml(guid.content(), TextTy::Text));
+                                    item.id_is_permalink = guid.is_perma_link().is_none_or(|v| {
+                                        !v.as_str().trim().eq_ignore_ascii_case("false")
+                                    });
+                                }
                             }
                             rss::ItemElem::PubDate(published) => {
                                 item.published_at = item
                                     .published_at
-                                    .or_else(|| convert_datetime(published.content()));
+                                    .or_else(|| date::parse_utc(published.content()));
+                            }
+                            rss::ItemElem::Enclosure(enclosure) => {
+                                let Some(url) = enclosure.url() else {
+                                    continue;
+                                };
+                                item.attachments.push(Attachment {
+                                    url: Text::xml(url.as_str(), TextTy::Text),
+                                    mime_type: enclosure
+                                        .ty()
+                                        .map(|ty| Text::xml(ty.as_str(), TextTy::Text)),
+                                    size_in_bytes: enclosure
+                                        .len()
+                                        .and_then(|len| len.as_str().trim().parse().ok()),
+                                    ..Attachment::default()
+                                });
                             }
-                            rss::ItemElem::Author(_)
-                            | rss::ItemElem::Category(_)
-                            | rss::ItemElem::Enclosure(_)
-                            | rss::ItemElem::Source(_)
+                            rss::ItemElem::Author(author) => {
+                                item.authors.extend(person(author.content()));
+                            }
+                            rss::ItemElem::Unknown(unknown) => {
+                                if let Some(author) = dc_creator(&unknown) {
+                                    item.authors.push(author);
+                                } else if let Some(content) = content_encoded(&unknown) {
+                                    item.content = item.content.or(Some(content));
+                                } else {
+                                    read_podcast_elem(&mut item.podcast, &unknown);
+                                }
+                            }
+                            rss::ItemElem::Category(c) => {
+                                let domain = c.domain().map(|v| v.as_str());
+                                item.categories.extend(category(c.content(), domain));
+                            }
+                            rss::ItemElem::Source(_)
                             | rss::ItemElem::Comments(_)
-                            | rss::ItemElem::Unknown(_)
                             | rss::ItemElem::Raw(_) => {}
                         }
                     }
 
+                    if item.authors.is_empty() {
+                        item.authors.clone_from(&self.feed.authors);
+                    }
+                    // The `itunes:duration` is the duration of the enclosure.
+                    if let Some(attachment) = item.attachments.first_mut() {
+                        attachment.duration = attachment.duration.or(item.podcast.duration);
+                    }
+                    // Many feeds omit `isPermaLink="false"` for guids which
+                    // are not URLs.
+                    if item.id_is_permalink && item.id.is_some_and(|id| url::is_http(&id.decode()))
+                    {
+                        item.url = item.url.or(item.id);
+                    }
+
                     return Some(item);
                 }
                 rss::ChannelElem::Title(_)
@@ -165,3 +509,312 @@ impl<'a> Iterator for Iter<rss::ChannelIter<'a>> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use alloc::string::ToString;
+
+    use super::super::ItemKey;
+    use super::*;
+
+    #[test]
+    fn iter_podcast() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Lorem ipsum</title>
+    <link>https://example.com/</link>
+    <item>
+      <title>Episode 1</title>
+      <enclosure url="https://example.com/1.mp3" length="12345678" type="audio/mpeg" />
+      <itunes:duration>1:02:03</itunes:duration>
+      <itunes:episode>1</itunes:episode>
+      <itunes:season>2</itunes:season>
+      <itunes:explicit>false</itunes:explicit>
+      <itunes:image href="https://example.com/1.jpg" />
+    </item>
+  </channel>
+</rss>
+"#;
+
+        let mut iter = Iter::with_rss(input).unwrap();
+        let item = iter.next().unwrap();
+
+        assert_eq!(1, item.attachments.len());
+        let attachment = item.attachments[0];
+        assert_eq!("https://example.com/1.mp3", attachment.url.as_raw());
+        assert_eq!(Some("audio/mpeg"), attachment.mime_type.map(|v| v.as_raw()));
+        assert_eq!(Some(12_345_678), attachment.size_in_bytes);
+        assert_eq!(Some(Duration::from_secs(3723)), attachment.duration);
+
+        assert_eq!(Some(Duration::from_secs(3723)), item.podcast.duration);
+        assert_eq!(Some(1), item.podcast.episode);
+        assert_eq!(Some(2), item.podcast.season);
+        assert_eq!(Some(false), item.podcast.explicit);
+        assert_eq!(
+            Some("https://example.com/1.jpg"),
+            item.podcast.image.map(|v| v.as_raw())
+        );
+
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn iter_authors() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel>
+    <title>Lorem ipsum</title>
+    <managingEditor>editor@example.com (Jane Doe)</managingEditor>
+    <item>
+      <title>Item 1</title>
+      <author>john@example.com (John Doe)</author>
+      <category domain="https://example.com/tags">Rust</category>
+      <category>Programming</category>
+    </item>
+    <item>
+      <title>Item 2</title>
+      <dc:creator>Alice</dc:creator>
+    </item>
+    <item>
+      <title>Item 3</title>
+    </item>
+  </channel>
+</rss>
+"#;
+
+        let feed = parse_feed(input).unwrap();
+        assert_eq!(1, feed.authors.len());
+        assert_eq!(
+            Some("Jane Doe"),
+            feed.authors[0].name.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(
+            Some("editor@example.com"),
+            feed.authors[0].email.map(|v| v.to_string()).as_deref()
+        );
+
+        let mut iter = Iter::with_rss(input).unwrap();
+        let item = iter.next().unwrap();
+        assert_eq!(1, item.authors.len());
+        assert_eq!(
+            Some("John Doe"),
+            item.authors[0].name.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(
+            alloc::vec![
+                Category {
+                    term: Text::xml("Rust", TextTy::Text),
+                    scheme: Some(Text::xml("https://example.com/tags", TextTy::Text)),
+                    label: None,
+                },
+                Category {
+                    term: Text::xml("Programming", TextTy::Text),
+                    scheme: None,
+                    label: None,
+                },
+            ],
+            item.categories
+        );
+
+        let item = iter.next().unwrap();
+        assert_eq!(1, item.authors.len());
+        assert_eq!(
+            Some("Alice"),
+            item.authors[0].name.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(None, item.authors[0].email);
+
+        let item = iter.next().unwrap();
+        assert_eq!(feed.authors, item.authors);
+    }
+
+    #[test]
+    fn iter_content_encoded() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>Lorem ipsum</title>
+    <item>
+      <title>Item 1</title>
+      <description>A summary</description>
+      <content:encoded><![CDATA[<p>The full <em>content</em></p>]]></content:encoded>
+    </item>
+    <item>
+      <title>Item 2</title>
+      <description>Only a summary</description>
+      <other:encoded xmlns:other="https://example.com/other">Not content</other:encoded>
+    </item>
+  </channel>
+</rss>
+"#;
+
+        let mut iter = Iter::with_rss(input).unwrap();
+        let item = iter.next().unwrap();
+        assert_eq!(
+            Some("A summary"),
+            item.summary.map(|v| v.decode()).as_deref()
+        );
+        let content = item.content.unwrap();
+        assert_eq!(TextTy::Html, content.ty());
+        assert_eq!("<p>The full <em>content</em></p>", content.decode());
+
+        let item = iter.next().unwrap();
+        assert_eq!(None, item.content);
+    }
+
+    #[test]
+    fn iter_keys() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Lorem ipsum</title>
+    <item>
+      <title>Item 1</title>
+      <guid>https://example.com/posts/1</guid>
+    </item>
+    <item>
+      <title>Item 2</title>
+      <link>https://example.com/posts/2</link>
+      <guid isPermaLink="false">post-2</guid>
+    </item>
+    <item>
+      <title>Item 3</title>
+      <link>HTTP://EXAMPLE.COM/posts/3/?utm_source=rss</link>
+    </item>
+    <item>
+      <title>Item 4</title>
+      <description>Hello</description>
+    </item>
+  </channel>
+</rss>
+"#;
+
+        let mut iter = Iter::with_rss(input).unwrap();
+
+        let item = iter.next().unwrap();
+        assert!(item.id_is_permalink);
+        assert_eq!(
+            Some("https://example.com/posts/1"),
+            item.url.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(
+            ItemKey::Id("https://example.com/posts/1".to_string()),
+            item.key()
+        );
+
+        let item = iter.next().unwrap();
+        assert!(!item.id_is_permalink);
+        assert_eq!(
+            Some("https://example.com/posts/2"),
+            item.url.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(ItemKey::Id("post-2".to_string()), item.key());
+
+        let item = iter.next().unwrap();
+        assert!(!item.id_is_permalink);
+        assert_eq!(
+            ItemKey::Url("https://example.com/posts/3".to_string()),
+            item.key()
+        );
+
+        let item = iter.next().unwrap();
+        assert!(matches!(item.key(), ItemKey::Hash(_)));
+        assert_eq!(item.key(), item.to_item_buf().key());
+
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn iter_guids_without_urls() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Lorem ipsum</title>
+    <item>
+      <title>Item 1</title>
+      <guid>12345</guid>
+    </item>
+    <item>
+      <title>Item 2</title>
+      <guid>tag:example.com,2024:posts/2</guid>
+    </item>
+  </channel>
+</rss>
+"#;
+
+        let items = Iter::with_str_and_base(input, "https://example.com/feed.xml")
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert_eq!(2, items.len());
+        for item in &items {
+            assert!(item.id_is_permalink);
+            assert_eq!(None, item.url);
+        }
+        assert_eq!(ItemKey::Id("12345".to_string()), items[0].key());
+    }
+
+    #[test]
+    fn parse_feed_metadata() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Lorem ipsum</title>
+    <link>https://example.com/</link>
+    <language>en-us</language>
+    <copyright>Copyright 2002, Example</copyright>
+    <pubDate>Tue, 10 Jun 2003 04:00:00 GMT</pubDate>
+    <lastBuildDate>Tue, 10 Jun 2003 09:41:01 GMT</lastBuildDate>
+    <generator>Example Generator 2.0</generator>
+    <ttl>60</ttl>
+    <image>
+      <url>https://example.com/logo.png</url>
+      <title>Lorem ipsum</title>
+      <link>https://example.com/</link>
+    </image>
+    <skipHours><hour>0</hour><hour>1</hour><hour>24</hour><hour>23</hour></skipHours>
+    <skipDays><day>Saturday</day><day>Sunday</day></skipDays>
+  </channel>
+</rss>
+"#;
+
+        let feed = parse_feed(input).unwrap();
+        assert_eq!(Some("en-us"), feed.language.map(|v| v.as_raw()));
+        assert_eq!(
+            Some("Copyright 2002, Example"),
+            feed.rights.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            Some("2003-06-10T09:41:01Z".parse::<DateTime<Utc>>().unwrap()),
+            feed.modified_at
+        );
+        assert_eq!(
+            Some("Example Generator 2.0"),
+            feed.generator.map(|v| v.name.as_raw())
+        );
+        assert_eq!(Some(3600), feed.ttl.map(|ttl| ttl.as_secs()));
+        assert_eq!(
+            Some("https://example.com/logo.png"),
+            feed.logo.map(|v| v.as_raw())
+        );
+        assert_eq!(
+            alloc::vec![0, 1, 23],
+            feed.skip_hours.iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            alloc::vec![Weekday::Sat, Weekday::Sun],
+            feed.skip_days.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn duration() {
+        assert_eq!(Some(Duration::from_secs(90)), parse_duration("90"));
+        assert_eq!(Some(Duration::from_secs(125)), parse_duration("02:05"));
+        assert_eq!(Some(Duration::from_secs(3725)), parse_duration("1:02:05.5"));
+        assert_eq!(None, parse_duration("1:2:3:4"));
+        assert_eq!(None, parse_duration("abc"));
+    }
+}
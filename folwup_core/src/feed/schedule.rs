@@ -0,0 +1,131 @@
+//! Publisher hints for when a feed does not need to be fetched.
+//!
+//! See the RSS [`skipHours`][skip_hours] and [`skipDays`][skip_days]
+//! elements.
+//!
+//! [skip_hours]: https://www.rssboard.org/skip-hours-days#skiphours
+//! [skip_days]: https://www.rssboard.org/skip-hours-days#skipdays
+
+use chrono::Weekday;
+
+/// A set of hours in GMT from 0 to 23.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SkipHours(u32);
+
+impl SkipHours {
+    const MASK: u32 = (1 << 24) - 1;
+
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Creates a set where bit `n` represents hour `n`.
+    ///
+    /// Bits above hour 23 are ignored.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits & Self::MASK)
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn contains(self, hour: u32) -> bool {
+        hour < 24 && self.0 & (1 << hour) != 0
+    }
+
+    /// Adds an hour to the set.
+    ///
+    /// Returns `false` if the hour is not between 0 and 23.
+    pub fn insert(&mut self, hour: u32) -> bool {
+        if hour >= 24 {
+            return false;
+        }
+        self.0 |= 1 << hour;
+        true
+    }
+
+    /// Iterates over the hours in ascending order.
+    pub fn iter(self) -> impl Iterator<Item = u32> {
+        (0..24).filter(move |hour| self.contains(*hour))
+    }
+}
+
+/// A set of days of the week.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SkipDays(u8);
+
+impl SkipDays {
+    const MASK: u8 = (1 << 7) - 1;
+
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self(0)
+    }
+
+    /// Creates a set where bit `n` represents the `n`th day from Monday.
+    ///
+    /// The highest bit is ignored.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits & Self::MASK)
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn contains(self, day: Weekday) -> bool {
+        self.0 & (1 << day.num_days_from_monday()) != 0
+    }
+
+    /// Adds a day to the set.
+    pub fn insert(&mut self, day: Weekday) {
+        self.0 |= 1 << day.num_days_from_monday();
+    }
+
+    /// Iterates over the days starting from Monday.
+    pub fn iter(self) -> impl Iterator<Item = Weekday> {
+        [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+            Weekday::Sat,
+            Weekday::Sun,
+        ]
+        .into_iter()
+        .filter(move |day| self.contains(*day))
+    }
+}
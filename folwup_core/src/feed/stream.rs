@@ -0,0 +1,1022 @@
+//! Incremental parsing of documents which arrive in chunks.
+//!
+//! A [`Parser`] buffers the input until an item is complete, yields the item
+//! as an owned [`ItemBuf`], and then discards the item's input. Memory use is
+//! bounded by the size of the feed level metadata and the largest item
+//! instead of the size of the whole document.
+//!
+//! The feed level metadata is parsed from the input before the first item.
+//! Feed level elements (or JSON Feed members) after the first item are
+//! ignored.
+//!
+//! With the `encoding` feature, the encoding of the input is detected from
+//! the start of the document (see [`encoding::detect()`]) and the input is
+//! transcoded to UTF-8. Otherwise, the input must be UTF-8.
+//!
+//! ```
+//! use folwup_core::feed::stream::Parser;
+//!
+//! let mut parser = Parser::new();
+//! let mut titles = Vec::new();
+//!
+//! for chunk in [
+//!     &b"<rss version=\"2.0\"><channel><title>Example</title><item><ti"[..],
+//!     &b"tle>Item 1</title></item><item><title>Item 2</title></item>"[..],
+//!     &b"</channel></rss>"[..],
+//! ] {
+//!     parser.push(chunk);
+//!     while let Some(item) = parser.next_item()? {
+//!         titles.extend(item.title.map(|title| title.value));
+//!     }
+//! }
+//!
+//! parser.finish();
+//! while let Some(item) = parser.next_item()? {
+//!     titles.extend(item.title.map(|title| title.value));
+//! }
+//!
+//! assert!(parser.is_done());
+//! assert_eq!(vec!["Item 1", "Item 2"], titles);
+//! # Ok::<(), folwup_core::feed::Error>(())
+//! ```
+
+use core::str;
+
+use alloc::{format, string::String, vec::Vec};
+use maybe_xml::{token, Reader};
+
+#[cfg(feature = "encoding")]
+use encoding_rs::{CoderResult, Decoder};
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(feature = "encoding")]
+use crate::encoding;
+
+use super::{effective_base, parse_feed, Error, FeedBuf, ItemBuf, Iter};
+
+/// The default limit for the length of buffered input (16 MiB).
+pub const DEFAULT_MAX_BUFFER_LEN: usize = 16 * 1024 * 1024;
+
+/// The type of an XML feed document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum XmlTy {
+    Atom,
+    Rss,
+    Rdf,
+}
+
+impl XmlTy {
+    #[must_use]
+    fn with_root(local_name: &str) -> Option<Self> {
+        if local_name.eq_ignore_ascii_case("feed") {
+            Some(XmlTy::Atom)
+        } else if local_name.eq_ignore_ascii_case("rss") {
+            Some(XmlTy::Rss)
+        } else if local_name.eq_ignore_ascii_case("RDF") {
+            Some(XmlTy::Rdf)
+        } else {
+            None
+        }
+    }
+
+    /// The depth of the item elements where the root element is at depth 0.
+    #[must_use]
+    const fn item_depth(self) -> usize {
+        match self {
+            XmlTy::Atom | XmlTy::Rdf => 1,
+            XmlTy::Rss => 2,
+        }
+    }
+
+    #[must_use]
+    const fn item_name(self) -> &'static str {
+        match self {
+            XmlTy::Atom => "entry",
+            XmlTy::Rss | XmlTy::Rdf => "item",
+        }
+    }
+
+    #[must_use]
+    const fn ty(self) -> readfeed::Ty {
+        match self {
+            XmlTy::Atom => readfeed::Ty::Atom,
+            XmlTy::Rss => readfeed::Ty::Rss,
+            XmlTy::Rdf => readfeed::Ty::XmlOrHtml,
+        }
+    }
+}
+
+/// Something found while scanning the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Event {
+    /// The feed level metadata ends at the index.
+    Header { end: usize },
+    /// An item is between the indexes.
+    Item { start: usize, end: usize },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+struct XmlState {
+    ty: Option<XmlTy>,
+    /// The start tags of the elements which contain the items.
+    skeleton: String,
+    /// The names of the open elements which contain the items.
+    path: Vec<String>,
+    depth: usize,
+    has_header: bool,
+    item_start: Option<usize>,
+}
+
+impl XmlState {
+    /// The end tags for the elements which contain the items.
+    #[must_use]
+    fn closing(&self) -> String {
+        let mut closing = String::new();
+        for name in self.path.iter().rev() {
+            closing.push_str("</");
+            closing.push_str(name);
+            closing.push('>');
+        }
+        closing
+    }
+
+    fn scan(&mut self, input: &str, pos: &mut usize) -> Result<Option<Event>, Error> {
+        let reader = Reader::from_str(input);
+
+        loop {
+            let start = *pos;
+
+            // The tokenizer may panic on a truncated `<!-`.
+            if input.len() - start < 4 && input[start..].starts_with('<') {
+                return Ok(None);
+            }
+
+            let Some(token) = reader.tokenize(pos) else {
+                return Ok(None);
+            };
+
+            match token.ty() {
+                token::Ty::StartTag(tag) => {
+                    let local_name = tag.name().local().as_str();
+                    if self.item_start.is_none() {
+                        let ty = if let Some(ty) = self.ty {
+                            ty
+                        } else {
+                            let ty = XmlTy::with_root(local_name)
+                                .ok_or(Error::Unsupported(readfeed::Ty::XmlOrHtml))?;
+                            self.ty = Some(ty);
+                            ty
+                        };
+
+                        if self.depth == ty.item_depth()
+                            && local_name.eq_ignore_ascii_case(ty.item_name())
+                        {
+                            self.item_start = Some(start);
+                            self.depth += 1;
+                            if !self.has_header {
+                                self.has_header = true;
+                                return Ok(Some(Event::Header { end: start }));
+                            }
+                            continue;
+                        }
+
+                        if self.depth == self.path.len()
+                            && self.depth < ty.item_depth()
+                            && (self.depth == 0 || local_name.eq_ignore_ascii_case("channel"))
+                        {
+                            self.skeleton.push_str(token.as_str());
+                            self.path.push(String::from(tag.name().as_str()));
+                        }
+                    }
+                    self.depth += 1;
+                }
+                token::Ty::EndTag(_) => {
+                    self.depth = self.depth.saturating_sub(1);
+                    if let Some(item_start) = self.item_start {
+                        if self.ty.is_some_and(|ty| ty.item_depth() == self.depth) {
+                            self.item_start = None;
+                            return Ok(Some(Event::Item {
+                                start: item_start,
+                                end: *pos,
+                            }));
+                        }
+                    }
+                }
+                token::Ty::EmptyElementTag(_)
+                | token::Ty::Characters(_)
+                | token::Ty::ProcessingInstruction(_)
+                | token::Ty::Declaration(_)
+                | token::Ty::Comment(_)
+                | token::Ty::Cdata(_) => {}
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[allow(clippy::struct_excessive_bools)]
+struct JsonState {
+    depth: usize,
+    in_string: bool,
+    is_escaped: bool,
+    string_start: usize,
+    /// The last string in the top-level object.
+    last_string: Option<(usize, usize)>,
+    /// The current member name in the top-level object.
+    key: Option<(usize, usize)>,
+    has_header: bool,
+    in_items: bool,
+    item_start: Option<usize>,
+}
+
+impl JsonState {
+    fn scan(&mut self, input: &str, pos: &mut usize) -> Result<Option<Event>, Error> {
+        let bytes = input.as_bytes();
+
+        while *pos < bytes.len() {
+            let idx = *pos;
+            let b = bytes[idx];
+            *pos += 1;
+
+            if self.in_string {
+                if self.is_escaped {
+                    self.is_escaped = false;
+                } else if b == b'\\' {
+                    self.is_escaped = true;
+                } else if b == b'"' {
+                    self.in_string = false;
+                    if self.depth == 1 {
+                        self.last_string = Some((self.string_start, idx));
+                    }
+                }
+                continue;
+            }
+
+            match b {
+                b'"' => {
+                    self.in_string = true;
+                    self.string_start = idx + 1;
+                }
+                b':' if self.depth == 1 => self.key = self.last_string.take(),
+                b',' if self.depth == 1 => self.key = None,
+                b'{' | b'[' => {
+                    if self.depth == 0 && b != b'{' {
+                        return Err(Error::Malformed(readfeed::Ty::Json));
+                    }
+
+                    if self.depth == 1
+                        && b == b'['
+                        && !self.has_header
+                        && self
+                            .key
+                            .is_some_and(|(start, end)| &input[start..end] == "items")
+                    {
+                        self.has_header = true;
+                        self.in_items = true;
+                        self.depth += 1;
+                        return Ok(Some(Event::Header { end: *pos }));
+                    }
+
+                    if self.in_items && self.depth == 2 && b == b'{' {
+                        self.item_start = Some(idx);
+                    }
+                    self.depth += 1;
+                }
+                b'}' | b']' => {
+                    self.depth = self.depth.saturating_sub(1);
+                    if self.in_items {
+                        if self.depth == 2 {
+                            if let Some(start) = self.item_start.take() {
+                                return Ok(Some(Event::Item { start, end: *pos }));
+                            }
+                        } else if self.depth == 1 {
+                            self.in_items = false;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum State {
+    /// The type of document is not known yet.
+    Unknown,
+    Xml(XmlState),
+    Json(JsonState),
+}
+
+impl State {
+    #[must_use]
+    fn ty(&self) -> readfeed::Ty {
+        match self {
+            State::Unknown => readfeed::Ty::Unknown,
+            State::Xml(state) => state.ty.map_or(readfeed::Ty::XmlOrHtml, XmlTy::ty),
+            State::Json(_) => readfeed::Ty::Json,
+        }
+    }
+
+    #[must_use]
+    fn item_start(&self) -> Option<usize> {
+        match self {
+            State::Unknown => None,
+            State::Xml(state) => state.item_start,
+            State::Json(state) => state.item_start,
+        }
+    }
+}
+
+/// A push parser which accepts a document in chunks of bytes.
+///
+/// With the `encoding` feature, the encoding is detected from a byte order
+/// mark, then the charset set with `set_content_type()`, and then the
+/// `encoding` in the XML declaration. The start of the document is buffered
+/// until the end of the XML declaration (or the first `>`) has been pushed.
+/// If no encoding is declared, or without the feature, the input is expected
+/// to be UTF-8. Invalid sequences are replaced with U+FFFD.
+///
+/// Call [`push()`][Parser::push()] with each chunk and then
+/// [`next_item()`][Parser::next_item()] until it returns `Ok(None)`. After
+/// the last chunk, call [`finish()`][Parser::finish()] and retrieve the
+/// remaining items.
+#[derive(Debug)]
+pub struct Parser {
+    buf: String,
+    /// An incomplete UTF-8 sequence at the end of the last chunk.
+    incomplete: Vec<u8>,
+    /// The start of the document which is buffered until the encoding is
+    /// detected.
+    #[cfg(feature = "encoding")]
+    head: Option<Vec<u8>>,
+    #[cfg(feature = "encoding")]
+    content_type: Option<String>,
+    /// Transcodes the input if the document is not UTF-8.
+    #[cfg(feature = "encoding")]
+    decoder: Option<Decoder>,
+    pos: usize,
+    state: State,
+    base: Option<String>,
+    /// The base URL for items which includes the feed's `xml:base`.
+    item_base: Option<String>,
+    feed: Option<FeedBuf>,
+    max_buffer_len: usize,
+    is_finished: bool,
+    is_done: bool,
+}
+
+impl Default for Parser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Parser {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            buf: String::new(),
+            incomplete: Vec::new(),
+            #[cfg(feature = "encoding")]
+            head: Some(Vec::new()),
+            #[cfg(feature = "encoding")]
+            content_type: None,
+            #[cfg(feature = "encoding")]
+            decoder: None,
+            pos: 0,
+            state: State::Unknown,
+            base: None,
+            item_base: None,
+            feed: None,
+            max_buffer_len: DEFAULT_MAX_BUFFER_LEN,
+            is_finished: false,
+            is_done: false,
+        }
+    }
+
+    /// Creates a parser which resolves relative URLs.
+    ///
+    /// The `base` is the URL which the document is retrieved from.
+    #[must_use]
+    pub fn with_base(base: &str) -> Self {
+        Self {
+            base: Some(String::from(base)),
+            ..Self::new()
+        }
+    }
+
+    /// Sets the value of the HTTP `Content-Type` header which is used to
+    /// detect the encoding.
+    ///
+    /// Must be called before the first chunk is pushed.
+    #[cfg(feature = "encoding")]
+    pub fn set_content_type(&mut self, content_type: &str) {
+        self.content_type = Some(String::from(content_type));
+    }
+
+    /// Sets the limit for the length of the buffered input.
+    ///
+    /// The buffered input is the feed level metadata or a single item.
+    /// Defaults to [`DEFAULT_MAX_BUFFER_LEN`].
+    pub fn set_max_buffer_len(&mut self, len: usize) {
+        self.max_buffer_len = len;
+    }
+
+    /// The feed level metadata.
+    ///
+    /// The metadata is available once the first item has been found or the
+    /// document has been completely parsed.
+    #[must_use]
+    pub fn feed(&self) -> Option<&FeedBuf> {
+        self.feed.as_ref()
+    }
+
+    /// Returns true if the document has been completely parsed or an error
+    /// was returned.
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        self.is_done
+    }
+
+    /// Appends a chunk of the document.
+    pub fn push(&mut self, bytes: &[u8]) {
+        #[cfg(feature = "encoding")]
+        {
+            if let Some(head) = &mut self.head {
+                head.extend_from_slice(bytes);
+                // The XML declaration must be complete to detect the encoding.
+                if !head.contains(&b'>') && head.len() < encoding::MAX_XML_DECLARATION_LEN {
+                    return;
+                }
+                self.detect_encoding();
+                return;
+            }
+            if let Some(decoder) = &mut self.decoder {
+                decode(decoder, &mut self.buf, bytes, false);
+                return;
+            }
+        }
+
+        if self.incomplete.is_empty() {
+            self.push_utf8(bytes);
+        } else {
+            let mut incomplete = core::mem::take(&mut self.incomplete);
+            incomplete.extend_from_slice(bytes);
+            self.push_utf8(&incomplete);
+        }
+    }
+
+    /// Detects the encoding from the buffered start of the document and
+    /// pushes the buffered input.
+    #[cfg(feature = "encoding")]
+    fn detect_encoding(&mut self) {
+        let Some(head) = self.head.take() else {
+            return;
+        };
+
+        let detected = encoding::detect(&head, self.content_type.as_deref());
+        if detected.encoding == encoding_rs::UTF_8 {
+            // A UTF-8 byte order mark is skipped when the input is scanned.
+            self.push(&head);
+            return;
+        }
+
+        let mut decoder = match detected.source {
+            encoding::Source::Bom => detected.encoding.new_decoder_with_bom_removal(),
+            encoding::Source::ContentType
+            | encoding::Source::XmlDeclaration
+            | encoding::Source::Default => detected.encoding.new_decoder_without_bom_handling(),
+        };
+        decode(&mut decoder, &mut self.buf, &head, false);
+        self.decoder = Some(decoder);
+    }
+
+    fn push_utf8(&mut self, mut bytes: &[u8]) {
+        loop {
+            match str::from_utf8(bytes) {
+                Ok(value) => {
+                    self.buf.push_str(value);
+                    return;
+                }
+                Err(error) => {
+                    let (valid, rest) = bytes.split_at(error.valid_up_to());
+                    self.buf.push_str(str::from_utf8(valid).unwrap_or_default());
+                    if let Some(len) = error.error_len() {
+                        self.buf.push(char::REPLACEMENT_CHARACTER);
+                        bytes = &rest[len..];
+                    } else {
+                        self.incomplete.extend_from_slice(rest);
+                        return;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Signals that the whole document has been pushed.
+    pub fn finish(&mut self) {
+        #[cfg(feature = "encoding")]
+        {
+            self.detect_encoding();
+            if let Some(decoder) = &mut self.decoder {
+                decode(decoder, &mut self.buf, &[], true);
+            }
+        }
+
+        if !self.incomplete.is_empty() {
+            self.incomplete.clear();
+            self.buf.push(char::REPLACEMENT_CHARACTER);
+        }
+        self.is_finished = true;
+    }
+
+    /// Returns the next complete item.
+    ///
+    /// Returns `Ok(None)` if more input is required or if the document has
+    /// been completely parsed (see [`is_done()`][Parser::is_done()]).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not a supported feed type, if the
+    /// document is malformed, or if the buffered input exceeds the limit.
+    pub fn next_item(&mut self) -> Result<Option<ItemBuf>, Error> {
+        if self.is_done {
+            return Ok(None);
+        }
+
+        let result = self.scan();
+        if result.is_err() {
+            self.is_done = true;
+        }
+        result
+    }
+
+    fn scan(&mut self) -> Result<Option<ItemBuf>, Error> {
+        if self.state == State::Unknown {
+            // The byte order mark is not part of the document.
+            if self.buf.starts_with('\u{feff}') {
+                self.buf.drain(..'\u{feff}'.len_utf8());
+            }
+            let input = self.buf.trim_start_matches([' ', '\t', '\r', '\n']);
+            match input.as_bytes().first() {
+                Some(b'<') => self.state = State::Xml(XmlState::default()),
+                Some(b'{') => self.state = State::Json(JsonState::default()),
+                Some(_) => return Err(Error::Unsupported(readfeed::Ty::Unknown)),
+                None => {}
+            }
+        }
+
+        loop {
+            let event = match &mut self.state {
+                State::Unknown => None,
+                State::Xml(state) => state.scan(&self.buf, &mut self.pos)?,
+                State::Json(state) => state.scan(&self.buf, &mut self.pos)?,
+            };
+
+            match event {
+                Some(Event::Header { end }) => {
+                    let input = match &self.state {
+                        State::Xml(state) => format!("{}{}", &self.buf[..end], state.closing()),
+                        State::Unknown | State::Json(_) => format!("{}]}}", &self.buf[..end]),
+                    };
+                    self.set_feed(&input)?;
+                }
+                Some(Event::Item { start, end }) => {
+                    let item = self.parse_item(start, end)?;
+                    self.buf.drain(..end);
+                    self.pos -= end;
+                    if let Some(item) = item {
+                        return Ok(Some(item));
+                    }
+                }
+                None => break,
+            }
+        }
+
+        // Discard the input between items.
+        if self.feed.is_some() && self.state.item_start().is_none() {
+            self.buf.drain(..self.pos);
+            self.pos = 0;
+        }
+
+        if self.is_finished {
+            self.is_done = true;
+            if self.state.item_start().is_some() {
+                return Err(Error::Malformed(self.state.ty()));
+            }
+            if self.feed.is_none() {
+                let input = core::mem::take(&mut self.buf);
+                self.set_feed(&input)?;
+            }
+            return Ok(None);
+        }
+
+        if self.buf.len() > self.max_buffer_len {
+            return Err(Error::LimitExceeded(self.state.ty()));
+        }
+
+        Ok(None)
+    }
+
+    fn set_feed(&mut self, input: &str) -> Result<(), Error> {
+        let mut feed = FeedBuf::from(parse_feed(input)?);
+        if let Some(base) = &self.base {
+            self.item_base = Some(effective_base(base, feed.xml_base.as_deref()));
+            feed.resolve_urls(base);
+        }
+        self.feed = Some(feed);
+        Ok(())
+    }
+
+    fn parse_item(&self, start: usize, end: usize) -> Result<Option<ItemBuf>, Error> {
+        let input = &self.buf[start..end];
+        let item = match &self.state {
+            State::Xml(state) => {
+                let input = format!("{}{input}{}", state.skeleton, state.closing());
+                match state.ty {
+                    Some(XmlTy::Atom) => Iter::with_atom(&input)?.next().map(ItemBuf::from),
+                    Some(XmlTy::Rss) => Iter::with_rss(&input)?.next().map(ItemBuf::from),
+                    Some(XmlTy::Rdf) => Iter::with_rdf(&input)?.next().map(ItemBuf::from),
+                    None => None,
+                }
+            }
+            State::Json(_) => {
+                let input = format!(r#"{{"items":[{input}]}}"#);
+                Iter::with_json(&input)?.next().map(ItemBuf::from)
+            }
+            State::Unknown => None,
+        };
+
+        Ok(item.map(|mut item| {
+            if item.authors.is_empty() {
+                if let Some(feed) = &self.feed {
+                    item.authors.clone_from(&feed.authors);
+                }
+            }
+            if let Some(base) = &self.item_base {
+                item.resolve_urls(base);
+            }
+            item
+        }))
+    }
+}
+
+/// Transcodes the bytes and appends the output to the buffer.
+///
+/// Malformed sequences are replaced with U+FFFD.
+#[cfg(feature = "encoding")]
+fn decode(decoder: &mut Decoder, buf: &mut String, mut bytes: &[u8], last: bool) {
+    loop {
+        buf.reserve(
+            decoder
+                .max_utf8_buffer_length(bytes.len())
+                .unwrap_or(bytes.len()),
+        );
+        let (result, read, _) = decoder.decode_to_string(bytes, buf, last);
+        bytes = &bytes[read..];
+        match result {
+            CoderResult::InputEmpty => return,
+            CoderResult::OutputFull => {}
+        }
+    }
+}
+
+/// Iterates over owned items read from an [`io::Read`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct ReadIter<R> {
+    reader: R,
+    parser: Parser,
+    is_eof: bool,
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Read> Iter<ReadIter<R>> {
+    /// Reads the document in chunks and iterates over the items.
+    ///
+    /// Items are yielded as soon as they have been read. A [`io::BufRead`]
+    /// can be used as the reader as well.
+    #[must_use]
+    pub fn with_reader(reader: R) -> Self {
+        Self::with_parser(reader, Parser::new())
+    }
+
+    /// Reads the document in chunks and iterates over the items with
+    /// relative URLs resolved.
+    ///
+    /// The `base` is the URL which the document was retrieved from.
+    #[must_use]
+    pub fn with_reader_and_base(reader: R, base: &str) -> Self {
+        Self::with_parser(reader, Parser::with_base(base))
+    }
+
+    /// Reads the document with a configured parser.
+    #[must_use]
+    pub fn with_parser(reader: R, parser: Parser) -> Self {
+        Self {
+            feed: ReadIter {
+                reader,
+                parser,
+                is_eof: false,
+            },
+        }
+    }
+
+    /// The feed level metadata.
+    ///
+    /// See [`Parser::feed()`].
+    #[must_use]
+    pub fn feed(&self) -> Option<&FeedBuf> {
+        self.feed.parser.feed()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Read> Iterator for Iter<ReadIter<R>> {
+    type Item = io::Result<ItemBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = [0; 8 * 1024];
+
+        loop {
+            match self.feed.parser.next_item() {
+                Ok(Some(item)) => return Some(Ok(item)),
+                Ok(None) => {}
+                Err(error) => return Some(Err(io::Error::new(io::ErrorKind::InvalidData, error))),
+            }
+
+            if self.feed.parser.is_done() {
+                return None;
+            }
+
+            if self.feed.is_eof {
+                self.feed.parser.finish();
+                continue;
+            }
+
+            match self.feed.reader.read(&mut chunk) {
+                Ok(0) => self.feed.is_eof = true,
+                Ok(len) => self.feed.parser.push(&chunk[..len]),
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    /// Pushes the input in chunks of `len` bytes and collects the items.
+    fn parse_chunks(mut parser: Parser, input: &[u8], len: usize) -> (Parser, Vec<ItemBuf>) {
+        let mut items = Vec::new();
+        for chunk in input.chunks(len) {
+            parser.push(chunk);
+            while let Some(item) = parser.next_item().unwrap() {
+                items.push(item);
+            }
+        }
+        parser.finish();
+        while let Some(item) = parser.next_item().unwrap() {
+            items.push(item);
+        }
+        assert!(parser.is_done());
+        (parser, items)
+    }
+
+    #[test]
+    fn chunked_rss() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel xml:base="https://example.com/blog/">
+    <title>Caf&#233; ☕</title>
+    <dc:creator>Jane Doe</dc:creator>
+    <item>
+      <title>Item 1</title>
+      <link>posts/1</link>
+      <description><![CDATA[<p>Hello </item> world</p>]]></description>
+    </item>
+    <!-- <item><title>Comment</title></item> -->
+    <item>
+      <title>Item 2 ☕</title>
+      <dc:creator>John Doe</dc:creator>
+    </item>
+  </channel>
+</rss>
+"#;
+
+        for len in [1, 2, 3, 7, 64, input.len()] {
+            let (parser, items) = parse_chunks(
+                Parser::with_base("https://example.com/feed.xml"),
+                input.as_bytes(),
+                len,
+            );
+
+            let feed = parser.feed().unwrap();
+            assert_eq!("Café ☕", feed.title.as_ref().unwrap().value);
+
+            assert_eq!(2, items.len());
+            assert_eq!("Item 1", items[0].title.as_ref().unwrap().value);
+            assert_eq!(
+                Some("https://example.com/blog/posts/1"),
+                items[0].url.as_deref()
+            );
+            assert_eq!(
+                "<p>Hello </item> world</p>",
+                items[0].summary.as_ref().unwrap().value
+            );
+            assert_eq!(Some("Jane Doe"), items[0].authors[0].name.as_deref());
+            assert_eq!("Item 2 ☕", items[1].title.as_ref().unwrap().value);
+            assert_eq!(Some("John Doe"), items[1].authors[0].name.as_deref());
+        }
+    }
+
+    #[test]
+    fn chunked_atom() {
+        let input = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Example</title>
+  <author><name>Jane Doe</name></author>
+  <entry>
+    <id>urn:uuid:1</id>
+    <source><title>Source</title></source>
+  </entry>
+  <entry><id>urn:uuid:2</id></entry>
+</feed>"#;
+
+        let (parser, items) = parse_chunks(Parser::new(), input.as_bytes(), 5);
+        assert_eq!(
+            "Example",
+            parser.feed().unwrap().title.as_ref().unwrap().value
+        );
+        assert_eq!(
+            vec![Some("urn:uuid:1"), Some("urn:uuid:2")],
+            items
+                .iter()
+                .map(|item| item.id.as_deref())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Some("Jane Doe"), items[1].authors[0].name.as_deref());
+    }
+
+    #[test]
+    fn chunked_json() {
+        let input = r#"{
+  "version": "https://jsonfeed.org/version/1.1",
+  "title": "Say \"hi\" [1]",
+  "authors": [{"name": "Jane Doe"}],
+  "items": [
+    {"id": "1", "content_text": "{\"nested\": [1, 2]}"},
+    {"id": "2", "content_html": "<p>Hi</p>", "tags": ["a", "b"]}
+  ],
+  "description": "Ignored after items"
+}"#;
+
+        for len in [1, 3, 16, input.len()] {
+            let (parser, items) = parse_chunks(Parser::new(), input.as_bytes(), len);
+            let feed = parser.feed().unwrap();
+            assert_eq!("Say \"hi\" [1]", feed.title.as_ref().unwrap().value);
+
+            assert_eq!(2, items.len());
+            assert_eq!(Some("1"), items[0].id.as_deref());
+            assert_eq!(
+                "{\"nested\": [1, 2]}",
+                items[0].content.as_ref().unwrap().value
+            );
+            assert_eq!(Some("Jane Doe"), items[0].authors[0].name.as_deref());
+            assert_eq!(Some("2"), items[1].id.as_deref());
+            assert_eq!(2, items[1].categories.len());
+        }
+    }
+
+    #[test]
+    fn discards_parsed_items() {
+        let mut parser = Parser::new();
+        parser.set_max_buffer_len(256);
+
+        parser.push(b"<rss><channel><title>Example</title>");
+        for idx in 0..100 {
+            parser.push(format!("<item><title>Item {idx}</title></item>\n").as_bytes());
+            let item = parser.next_item().unwrap().unwrap();
+            assert_eq!(format!("Item {idx}"), item.title.unwrap().value);
+            assert!(parser.buf.len() < 64);
+        }
+
+        parser.push(b"<item><title>");
+        parser.push(&[b'a'; 256]);
+        assert_eq!(
+            Err(Error::LimitExceeded(readfeed::Ty::Rss)),
+            parser.next_item()
+        );
+        assert!(parser.is_done());
+    }
+
+    #[test]
+    fn errors() {
+        let mut parser = Parser::new();
+        parser.push(b"<!DOCTYPE html><html><body></body></html>");
+        assert_eq!(
+            Err(Error::Unsupported(readfeed::Ty::XmlOrHtml)),
+            parser.next_item()
+        );
+
+        let mut parser = Parser::new();
+        parser.push(b"<rss><channel><item><title>Truncated");
+        assert_eq!(Ok(None), parser.next_item());
+        parser.finish();
+        assert_eq!(Err(Error::Malformed(readfeed::Ty::Rss)), parser.next_item());
+
+        // A truncated header is parsed up to the truncated markup.
+        let mut parser = Parser::new();
+        parser.push(b"<rss version=\"2.0\"><channel><title>t</title><!-");
+        parser.finish();
+        assert_eq!(Ok(None), parser.next_item());
+        assert_eq!("t", parser.feed().unwrap().title.as_ref().unwrap().value);
+
+        let mut parser = Parser::new();
+        parser.push(b"<!-");
+        parser.finish();
+        assert!(parser.next_item().is_err());
+    }
+
+    #[cfg(feature = "encoding")]
+    #[test]
+    fn transcode() {
+        let input = "<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\
+            <rss version=\"2.0\"><channel><title>Caf\u{e9}</title>\
+            <item><title>\u{c9}t\u{e9}</title></item></channel></rss>";
+        let (bytes, _, _) = encoding_rs::WINDOWS_1252.encode(input);
+        for len in [1, 7, bytes.len()] {
+            let (parser, items) = parse_chunks(Parser::new(), &bytes, len);
+            assert_eq!(
+                "Caf\u{e9}",
+                parser.feed().unwrap().title.as_ref().unwrap().value
+            );
+            assert_eq!("\u{c9}t\u{e9}", items[0].title.as_ref().unwrap().value);
+        }
+
+        // Multi-byte sequences are split between chunks.
+        let input = "<?xml version=\"1.0\" encoding=\"Shift_JIS\"?>\
+            <rss version=\"2.0\"><channel><title>\u{65e5}\u{672c}\u{8a9e}</title>\
+            <item><title>\u{65e5}\u{672c}</title></item></channel></rss>";
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(input);
+        let (_, items) = parse_chunks(Parser::new(), &bytes, 3);
+        assert_eq!("\u{65e5}\u{672c}", items[0].title.as_ref().unwrap().value);
+
+        // The charset in the Content-Type header takes precedence.
+        let input = "<rss version=\"2.0\"><channel><title>t</title>\
+            <item><title>\u{41d}\u{43e}\u{432}\u{43e}\u{441}\u{442}\u{438}</title></item></channel></rss>";
+        let (bytes, _, _) = encoding_rs::WINDOWS_1251.encode(input);
+        let mut parser = Parser::new();
+        parser.set_content_type("application/rss+xml; charset=windows-1251");
+        let (_, items) = parse_chunks(parser, &bytes, 4);
+        assert_eq!(
+            "\u{41d}\u{43e}\u{432}\u{43e}\u{441}\u{442}\u{438}",
+            items[0].title.as_ref().unwrap().value
+        );
+
+        let mut bytes = Vec::from(&b"\xFF\xFE"[..]);
+        for unit in
+            "<rss><channel><title>t</title><item><title>\u{65e5}</title></item></channel></rss>"
+                .encode_utf16()
+        {
+            bytes.extend_from_slice(&unit.to_le_bytes());
+        }
+        let (_, items) = parse_chunks(Parser::new(), &bytes, 5);
+        assert_eq!("\u{65e5}", items[0].title.as_ref().unwrap().value);
+    }
+
+    #[test]
+    fn byte_order_mark() {
+        for input in [
+            "\u{feff}<rss><channel><title>Example</title><item><title>Item 1</title></item></channel></rss>",
+            "\u{feff}{\"version\":\"https://jsonfeed.org/version/1.1\",\"title\":\"Example\",\"items\":[{\"id\":\"1\",\"title\":\"Item 1\"}]}",
+        ] {
+            let (parser, items) = parse_chunks(Parser::new(), input.as_bytes(), 2);
+            assert_eq!("Example", parser.feed().unwrap().title.as_ref().unwrap().value);
+            assert_eq!(1, items.len());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn read() {
+        let input = "<rss><channel><title>Example</title><item><title>Item 1</title></item></channel></rss>";
+        let mut iter = Iter::with_reader(io::BufReader::with_capacity(4, input.as_bytes()));
+        let item = iter.next().unwrap().unwrap();
+        assert_eq!("Item 1", item.title.unwrap().value);
+        assert!(iter.feed().is_some());
+        assert!(iter.next().is_none());
+
+        let mut iter = Iter::with_reader("plain text".as_bytes());
+        let error = iter.next().unwrap().unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+        assert!(iter.next().is_none());
+    }
+}
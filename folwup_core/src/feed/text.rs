@@ -0,0 +1,371 @@
+//! Text values as they appear in a feed document.
+//!
+//! Values are borrowed from the input without being unescaped. Use
+//! [`Text::decode()`] or the [`Display`][fmt::Display] implementation to
+//! resolve entities, `CDATA` sections and escape sequences.
+
+use core::fmt;
+
+use alloc::{borrow::Cow, string::String};
+
+use crate::xml;
+
+/// Type of a text construct.
+///
+/// See the [Atom text constructs][text_constructs].
+///
+/// [text_constructs]: https://datatracker.ietf.org/doc/html/rfc4287#section-3.1
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TextTy {
+    /// Plain text.
+    #[default]
+    Text,
+    /// HTML markup.
+    Html,
+    /// XHTML markup.
+    Xhtml,
+}
+
+impl TextTy {
+    /// Maps an Atom `type` attribute value to the text construct type.
+    ///
+    /// Atom `content` elements may also use a MIME media type.
+    #[must_use]
+    pub fn from_atom_ty(ty: &str) -> Self {
+        let ty = ty.trim();
+        if ty.eq_ignore_ascii_case("html") || ty.eq_ignore_ascii_case("text/html") {
+            TextTy::Html
+        } else if ty.eq_ignore_ascii_case("xhtml")
+            || ty.eq_ignore_ascii_case("application/xhtml+xml")
+        {
+            TextTy::Xhtml
+        } else {
+            TextTy::Text
+        }
+    }
+}
+
+/// How a raw value is escaped in the source document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Escaping {
+    /// The value is not escaped.
+    #[default]
+    None,
+    /// XML character content which may contain entity references and `CDATA`
+    /// sections.
+    Xml,
+    /// JSON string content which may contain escape sequences.
+    Json,
+}
+
+/// A text value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Text<'a> {
+    raw: &'a str,
+    ty: TextTy,
+    escaping: Escaping,
+}
+
+impl<'a> Text<'a> {
+    #[inline]
+    #[must_use]
+    pub const fn new(raw: &'a str, ty: TextTy, escaping: Escaping) -> Self {
+        Self { raw, ty, escaping }
+    }
+
+    /// An unescaped plain text value.
+    #[inline]
+    #[must_use]
+    pub const fn plain(value: &'a str) -> Self {
+        Self::new(value, TextTy::Text, Escaping::None)
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) const fn xml(raw: &'a str, ty: TextTy) -> Self {
+        Self::new(raw, ty, Escaping::Xml)
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) const fn json(raw: &'a str, ty: TextTy) -> Self {
+        Self::new(raw, ty, Escaping::Json)
+    }
+
+    /// The value as it appears in the source document.
+    #[inline]
+    #[must_use]
+    pub const fn as_raw(&self) -> &'a str {
+        self.raw
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn ty(&self) -> TextTy {
+        self.ty
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn escaping(&self) -> Escaping {
+        self.escaping
+    }
+
+    /// Returns the unescaped value.
+    ///
+    /// For [`TextTy::Html`] the returned value is HTML markup. For
+    /// [`TextTy::Xhtml`], the returned value is the markup inside of the
+    /// wrapping `div` element as it appears in the document.
+    #[must_use]
+    pub fn decode(&self) -> Cow<'a, str> {
+        let value = self.unwrapped();
+        match self.escaping {
+            Escaping::None => Cow::Borrowed(value),
+            Escaping::Xml if self.ty == TextTy::Xhtml => Cow::Borrowed(value),
+            Escaping::Xml => {
+                if !value.contains(['&', '<']) {
+                    return Cow::Borrowed(value);
+                }
+
+                if let Some(content) = value
+                    .strip_prefix("<![CDATA[")
+                    .and_then(|v| v.strip_suffix("]]>"))
+                {
+                    if !content.contains("]]>") {
+                        return Cow::Borrowed(content);
+                    }
+                }
+
+                let mut output = String::with_capacity(value.len());
+                let _ = write_xml(&mut output, value);
+                Cow::Owned(output)
+            }
+            Escaping::Json => {
+                if !value.contains('\\') {
+                    return Cow::Borrowed(value);
+                }
+
+                let mut output = String::with_capacity(value.len());
+                let _ = write_json(&mut output, value);
+                Cow::Owned(output)
+            }
+        }
+    }
+
+    /// The trimmed raw value with any `xhtml` wrapper removed.
+    #[must_use]
+    fn unwrapped(&self) -> &'a str {
+        let value = match self.escaping {
+            Escaping::None => self.raw,
+            Escaping::Xml | Escaping::Json => self.raw.trim(),
+        };
+
+        if self.ty == TextTy::Xhtml && self.escaping == Escaping::Xml {
+            if let Some(div) = xml::Iter::new(value).next() {
+                return div.content().trim();
+            }
+        }
+
+        value
+    }
+}
+
+impl fmt::Display for Text<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.unwrapped();
+        match self.escaping {
+            Escaping::None => f.write_str(value),
+            Escaping::Xml if self.ty == TextTy::Xhtml => f.write_str(value),
+            Escaping::Xml => write_xml(f, value),
+            Escaping::Json => write_json(f, value),
+        }
+    }
+}
+
+#[must_use]
+fn decode_xml_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            let num = entity.strip_prefix('#')?;
+            let code = if let Some(hex) = num.strip_prefix(['x', 'X']) {
+                u32::from_str_radix(hex, 16).ok()?
+            } else {
+                num.parse::<u32>().ok()?
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+/// Writes XML character content with `CDATA` sections and entity references
+/// resolved.
+///
+/// Unknown entity references are written as is.
+fn write_xml<W: fmt::Write>(w: &mut W, mut value: &str) -> fmt::Result {
+    while let Some(idx) = value.find(['&', '<']) {
+        w.write_str(&value[..idx])?;
+        value = &value[idx..];
+
+        if let Some(rest) = value.strip_prefix("<![CDATA[") {
+            let end = rest.find("]]>").unwrap_or(rest.len());
+            w.write_str(&rest[..end])?;
+            value = rest.get(end + "]]>".len()..).unwrap_or_default();
+            continue;
+        }
+
+        if value.starts_with('&') {
+            if let Some(end) = value.bytes().take(12).position(|b| b == b';') {
+                if let Some(ch) = decode_xml_entity(&value[1..end]) {
+                    w.write_char(ch)?;
+                    value = &value[end + 1..];
+                    continue;
+                }
+            }
+        }
+
+        w.write_char(value.as_bytes()[0].into())?;
+        value = &value[1..];
+    }
+
+    w.write_str(value)
+}
+
+/// Writes JSON string content with escape sequences resolved.
+///
+/// Invalid escape sequences are written as is.
+fn write_json<W: fmt::Write>(w: &mut W, mut value: &str) -> fmt::Result {
+    fn parse_hex(value: &str) -> Option<u32> {
+        let hex = value.get(..4)?;
+        u32::from_str_radix(hex, 16).ok()
+    }
+
+    while let Some(idx) = value.find('\\') {
+        w.write_str(&value[..idx])?;
+        value = &value[idx + 1..];
+
+        let Some(escaped) = value.chars().next() else {
+            return w.write_char('\\');
+        };
+
+        let unescaped = match escaped {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{0008}',
+            'f' => '\u{000C}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                let Some(high) = parse_hex(&value[1..]) else {
+                    w.write_char('\\')?;
+                    continue;
+                };
+                value = &value[5..];
+
+                if (0xD800..0xDC00).contains(&high) {
+                    if let Some(low) = value.strip_prefix("\\u").and_then(parse_hex) {
+                        if (0xDC00..0xE000).contains(&low) {
+                            let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                            w.write_char(
+                                char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER),
+                            )?;
+                            value = &value[6..];
+                            continue;
+                        }
+                    }
+                }
+
+                w.write_char(char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER))?;
+                continue;
+            }
+            _ => {
+                w.write_char('\\')?;
+                continue;
+            }
+        };
+
+        w.write_char(unescaped)?;
+        value = &value[1..];
+    }
+
+    w.write_str(value)
+}
+
+/// An owned text value which has been decoded.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TextBuf {
+    pub value: String,
+    pub ty: TextTy,
+}
+
+impl TextBuf {
+    /// Borrows the value as a [`Text`].
+    #[must_use]
+    pub fn as_text(&self) -> Text<'_> {
+        Text::new(&self.value, self.ty, Escaping::None)
+    }
+}
+
+impl From<Text<'_>> for TextBuf {
+    fn from(value: Text<'_>) -> Self {
+        Self {
+            value: value.decode().into_owned(),
+            ty: value.ty,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_xml() {
+        let text = Text::xml(
+            "  AT&amp;T &#169; &#x263A; &nbsp; &lt;b&gt;  ",
+            TextTy::Text,
+        );
+        assert_eq!("AT&T © ☺ &nbsp; <b>", text.decode());
+
+        let text = Text::xml("<![CDATA[<p>Hello & goodbye</p>]]>", TextTy::Html);
+        assert!(matches!(
+            text.decode(),
+            Cow::Borrowed("<p>Hello & goodbye</p>")
+        ));
+
+        let text = Text::xml("A &amp; <![CDATA[<b>&amp;</b>]]> C", TextTy::Html);
+        assert_eq!("A & <b>&amp;</b> C", text.decode());
+        assert_eq!("A & <b>&amp;</b> C", alloc::format!("{text}"));
+    }
+
+    #[test]
+    fn decode_xhtml() {
+        let text = Text::xml(
+            r#"
+            <div xmlns="http://www.w3.org/1999/xhtml">
+                <p>Hello &amp; <em>world</em></p>
+            </div>
+            "#,
+            TextTy::Xhtml,
+        );
+        assert_eq!("<p>Hello &amp; <em>world</em></p>", text.decode());
+    }
+
+    #[test]
+    fn decode_json() {
+        let text = Text::json(
+            r#"https:\/\/example.com\/ \"\u00e9\" \ud83d\ude00\n"#,
+            TextTy::Text,
+        );
+        assert_eq!("https://example.com/ \"é\" 😀\n", text.decode());
+    }
+}
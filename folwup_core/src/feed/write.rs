@@ -0,0 +1,220 @@
+//! Writers for Atom, RSS and JSON Feed documents.
+//!
+//! The writers take a [`Feed`] and the [`Item`]s to include so a parsed feed
+//! can be filtered or merged and then published again:
+//!
+//! ```
+//! use folwup_core::feed::{self, write};
+//!
+//! let input = r#"<rss version="2.0"><channel><title>Example</title>
+//! <item><title>Keep</title></item><item><title>Skip</title></item>
+//! </channel></rss>"#;
+//!
+//! let feed = feed::parse_feed(input)?;
+//! let items = feed::Iter::with_str(input)?
+//!     .filter(|item| item.title.is_some_and(|title| title.decode() == "Keep"));
+//!
+//! let mut output = String::new();
+//! write::atom::write_feed(&mut output, &feed, items).expect("writing to a String cannot fail");
+//! # Ok::<(), feed::Error>(())
+//! ```
+//!
+//! Values are decoded from their source format and escaped for the output
+//! format. Relative URLs are written as is.
+
+use core::fmt;
+
+use alloc::{borrow::Cow, format, string::String};
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use super::{key::Fnv1a, Feed, Item, ItemKey, Text, TextTy};
+
+pub mod atom;
+pub mod json;
+pub mod rss;
+
+/// Decodes a value which is not blank.
+#[must_use]
+fn decode(text: Option<Text<'_>>) -> Option<Cow<'_, str>> {
+    text.map(|v| v.decode()).filter(|v| !v.trim().is_empty())
+}
+
+/// Whether a character is allowed in an XML 1.0 document.
+#[must_use]
+fn is_xml_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}')
+        || c >= '\u{10000}'
+}
+
+/// Escapes a value for XML character content or a quoted attribute value.
+///
+/// Characters which are not allowed in XML documents are removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct XmlEscaped<'a>(pub(crate) &'a str);
+
+impl fmt::Display for XmlEscaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut value = self.0;
+        while let Some(idx) = value.find(|c| matches!(c, '&' | '<' | '>' | '"') || !is_xml_char(c))
+        {
+            f.write_str(&value[..idx])?;
+            let c = value[idx..].chars().next().unwrap_or_default();
+            match c {
+                '&' => f.write_str("&amp;")?,
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '"' => f.write_str("&quot;")?,
+                _ => {}
+            }
+            value = &value[idx + c.len_utf8()..];
+        }
+        f.write_str(value)
+    }
+}
+
+/// Escapes plain text so it can be used as HTML markup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct HtmlEscaped<'a>(&'a str);
+
+impl fmt::Display for HtmlEscaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut value = self.0;
+        while let Some(idx) = value.find(['&', '<', '>', '"']) {
+            f.write_str(&value[..idx])?;
+            f.write_str(match value.as_bytes()[idx] {
+                b'&' => "&amp;",
+                b'<' => "&lt;",
+                b'>' => "&gt;",
+                _ => "&quot;",
+            })?;
+            value = &value[idx + 1..];
+        }
+        f.write_str(value)
+    }
+}
+
+/// Escapes a value as a quoted JSON string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct JsonString<'a>(&'a str);
+
+impl fmt::Display for JsonString<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("\"")?;
+        let mut value = self.0;
+        while let Some(idx) = value.find(|c: char| matches!(c, '"' | '\\') || c.is_control()) {
+            f.write_str(&value[..idx])?;
+            let c = value[idx..].chars().next().unwrap_or_default();
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if u32::from(c) < 0x20 => write!(f, "\\u{:04x}", u32::from(c))?,
+                c => write!(f, "{c}")?,
+            }
+            value = &value[idx + c.len_utf8()..];
+        }
+        f.write_str(value)?;
+        f.write_str("\"")
+    }
+}
+
+/// The value of a text construct as HTML markup.
+#[must_use]
+fn to_html(text: Text<'_>) -> Cow<'_, str> {
+    let value = text.decode();
+    match text.ty() {
+        TextTy::Html | TextTy::Xhtml => value,
+        TextTy::Text => {
+            if value.contains(['&', '<', '>', '"']) {
+                Cow::Owned(format!("{}", HtmlEscaped(&value)))
+            } else {
+                value
+            }
+        }
+    }
+}
+
+/// Formats a date as an [RFC 3339][rfc3339] timestamp such as
+/// `2003-12-13T18:30:02Z`.
+///
+/// [rfc3339]: https://datatracker.ietf.org/doc/html/rfc3339
+#[must_use]
+fn rfc_3339(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// The most recent date of the items.
+#[must_use]
+fn latest_date<'a, 'b: 'a>(items: impl IntoIterator<Item = &'a Item<'b>>) -> Option<DateTime<Utc>> {
+    items
+        .into_iter()
+        .filter_map(|item| item.modified_at.or(item.published_at))
+        .max()
+}
+
+/// A unique identifier for the feed.
+///
+/// The feed's URL or home page is preferred. Otherwise an identifier derived
+/// from the title is used.
+#[must_use]
+fn feed_id(feed: &Feed<'_>) -> String {
+    if let Some(url) = decode(feed.feed_url)
+        .or_else(|| decode(feed.alternate_url))
+        .or_else(|| decode(feed.home_page_url))
+    {
+        return String::from(url.trim());
+    }
+
+    let mut hasher = Fnv1a::new();
+    if let Some(title) = decode(feed.title) {
+        hasher.write(title.trim().as_bytes());
+    }
+    format!("urn:folwup:feed:{:016x}", hasher.finish())
+}
+
+/// A unique identifier for the item derived from its [key][Item::key()].
+#[must_use]
+fn item_id(item: &Item<'_>) -> String {
+    match item.key() {
+        ItemKey::Id(id) => id,
+        ItemKey::Url(url) => url,
+        ItemKey::Hash(hash) => format!("urn:folwup:item:{hash:016x}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn escape_xml() {
+        assert_eq!(
+            "AT&amp;T &lt;b&gt; &quot;quoted&quot; 'single'  ☺",
+            XmlEscaped("AT&T <b> \"quoted\" 'single' \u{0} ☺").to_string()
+        );
+    }
+
+    #[test]
+    fn escape_json() {
+        assert_eq!(
+            r#""a \"b\" \\ \n \u0001 é""#,
+            JsonString("a \"b\" \\ \n \u{1} é").to_string()
+        );
+    }
+
+    #[test]
+    fn html() {
+        assert_eq!(
+            "1 &lt; 2 &amp; 3",
+            to_html(Text::plain("1 < 2 & 3")).as_ref()
+        );
+        assert_eq!(
+            "<p>Hi</p>",
+            to_html(Text::xml("&lt;p&gt;Hi&lt;/p&gt;", TextTy::Html)).as_ref()
+        );
+    }
+}
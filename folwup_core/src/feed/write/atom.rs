@@ -0,0 +1,331 @@
+//! Writes [Atom 1.0][rfc4287] documents.
+//!
+//! [rfc4287]: https://datatracker.ietf.org/doc/html/rfc4287
+
+use core::fmt;
+
+use alloc::vec::Vec;
+
+use crate::feed::{Author, Category, Feed, Item, Text, TextTy};
+
+use super::{decode, feed_id, item_id, latest_date, rfc_3339, XmlEscaped};
+
+/// Writes a text construct element.
+///
+/// HTML and XHTML values are written as escaped HTML.
+fn write_text<W: fmt::Write + ?Sized>(
+    w: &mut W,
+    indent: &str,
+    name: &str,
+    text: Text<'_>,
+) -> fmt::Result {
+    let ty = match text.ty() {
+        TextTy::Text => "",
+        TextTy::Html | TextTy::Xhtml => r#" type="html""#,
+    };
+    writeln!(
+        w,
+        "{indent}<{name}{ty}>{}</{name}>",
+        XmlEscaped(&text.decode())
+    )
+}
+
+fn write_person<W: fmt::Write + ?Sized>(
+    w: &mut W,
+    indent: &str,
+    name: &str,
+    person: &Author<'_>,
+) -> fmt::Result {
+    // A name is required so fallback to the email address.
+    let email = decode(person.email);
+    let person_name = decode(person.name).or_else(|| email.clone());
+
+    writeln!(w, "{indent}<{name}>")?;
+    writeln!(
+        w,
+        "{indent}  <name>{}</name>",
+        XmlEscaped(person_name.as_deref().unwrap_or_default())
+    )?;
+    if let Some(email) = email {
+        writeln!(w, "{indent}  <email>{}</email>", XmlEscaped(&email))?;
+    }
+    if let Some(url) = decode(person.url) {
+        writeln!(w, "{indent}  <uri>{}</uri>", XmlEscaped(&url))?;
+    }
+    writeln!(w, "{indent}</{name}>")
+}
+
+fn write_category<W: fmt::Write + ?Sized>(
+    w: &mut W,
+    indent: &str,
+    category: &Category<'_>,
+) -> fmt::Result {
+    write!(
+        w,
+        r#"{indent}<category term="{}""#,
+        XmlEscaped(&category.term.decode())
+    )?;
+    if let Some(scheme) = decode(category.scheme) {
+        write!(w, r#" scheme="{}""#, XmlEscaped(&scheme))?;
+    }
+    if let Some(label) = decode(category.label) {
+        write!(w, r#" label="{}""#, XmlEscaped(&label))?;
+    }
+    w.write_str("/>\n")
+}
+
+fn write_entry<W: fmt::Write + ?Sized>(
+    w: &mut W,
+    feed: &Feed<'_>,
+    item: &Item<'_>,
+    feed_updated: &str,
+) -> fmt::Result {
+    w.write_str("  <entry>\n")?;
+    writeln!(w, "    <id>{}</id>", XmlEscaped(&item_id(item)))?;
+    write_text(w, "    ", "title", item.title.unwrap_or_default())?;
+
+    // The first link without a `rel` is the entry's alternate link.
+    let url = decode(item.url);
+    if let Some(url) = &url {
+        writeln!(w, r#"    <link href="{}"/>"#, XmlEscaped(url))?;
+    }
+    if let Some(external_url) = decode(item.external_url).filter(|v| Some(v) != url.as_ref()) {
+        writeln!(
+            w,
+            r#"    <link rel="related" href="{}"/>"#,
+            XmlEscaped(&external_url)
+        )?;
+    }
+    for attachment in &item.attachments {
+        write!(
+            w,
+            r#"    <link rel="enclosure" href="{}""#,
+            XmlEscaped(&attachment.url.decode())
+        )?;
+        if let Some(mime_type) = decode(attachment.mime_type) {
+            write!(w, r#" type="{}""#, XmlEscaped(&mime_type))?;
+        }
+        if let Some(size_in_bytes) = attachment.size_in_bytes {
+            write!(w, r#" length="{size_in_bytes}""#)?;
+        }
+        if let Some(title) = decode(attachment.title) {
+            write!(w, r#" title="{}""#, XmlEscaped(&title))?;
+        }
+        w.write_str("/>\n")?;
+    }
+
+    if let Some(published_at) = item.published_at {
+        writeln!(w, "    <published>{}</published>", rfc_3339(published_at))?;
+    }
+    match item.modified_at.or(item.published_at) {
+        Some(updated) => writeln!(w, "    <updated>{}</updated>", rfc_3339(updated))?,
+        None => writeln!(w, "    <updated>{feed_updated}</updated>")?,
+    }
+
+    // Entries without authors inherit the feed's authors.
+    if item.authors != feed.authors {
+        for author in &item.authors {
+            write_person(w, "    ", "author", author)?;
+        }
+    }
+    for contributor in &item.contributors {
+        write_person(w, "    ", "contributor", contributor)?;
+    }
+    for category in &item.categories {
+        write_category(w, "    ", category)?;
+    }
+
+    if let Some(summary) = item.summary {
+        write_text(w, "    ", "summary", summary)?;
+    }
+    if let Some(content) = item.content {
+        write_text(w, "    ", "content", content)?;
+    }
+
+    w.write_str("  </entry>\n")
+}
+
+/// Writes the feed and items as an Atom document.
+///
+/// Required elements are always written. Missing titles are written as empty
+/// elements, and missing `updated` dates fallback to the most recent item
+/// date. A missing entry `id` is derived from the item's
+/// [key][Item::key()].
+///
+/// Atom requires an `author` for every entry. If neither the feed nor an
+/// item has an author, the document will not be valid.
+///
+/// # Errors
+///
+/// Returns an error if the writer returns an error.
+pub fn write_feed<'a, W, I>(w: &mut W, feed: &Feed<'_>, items: I) -> fmt::Result
+where
+    W: fmt::Write + ?Sized,
+    I: IntoIterator<Item = Item<'a>>,
+{
+    // The feed's `updated` date may depend on the items.
+    let items = items.into_iter().collect::<Vec<_>>();
+    let updated = rfc_3339(
+        feed.modified_at
+            .or_else(|| latest_date(&items))
+            .unwrap_or_default(),
+    );
+
+    w.write_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n")?;
+    w.write_str(r#"<feed xmlns="http://www.w3.org/2005/Atom""#)?;
+    if let Some(language) = decode(feed.language) {
+        write!(w, r#" xml:lang="{}""#, XmlEscaped(language.trim()))?;
+    }
+    w.write_str(">\n")?;
+
+    writeln!(w, "  <id>{}</id>", XmlEscaped(&feed_id(feed)))?;
+    write_text(w, "  ", "title", feed.title.unwrap_or_default())?;
+    if let Some(description) = feed.description {
+        write_text(w, "  ", "subtitle", description)?;
+    }
+    if let Some(url) = decode(feed.alternate_url).or_else(|| decode(feed.home_page_url)) {
+        writeln!(
+            w,
+            r#"  <link rel="alternate" type="text/html" href="{}"/>"#,
+            XmlEscaped(&url)
+        )?;
+    }
+    if let Some(url) = decode(feed.feed_url) {
+        writeln!(
+            w,
+            r#"  <link rel="self" type="application/atom+xml" href="{}"/>"#,
+            XmlEscaped(&url)
+        )?;
+    }
+    writeln!(w, "  <updated>{updated}</updated>")?;
+
+    for author in &feed.authors {
+        write_person(w, "  ", "author", author)?;
+    }
+    for contributor in &feed.contributors {
+        write_person(w, "  ", "contributor", contributor)?;
+    }
+    for category in &feed.categories {
+        write_category(w, "  ", category)?;
+    }
+    if let Some(generator) = &feed.generator {
+        w.write_str("  <generator")?;
+        if let Some(url) = decode(generator.url) {
+            write!(w, r#" uri="{}""#, XmlEscaped(&url))?;
+        }
+        if let Some(version) = decode(generator.version) {
+            write!(w, r#" version="{}""#, XmlEscaped(&version))?;
+        }
+        writeln!(w, ">{}</generator>", XmlEscaped(&generator.name.decode()))?;
+    }
+    if let Some(icon) = decode(feed.icon) {
+        writeln!(w, "  <icon>{}</icon>", XmlEscaped(&icon))?;
+    }
+    if let Some(logo) = decode(feed.logo) {
+        writeln!(w, "  <logo>{}</logo>", XmlEscaped(&logo))?;
+    }
+    if let Some(rights) = feed.rights {
+        write_text(w, "  ", "rights", rights)?;
+    }
+
+    for item in &items {
+        write_entry(w, feed, item, &updated)?;
+    }
+
+    w.write_str("</feed>\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::{String, ToString};
+
+    use crate::feed::{self, Iter};
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+  <channel>
+    <title>Tom &amp; Jerry</title>
+    <link>https://example.com/</link>
+    <description>Cartoons</description>
+    <language>en-us</language>
+    <managingEditor>editor@example.com (Jane Doe)</managingEditor>
+    <lastBuildDate>Wed, 24 Feb 2021 09:08:10 GMT</lastBuildDate>
+    <item>
+      <title>1 &lt; 2</title>
+      <link>https://example.com/1</link>
+      <description>&lt;p&gt;Summary&lt;/p&gt;</description>
+      <content:encoded><![CDATA[<p>Content & more</p>]]></content:encoded>
+      <guid isPermaLink="false">item-1</guid>
+      <pubDate>Tue, 23 Feb 2021 09:08:10 GMT</pubDate>
+      <category domain="https://example.com/tags">Rust</category>
+      <enclosure url="https://example.com/1.mp3" length="1024" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Item 2</title>
+    </item>
+  </channel>
+</rss>
+"#;
+
+        let feed = feed::parse_feed(input).unwrap();
+        let mut output = String::new();
+        write_feed(&mut output, &feed, Iter::with_str(input).unwrap()).unwrap();
+
+        let written = feed::parse_feed(&output).unwrap();
+        assert_eq!(
+            Some("Tom & Jerry"),
+            written.title.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(
+            Some("https://example.com/"),
+            written.home_page_url.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(
+            Some("en-us"),
+            written.language.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(feed.modified_at, written.modified_at);
+        assert_eq!(1, written.authors.len());
+        assert_eq!(
+            Some("Jane Doe"),
+            written.authors[0].name.map(|v| v.to_string()).as_deref()
+        );
+
+        let mut iter = Iter::with_str(&output).unwrap();
+        let item = iter.next().unwrap();
+        assert_eq!(Some("item-1"), item.id.map(|v| v.to_string()).as_deref());
+        assert_eq!(Some("1 < 2"), item.title.map(|v| v.to_string()).as_deref());
+        assert_eq!(
+            Some("https://example.com/1"),
+            item.url.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(
+            Some("<p>Summary</p>"),
+            item.summary.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(Some(TextTy::Html), item.content.map(|v| v.ty()));
+        assert_eq!(
+            Some("<p>Content & more</p>"),
+            item.content.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!("2021-02-23T09:08:10Z".parse().ok(), item.published_at);
+        assert_eq!(1, item.categories.len());
+        assert_eq!(
+            Some("https://example.com/tags"),
+            item.categories[0].scheme.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(1, item.attachments.len());
+        assert_eq!(Some(1024), item.attachments[0].size_in_bytes);
+        assert_eq!(1, item.authors.len());
+
+        let item = iter.next().unwrap();
+        assert!(item.id.is_some());
+        assert_eq!("2021-02-24T09:08:10Z".parse().ok(), item.modified_at);
+
+        assert!(iter.next().is_none());
+    }
+}
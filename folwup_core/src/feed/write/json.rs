@@ -0,0 +1,299 @@
+//! Writes [JSON Feed 1.1][json_feed] documents.
+//!
+//! [json_feed]: https://www.jsonfeed.org/version/1.1/
+
+use core::fmt;
+
+use crate::feed::{Attachment, Author, Feed, Item, Text, TextTy};
+
+use super::{decode, item_id, rfc_3339, JsonString};
+
+/// Writes the members of a JSON object.
+struct ObjectWriter<'w, W: ?Sized> {
+    w: &'w mut W,
+    indent: &'static str,
+    is_empty: bool,
+}
+
+impl<'w, W: fmt::Write + ?Sized> ObjectWriter<'w, W> {
+    /// Writes the opening brace of an object whose members are indented by
+    /// `indent`.
+    fn begin(w: &'w mut W, indent: &'static str) -> Result<Self, fmt::Error> {
+        w.write_char('{')?;
+        Ok(Self {
+            w,
+            indent,
+            is_empty: true,
+        })
+    }
+
+    /// Writes the member name and returns the writer for the value.
+    fn key(&mut self, key: &str) -> Result<&mut W, fmt::Error> {
+        if !self.is_empty {
+            self.w.write_char(',')?;
+        }
+        self.is_empty = false;
+        write!(self.w, "\n{}{}: ", self.indent, JsonString(key))?;
+        Ok(self.w)
+    }
+
+    fn string(&mut self, key: &str, value: &str) -> fmt::Result {
+        let w = self.key(key)?;
+        write!(w, "{}", JsonString(value))
+    }
+
+    fn opt_text(&mut self, key: &str, value: Option<Text<'_>>) -> fmt::Result {
+        match decode(value) {
+            Some(value) => self.string(key, &value),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes the closing brace of the object.
+    fn end(self) -> fmt::Result {
+        if !self.is_empty {
+            let outer = &self.indent[..self.indent.len().saturating_sub(2)];
+            write!(self.w, "\n{outer}")?;
+        }
+        self.w.write_char('}')
+    }
+}
+
+fn write_authors<W: fmt::Write + ?Sized>(
+    object: &mut ObjectWriter<'_, W>,
+    indent: &'static str,
+    authors: &[Author<'_>],
+) -> fmt::Result {
+    if authors.is_empty() {
+        return Ok(());
+    }
+
+    let w = object.key("authors")?;
+    w.write_char('[')?;
+    for (idx, author) in authors.iter().enumerate() {
+        if idx > 0 {
+            w.write_char(',')?;
+        }
+        let mut author_object = ObjectWriter::begin(&mut *w, indent)?;
+        author_object.opt_text("name", author.name)?;
+        // There is no email member so use a `mailto` URL if there is no URL.
+        match (decode(author.url), decode(author.email)) {
+            (Some(url), _) => author_object.string("url", &url)?,
+            (None, Some(email)) => {
+                author_object.string("url", &alloc::format!("mailto:{}", email.trim()))?;
+            }
+            (None, None) => {}
+        }
+        author_object.opt_text("avatar", author.avatar)?;
+        author_object.end()?;
+    }
+    w.write_char(']')
+}
+
+fn write_attachment<W: fmt::Write + ?Sized>(w: &mut W, attachment: &Attachment<'_>) -> fmt::Result {
+    let mut object = ObjectWriter::begin(w, "        ")?;
+    object.string("url", &attachment.url.decode())?;
+    object.string(
+        "mime_type",
+        decode(attachment.mime_type)
+            .as_deref()
+            .unwrap_or("application/octet-stream"),
+    )?;
+    object.opt_text("title", attachment.title)?;
+    if let Some(size_in_bytes) = attachment.size_in_bytes {
+        write!(object.key("size_in_bytes")?, "{size_in_bytes}")?;
+    }
+    if let Some(duration) = attachment.duration {
+        write!(
+            object.key("duration_in_seconds")?,
+            "{}",
+            duration.as_secs_f64()
+        )?;
+    }
+    object.end()
+}
+
+fn write_item<W: fmt::Write + ?Sized>(w: &mut W, feed: &Feed<'_>, item: &Item<'_>) -> fmt::Result {
+    let mut object = ObjectWriter::begin(w, "      ")?;
+    object.string("id", &item_id(item))?;
+    object.opt_text("url", item.url)?;
+    if decode(item.external_url) != decode(item.url) {
+        object.opt_text("external_url", item.external_url)?;
+    }
+    object.opt_text("title", item.title)?;
+
+    // Either `content_html` or `content_text` is required.
+    let content = item.content.or(item.summary).unwrap_or_default();
+    match content.ty() {
+        TextTy::Text => object.string("content_text", &content.decode())?,
+        TextTy::Html | TextTy::Xhtml => object.string("content_html", &content.decode())?,
+    }
+    if item.content.is_some() {
+        object.opt_text("summary", item.summary)?;
+    }
+    object.opt_text("image", item.podcast.image)?;
+
+    if let Some(published_at) = item.published_at {
+        object.string("date_published", &rfc_3339(published_at))?;
+    }
+    if let Some(modified_at) = item.modified_at {
+        object.string("date_modified", &rfc_3339(modified_at))?;
+    }
+
+    // Items without authors inherit the feed's authors.
+    if item.authors != feed.authors {
+        write_authors(&mut object, "        ", &item.authors)?;
+    }
+
+    if !item.categories.is_empty() {
+        let w = object.key("tags")?;
+        w.write_char('[')?;
+        for (idx, category) in item.categories.iter().enumerate() {
+            if idx > 0 {
+                w.write_str(", ")?;
+            }
+            write!(w, "{}", JsonString(&category.term.decode()))?;
+        }
+        w.write_char(']')?;
+    }
+
+    if !item.attachments.is_empty() {
+        let w = object.key("attachments")?;
+        w.write_char('[')?;
+        for (idx, attachment) in item.attachments.iter().enumerate() {
+            if idx > 0 {
+                w.write_char(',')?;
+            }
+            write_attachment(w, attachment)?;
+        }
+        w.write_char(']')?;
+    }
+
+    object.end()
+}
+
+/// Writes the feed and items as a JSON Feed document.
+///
+/// The required `title` member is always written. Items without content use
+/// the summary (or an empty string) as the content, and a missing item `id`
+/// is derived from the item's [key][Item::key()].
+///
+/// # Errors
+///
+/// Returns an error if the writer returns an error.
+pub fn write_feed<'a, W, I>(w: &mut W, feed: &Feed<'_>, items: I) -> fmt::Result
+where
+    W: fmt::Write + ?Sized,
+    I: IntoIterator<Item = Item<'a>>,
+{
+    let mut object = ObjectWriter::begin(&mut *w, "  ")?;
+    object.string("version", "https://jsonfeed.org/version/1.1")?;
+    object.string("title", decode(feed.title).as_deref().unwrap_or_default())?;
+    object.opt_text("home_page_url", feed.alternate_url.or(feed.home_page_url))?;
+    object.opt_text("feed_url", feed.feed_url)?;
+    object.opt_text("description", feed.description)?;
+    object.opt_text("icon", feed.logo)?;
+    object.opt_text("favicon", feed.icon)?;
+    write_authors(&mut object, "    ", &feed.authors)?;
+    object.opt_text("language", feed.language)?;
+
+    let items_w = object.key("items")?;
+    items_w.write_char('[')?;
+    let mut is_empty = true;
+    for item in items {
+        items_w.write_str(if is_empty { "\n    " } else { ",\n    " })?;
+        is_empty = false;
+        write_item(items_w, feed, &item)?;
+    }
+    if !is_empty {
+        items_w.write_str("\n  ")?;
+    }
+    items_w.write_char(']')?;
+
+    object.end()?;
+    w.write_char('\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::{String, ToString};
+
+    use crate::feed::{self, Iter};
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Say "hello"</title>
+    <link>https://example.com/</link>
+    <description>Line 1
+Line 2</description>
+    <item>
+      <title>Episode 1</title>
+      <link>https://example.com/1</link>
+      <description>&lt;p&gt;Hello \ world&lt;/p&gt;</description>
+      <pubDate>Tue, 23 Feb 2021 09:08:10 GMT</pubDate>
+      <author>john@example.com (John Doe)</author>
+      <category>Rust</category>
+      <category>Podcast</category>
+      <enclosure url="https://example.com/1.mp3" length="1024" type="audio/mpeg"/>
+    </item>
+    <item>
+      <description>No title</description>
+    </item>
+  </channel>
+</rss>
+"#;
+
+        let feed = feed::parse_feed(input).unwrap();
+        let mut output = String::new();
+        write_feed(&mut output, &feed, Iter::with_str(input).unwrap()).unwrap();
+
+        let written = feed::parse_feed(&output).unwrap();
+        assert_eq!(
+            Some("Say \"hello\""),
+            written.title.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(
+            Some("Line 1\nLine 2"),
+            written.description.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(
+            Some("https://example.com/"),
+            written.home_page_url.map(|v| v.to_string()).as_deref()
+        );
+
+        let mut iter = Iter::with_str(&output).unwrap();
+        let item = iter.next().unwrap();
+        assert_eq!(
+            Some("https://example.com/1"),
+            item.id.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(
+            Some("<p>Hello \\ world</p>"),
+            item.content.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!("2021-02-23T09:08:10Z".parse().ok(), item.published_at);
+        assert_eq!(
+            Some("John Doe"),
+            item.authors[0].name.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(2, item.categories.len());
+        assert_eq!(1, item.attachments.len());
+        assert_eq!(Some(1024), item.attachments[0].size_in_bytes);
+
+        let item = iter.next().unwrap();
+        assert!(item
+            .id
+            .is_some_and(|id| id.to_string().starts_with("urn:folwup:item:")));
+        assert_eq!(
+            Some("No title"),
+            item.content.map(|v| v.to_string()).as_deref()
+        );
+
+        assert!(iter.next().is_none());
+    }
+}
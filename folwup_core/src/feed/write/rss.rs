@@ -0,0 +1,413 @@
+//! Writes [RSS 2.0][rss] documents.
+//!
+//! [rss]: https://www.rssboard.org/rss-specification
+
+use core::{fmt, time::Duration};
+
+use chrono::{DateTime, Utc, Weekday};
+
+use crate::{
+    feed::{Author, Category, Feed, Item},
+    url,
+};
+
+use super::{decode, to_html, XmlEscaped};
+
+#[must_use]
+fn rfc_2822(value: DateTime<Utc>) -> impl fmt::Display {
+    value.format("%a, %d %b %Y %H:%M:%S +0000")
+}
+
+#[must_use]
+const fn day_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+/// Formats a duration as `HH:MM:SS` for `itunes:duration`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ItunesDuration(Duration);
+
+impl fmt::Display for ItunesDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let secs = self.0.as_secs();
+        write!(
+            f,
+            "{:02}:{:02}:{:02}",
+            secs / 3600,
+            secs / 60 % 60,
+            secs % 60
+        )
+    }
+}
+
+/// Writes authors as `author` (or `managingEditor`) elements if they have an
+/// email address and as `dc:creator` elements otherwise.
+fn write_people<W: fmt::Write + ?Sized>(
+    w: &mut W,
+    indent: &str,
+    name: &str,
+    people: &[Author<'_>],
+) -> fmt::Result {
+    for person in people {
+        let person_name = decode(person.name);
+        if let Some(email) = decode(person.email) {
+            write!(w, "{indent}<{name}>{}", XmlEscaped(email.trim()))?;
+            if let Some(person_name) = person_name {
+                write!(w, " ({})", XmlEscaped(person_name.trim()))?;
+            }
+            writeln!(w, "</{name}>")?;
+        } else if let Some(person_name) = person_name {
+            writeln!(
+                w,
+                "{indent}<dc:creator>{}</dc:creator>",
+                XmlEscaped(&person_name)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn write_categories<W: fmt::Write + ?Sized>(
+    w: &mut W,
+    indent: &str,
+    categories: &[Category<'_>],
+) -> fmt::Result {
+    for category in categories {
+        write!(w, "{indent}<category")?;
+        if let Some(scheme) = decode(category.scheme) {
+            write!(w, r#" domain="{}""#, XmlEscaped(&scheme))?;
+        }
+        writeln!(w, ">{}</category>", XmlEscaped(&category.term.decode()))?;
+    }
+    Ok(())
+}
+
+fn write_item<W: fmt::Write + ?Sized>(w: &mut W, feed: &Feed<'_>, item: &Item<'_>) -> fmt::Result {
+    w.write_str("    <item>\n")?;
+
+    // Either a title or a description is required.
+    let title = decode(item.title);
+    if title.is_some() || (item.summary.is_none() && item.content.is_none()) {
+        writeln!(
+            w,
+            "      <title>{}</title>",
+            XmlEscaped(title.as_deref().unwrap_or_default())
+        )?;
+    }
+    if let Some(url) = decode(item.url) {
+        writeln!(w, "      <link>{}</link>", XmlEscaped(&url))?;
+    }
+
+    // The description is usually a summary. If there is no summary, the
+    // content is the description.
+    let (description, content) = match (item.summary, item.content) {
+        (Some(summary), content) => (Some(summary), content),
+        (None, content) => (content, None),
+    };
+    if let Some(description) = description {
+        writeln!(
+            w,
+            "      <description>{}</description>",
+            XmlEscaped(&to_html(description))
+        )?;
+    }
+    if let Some(content) = content {
+        writeln!(
+            w,
+            "      <content:encoded>{}</content:encoded>",
+            XmlEscaped(&to_html(content))
+        )?;
+    }
+
+    // Items without authors inherit the channel's authors.
+    if item.authors != feed.authors {
+        write_people(w, "      ", "author", &item.authors)?;
+    }
+    write_categories(w, "      ", &item.categories)?;
+
+    // Only a single enclosure is allowed.
+    if let Some(attachment) = item.attachments.first() {
+        writeln!(
+            w,
+            r#"      <enclosure url="{}" length="{}" type="{}"/>"#,
+            XmlEscaped(&attachment.url.decode()),
+            attachment.size_in_bytes.unwrap_or_default(),
+            XmlEscaped(
+                decode(attachment.mime_type)
+                    .as_deref()
+                    .unwrap_or("application/octet-stream")
+            )
+        )?;
+    }
+
+    if let Some(id) = decode(item.id) {
+        let id = id.trim();
+        if item.id_is_permalink && url::is_absolute(id) {
+            writeln!(w, "      <guid>{}</guid>", XmlEscaped(id))?;
+        } else {
+            writeln!(
+                w,
+                r#"      <guid isPermaLink="false">{}</guid>"#,
+                XmlEscaped(id)
+            )?;
+        }
+    }
+
+    if let Some(published_at) = item.published_at.or(item.modified_at) {
+        writeln!(w, "      <pubDate>{}</pubDate>", rfc_2822(published_at))?;
+    }
+
+    let podcast = &item.podcast;
+    if let Some(duration) = podcast.duration {
+        writeln!(
+            w,
+            "      <itunes:duration>{}</itunes:duration>",
+            ItunesDuration(duration)
+        )?;
+    }
+    if let Some(episode) = podcast.episode {
+        writeln!(w, "      <itunes:episode>{episode}</itunes:episode>")?;
+    }
+    if let Some(season) = podcast.season {
+        writeln!(w, "      <itunes:season>{season}</itunes:season>")?;
+    }
+    if let Some(explicit) = podcast.explicit {
+        writeln!(w, "      <itunes:explicit>{explicit}</itunes:explicit>")?;
+    }
+    if let Some(image) = decode(podcast.image) {
+        writeln!(w, r#"      <itunes:image href="{}"/>"#, XmlEscaped(&image))?;
+    }
+
+    w.write_str("    </item>\n")
+}
+
+/// Writes the feed and items as an RSS 2.0 document.
+///
+/// The channel's required `title`, `link` and `description` elements are
+/// always written even if the values are missing. Item content is written in
+/// a `content:encoded` element when the item also has a summary, and only the
+/// first attachment is written as an `enclosure`.
+///
+/// # Errors
+///
+/// Returns an error if the writer returns an error.
+#[allow(clippy::too_many_lines)]
+pub fn write_feed<'a, W, I>(w: &mut W, feed: &Feed<'_>, items: I) -> fmt::Result
+where
+    W: fmt::Write + ?Sized,
+    I: IntoIterator<Item = Item<'a>>,
+{
+    w.write_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n")?;
+    w.write_str(concat!(
+        r#"<rss version="2.0""#,
+        r#" xmlns:atom="http://www.w3.org/2005/Atom""#,
+        r#" xmlns:content="http://purl.org/rss/1.0/modules/content/""#,
+        r#" xmlns:dc="http://purl.org/dc/elements/1.1/""#,
+        r#" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">"#,
+        "\n",
+        "  <channel>\n",
+    ))?;
+
+    let title = decode(feed.title);
+    let link = decode(feed.alternate_url).or_else(|| decode(feed.home_page_url));
+    writeln!(
+        w,
+        "    <title>{}</title>",
+        XmlEscaped(title.as_deref().unwrap_or_default())
+    )?;
+    writeln!(
+        w,
+        "    <link>{}</link>",
+        XmlEscaped(link.as_deref().unwrap_or_default())
+    )?;
+    writeln!(
+        w,
+        "    <description>{}</description>",
+        XmlEscaped(decode(feed.description).as_deref().unwrap_or_default())
+    )?;
+    if let Some(url) = decode(feed.feed_url) {
+        writeln!(
+            w,
+            r#"    <atom:link rel="self" type="application/rss+xml" href="{}"/>"#,
+            XmlEscaped(&url)
+        )?;
+    }
+
+    if let Some(language) = decode(feed.language) {
+        writeln!(
+            w,
+            "    <language>{}</language>",
+            XmlEscaped(language.trim())
+        )?;
+    }
+    if let Some(rights) = decode(feed.rights) {
+        writeln!(w, "    <copyright>{}</copyright>", XmlEscaped(&rights))?;
+    }
+    write_people(w, "    ", "managingEditor", &feed.authors)?;
+    if let Some(modified_at) = feed.modified_at {
+        writeln!(
+            w,
+            "    <lastBuildDate>{}</lastBuildDate>",
+            rfc_2822(modified_at)
+        )?;
+    }
+    write_categories(w, "    ", &feed.categories)?;
+    if let Some(generator) = &feed.generator {
+        writeln!(
+            w,
+            "    <generator>{}</generator>",
+            XmlEscaped(&generator.name.decode())
+        )?;
+    }
+    if let Some(ttl) = feed.ttl {
+        writeln!(w, "    <ttl>{}</ttl>", ttl.as_secs().div_ceil(60))?;
+    }
+    if let Some(logo) = decode(feed.logo) {
+        writeln!(
+            w,
+            "    <image>\n      <url>{}</url>\n      <title>{}</title>\n      <link>{}</link>\n    </image>",
+            XmlEscaped(&logo),
+            XmlEscaped(title.as_deref().unwrap_or_default()),
+            XmlEscaped(link.as_deref().unwrap_or_default()),
+        )?;
+    }
+    if !feed.skip_hours.is_empty() {
+        w.write_str("    <skipHours>\n")?;
+        for hour in feed.skip_hours.iter() {
+            writeln!(w, "      <hour>{hour}</hour>")?;
+        }
+        w.write_str("    </skipHours>\n")?;
+    }
+    if !feed.skip_days.is_empty() {
+        w.write_str("    <skipDays>\n")?;
+        for day in feed.skip_days.iter() {
+            writeln!(w, "      <day>{}</day>", day_name(day))?;
+        }
+        w.write_str("    </skipDays>\n")?;
+    }
+
+    for item in items {
+        write_item(w, feed, &item)?;
+    }
+
+    w.write_str("  </channel>\n</rss>\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::{String, ToString};
+
+    use crate::feed::{self, Iter, TextTy};
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let input = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en">
+    <title>Tom &amp; Jerry</title>
+    <link href="https://example.com/"/>
+    <link rel="self" href="https://example.com/feed.xml"/>
+    <id>urn:uuid:60a76c80-d399-11d9-b93c-0003939e0af6</id>
+    <updated>2021-02-24T09:08:10Z</updated>
+    <author><name>John Doe</name></author>
+    <entry>
+        <title>1 &lt; 2</title>
+        <link href="https://example.com/1"/>
+        <link rel="enclosure" type="audio/mpeg" length="1024" href="https://example.com/1.mp3"/>
+        <id>https://example.com/1</id>
+        <published>2021-02-23T09:08:10Z</published>
+        <summary>Plain &amp; simple</summary>
+        <content type="html">&lt;p&gt;Hello&lt;/p&gt;</content>
+        <author><name>Alice</name><email>alice@example.com</email></author>
+    </entry>
+</feed>
+"#;
+
+        let feed = feed::parse_feed(input).unwrap();
+        let mut output = String::new();
+        write_feed(&mut output, &feed, Iter::with_str(input).unwrap()).unwrap();
+
+        let written = feed::parse_feed(&output).unwrap();
+        assert_eq!(
+            Some("Tom & Jerry"),
+            written.title.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(
+            Some("https://example.com/feed.xml"),
+            written.feed_url.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(
+            Some("https://example.com/"),
+            written.home_page_url.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(feed.modified_at, written.modified_at);
+        assert_eq!(1, written.authors.len());
+        assert_eq!(
+            Some("John Doe"),
+            written.authors[0].name.map(|v| v.to_string()).as_deref()
+        );
+
+        let mut iter = Iter::with_str(&output).unwrap();
+        let item = iter.next().unwrap();
+        // Atom identifiers are not permalinks even if they are URLs.
+        assert!(!item.id_is_permalink);
+        assert_eq!(
+            Some("https://example.com/1"),
+            item.id.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(Some("1 < 2"), item.title.map(|v| v.to_string()).as_deref());
+        // Plain text is escaped as HTML in the description.
+        assert_eq!(Some(TextTy::Html), item.summary.map(|v| v.ty()));
+        assert_eq!(
+            Some("Plain &amp; simple"),
+            item.summary.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!(
+            Some("<p>Hello</p>"),
+            item.content.map(|v| v.to_string()).as_deref()
+        );
+        assert_eq!("2021-02-23T09:08:10Z".parse().ok(), item.published_at);
+        assert_eq!(1, item.attachments.len());
+        assert_eq!(
+            Some("audio/mpeg"),
+            item.attachments[0]
+                .mime_type
+                .map(|v| v.to_string())
+                .as_deref()
+        );
+        assert_eq!(1, item.authors.len());
+        assert_eq!(
+            Some("alice@example.com"),
+            item.authors[0].email.map(|v| v.to_string()).as_deref()
+        );
+
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn schedule() {
+        let mut feed = Feed {
+            ttl: Some(Duration::from_secs(90)),
+            ..Feed::default()
+        };
+        feed.skip_hours.insert(0);
+        feed.skip_days.insert(Weekday::Sat);
+
+        let mut output = String::new();
+        write_feed(&mut output, &feed, []).unwrap();
+
+        let written = feed::parse_feed(&output).unwrap();
+        assert_eq!(Some(120), written.ttl.map(|v| v.as_secs()));
+        assert_eq!(feed.skip_hours, written.skip_hours);
+        assert_eq!(feed.skip_days, written.skip_days);
+    }
+}
@@ -8,4 +8,21 @@
     unused_qualifications
 )]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+// The parsed documents own collections, so every module requires `alloc`. See
+// the README for the `no_std` note.
+#[cfg(feature = "alloc")]
+pub mod discovery;
+#[cfg(feature = "encoding")]
+pub mod encoding;
+#[cfg(feature = "alloc")]
 pub mod feed;
+#[cfg(feature = "alloc")]
+pub mod opml;
+#[cfg(feature = "alloc")]
+pub mod url;
+
+#[cfg(feature = "alloc")]
+mod xml;
@@ -0,0 +1,460 @@
+//! Reading and writing [OPML][opml] subscription lists.
+//!
+//! Outlines with an `xmlUrl` are subscriptions. Outlines without an `xmlUrl`
+//! are folders which group their child outlines.
+//!
+//! ```
+//! use folwup_core::opml;
+//!
+//! let input = r#"<?xml version="1.0" encoding="utf-8"?>
+//! <opml version="2.0">
+//!   <head><title>Subscriptions</title></head>
+//!   <body>
+//!     <outline text="Rust">
+//!       <outline type="rss" text="Blog" xmlUrl="https://example.com/feed.xml"/>
+//!     </outline>
+//!   </body>
+//! </opml>"#;
+//!
+//! let document = opml::parse(input)?;
+//! let folder = &document.outlines[0];
+//! assert!(folder.is_folder());
+//! assert_eq!(Some("Rust"), folder.name());
+//! assert_eq!(
+//!     Some("https://example.com/feed.xml"),
+//!     folder.children[0].xml_url.as_deref()
+//! );
+//!
+//! let mut output = String::new();
+//! opml::write(&mut output, &document).expect("writing to a String cannot fail");
+//! assert_eq!(document, opml::parse(&output)?);
+//! # Ok::<(), opml::Error>(())
+//! ```
+//!
+//! [opml]: https://opml.org/spec2.opml
+
+use core::fmt;
+
+use alloc::{string::String, vec::Vec};
+use chrono::{DateTime, Utc};
+
+use crate::{
+    feed::{date, write::XmlEscaped, Text, TextTy},
+    xml,
+};
+
+/// The maximum depth of nested outlines which are read.
+///
+/// Outlines nested more deeply are ignored.
+pub const MAX_DEPTH: usize = 32;
+
+/// An OPML document.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Opml {
+    pub title: Option<String>,
+    pub date_created: Option<DateTime<Utc>>,
+    pub date_modified: Option<DateTime<Utc>>,
+    pub owner_name: Option<String>,
+    pub owner_email: Option<String>,
+    /// The top level outlines in the `body`.
+    pub outlines: Vec<Outline>,
+}
+
+/// An `outline` element.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Outline {
+    /// The text which is displayed for the outline.
+    pub text: Option<String>,
+    pub title: Option<String>,
+    /// The type of outline such as `rss`.
+    pub ty: Option<String>,
+    /// The URL of the feed.
+    pub xml_url: Option<String>,
+    /// The URL of the website.
+    pub html_url: Option<String>,
+    /// The values of the comma separated `category` attribute.
+    ///
+    /// A category without a `/` is a tag. Otherwise, the category is a
+    /// `/` delimited path.
+    pub categories: Vec<String>,
+    pub children: Vec<Outline>,
+}
+
+impl Outline {
+    /// Returns true if the outline is a subscription to a feed.
+    #[must_use]
+    pub fn is_subscription(&self) -> bool {
+        self.xml_url.is_some()
+    }
+
+    /// Returns true if the outline groups other outlines.
+    #[must_use]
+    pub fn is_folder(&self) -> bool {
+        self.xml_url.is_none()
+    }
+
+    /// The `text` or else the `title` of the outline.
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.text.as_deref().or(self.title.as_deref())
+    }
+}
+
+/// Error when a document cannot be parsed as OPML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Error {
+    /// The root element is not `opml`.
+    Unsupported,
+    /// A required element is missing from the document.
+    MissingElement(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Unsupported => f.write_str("document is not an OPML document"),
+            Error::MissingElement(name) => {
+                write!(f, "OPML document is missing a `{name}` element")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Decodes a value which is not blank.
+#[must_use]
+fn decode(raw: &str) -> Option<String> {
+    let value = Text::xml(raw, TextTy::Text).decode();
+    let value = value.trim();
+    (!value.is_empty()).then(|| String::from(value))
+}
+
+#[must_use]
+fn parse_outline(element: &xml::Element<'_>, depth: usize) -> Outline {
+    let attribute = |name| element.find_attribute(name).and_then(decode);
+
+    let children = if depth < MAX_DEPTH {
+        element
+            .children()
+            .filter(|child| child.local_name().eq_ignore_ascii_case("outline"))
+            .map(|child| parse_outline(&child, depth + 1))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Outline {
+        text: attribute("text"),
+        title: attribute("title"),
+        ty: attribute("type"),
+        xml_url: attribute("xmlUrl"),
+        html_url: attribute("htmlUrl"),
+        categories: attribute("category")
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        children,
+    }
+}
+
+/// Parses an OPML 1.0 or 2.0 document.
+///
+/// # Errors
+///
+/// Returns an error if the root element is not `opml` or if the `body` is
+/// missing.
+pub fn parse(input: &str) -> Result<Opml, Error> {
+    let root = xml::Iter::new(input).next().ok_or(Error::Unsupported)?;
+    if !root.local_name().eq_ignore_ascii_case("opml") {
+        return Err(Error::Unsupported);
+    }
+
+    let mut opml = Opml::default();
+    let mut has_body = false;
+
+    for element in root.children() {
+        match element.local_name() {
+            "head" => {
+                for child in element.children() {
+                    let value = || decode(child.content());
+                    match child.local_name() {
+                        "title" => opml.title = value(),
+                        "dateCreated" => {
+                            opml.date_created = value().and_then(|v| date::parse_utc(&v));
+                        }
+                        "dateModified" => {
+                            opml.date_modified = value().and_then(|v| date::parse_utc(&v));
+                        }
+                        "ownerName" => opml.owner_name = value(),
+                        "ownerEmail" => opml.owner_email = value(),
+                        _ => {}
+                    }
+                }
+            }
+            "body" => {
+                has_body = true;
+                opml.outlines.extend(
+                    element
+                        .children()
+                        .filter(|child| child.local_name().eq_ignore_ascii_case("outline"))
+                        .map(|child| parse_outline(&child, 1)),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    if !has_body {
+        return Err(Error::MissingElement("body"));
+    }
+
+    Ok(opml)
+}
+
+/// Writes an optional attribute.
+fn write_attribute<W: fmt::Write + ?Sized>(
+    w: &mut W,
+    name: &str,
+    value: Option<&str>,
+) -> fmt::Result {
+    match value {
+        Some(value) => write!(w, r#" {name}="{}""#, XmlEscaped(value)),
+        None => Ok(()),
+    }
+}
+
+fn write_outline<W: fmt::Write + ?Sized>(
+    w: &mut W,
+    indent: usize,
+    outline: &Outline,
+) -> fmt::Result {
+    write!(w, "{:indent$}<outline", "")?;
+
+    // The `text` attribute is required.
+    let text = outline
+        .name()
+        .or(outline.xml_url.as_deref())
+        .unwrap_or_default();
+    write_attribute(w, "text", Some(text))?;
+    write_attribute(w, "title", outline.title.as_deref())?;
+    let ty = outline
+        .ty
+        .as_deref()
+        .or_else(|| outline.is_subscription().then_some("rss"));
+    write_attribute(w, "type", ty)?;
+    write_attribute(w, "xmlUrl", outline.xml_url.as_deref())?;
+    write_attribute(w, "htmlUrl", outline.html_url.as_deref())?;
+    if !outline.categories.is_empty() {
+        write_attribute(w, "category", Some(&outline.categories.join(",")))?;
+    }
+
+    if outline.children.is_empty() {
+        return w.write_str("/>\n");
+    }
+
+    w.write_str(">\n")?;
+    for child in &outline.children {
+        write_outline(w, indent + 2, child)?;
+    }
+    writeln!(w, "{:indent$}</outline>", "")
+}
+
+/// Writes an OPML 2.0 document.
+///
+/// # Errors
+///
+/// Returns an error if the writer returns an error.
+pub fn write<W: fmt::Write + ?Sized>(w: &mut W, opml: &Opml) -> fmt::Result {
+    fn rfc_822(value: DateTime<Utc>) -> impl fmt::Display {
+        value.format("%a, %d %b %Y %H:%M:%S GMT")
+    }
+
+    w.write_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n")?;
+    w.write_str("<opml version=\"2.0\">\n")?;
+
+    w.write_str("  <head>\n")?;
+    if let Some(title) = &opml.title {
+        writeln!(w, "    <title>{}</title>", XmlEscaped(title))?;
+    }
+    if let Some(date_created) = opml.date_created {
+        writeln!(
+            w,
+            "    <dateCreated>{}</dateCreated>",
+            rfc_822(date_created)
+        )?;
+    }
+    if let Some(date_modified) = opml.date_modified {
+        writeln!(
+            w,
+            "    <dateModified>{}</dateModified>",
+            rfc_822(date_modified)
+        )?;
+    }
+    if let Some(owner_name) = &opml.owner_name {
+        writeln!(w, "    <ownerName>{}</ownerName>", XmlEscaped(owner_name))?;
+    }
+    if let Some(owner_email) = &opml.owner_email {
+        writeln!(
+            w,
+            "    <ownerEmail>{}</ownerEmail>",
+            XmlEscaped(owner_email)
+        )?;
+    }
+    w.write_str("  </head>\n")?;
+
+    w.write_str("  <body>\n")?;
+    for outline in &opml.outlines {
+        write_outline(w, 4, outline)?;
+    }
+    w.write_str("  </body>\n")?;
+
+    w.write_str("</opml>\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec};
+
+    use super::*;
+
+    #[test]
+    fn parse_opml_1() {
+        let input = r#"<?xml version="1.0" encoding="ISO-8859-1"?>
+<opml version="1.0">
+  <head>
+    <title>mySubscriptions.opml</title>
+    <dateCreated>Sat, 18 Jun 2005 12:11:52 GMT</dateCreated>
+    <ownerName>Jane Doe</ownerName>
+  </head>
+  <body>
+    <outline text="CNET News.com" description="Tech news" htmlUrl="http://news.com.com/" language="unknown" title="CNET News.com" type="rss" version="RSS2" xmlUrl="http://news.com.com/2547-1_3-0-5.xml"/>
+    <outline text="Tom &amp; Jerry" type="rss" xmlUrl="https://example.com/feed?a=1&amp;b=2" category="cartoons, /Entertainment/TV,"></outline>
+    <outline title="No text" xmlUrl="https://example.com/atom.xml"/>
+  </body>
+</opml>
+"#;
+
+        let opml = parse(input).unwrap();
+        assert_eq!(Some("mySubscriptions.opml"), opml.title.as_deref());
+        assert_eq!("2005-06-18T12:11:52Z".parse().ok(), opml.date_created);
+        assert_eq!(Some("Jane Doe"), opml.owner_name.as_deref());
+        assert_eq!(3, opml.outlines.len());
+
+        let outline = &opml.outlines[0];
+        assert!(outline.is_subscription());
+        assert_eq!(Some("CNET News.com"), outline.name());
+        assert_eq!(Some("rss"), outline.ty.as_deref());
+        assert_eq!(Some("http://news.com.com/"), outline.html_url.as_deref());
+        assert_eq!(
+            Some("http://news.com.com/2547-1_3-0-5.xml"),
+            outline.xml_url.as_deref()
+        );
+
+        let outline = &opml.outlines[1];
+        assert_eq!(Some("Tom & Jerry"), outline.name());
+        assert_eq!(
+            Some("https://example.com/feed?a=1&b=2"),
+            outline.xml_url.as_deref()
+        );
+        assert_eq!(vec!["cartoons", "/Entertainment/TV"], outline.categories);
+
+        assert_eq!(Some("No text"), opml.outlines[2].name());
+    }
+
+    #[test]
+    fn parse_nested() {
+        let input = r#"<opml version="2.0">
+  <head/>
+  <body>
+    <outline text="Tech">
+      <outline text="Rust">
+        <outline text="This Week in Rust" type="rss" xmlUrl="https://this-week-in-rust.org/rss.xml"/>
+      </outline>
+      <outline text="Hacker News" type="rss" xmlUrl="https://news.ycombinator.com/rss"/>
+    </outline>
+    <outline text="Empty"/>
+  </body>
+</opml>"#;
+
+        let opml = parse(input).unwrap();
+        assert_eq!(None, opml.title);
+        assert_eq!(2, opml.outlines.len());
+
+        let tech = &opml.outlines[0];
+        assert!(tech.is_folder());
+        assert_eq!(2, tech.children.len());
+        assert_eq!(Some("Rust"), tech.children[0].name());
+        assert_eq!(
+            Some("https://this-week-in-rust.org/rss.xml"),
+            tech.children[0].children[0].xml_url.as_deref()
+        );
+        assert!(tech.children[1].is_subscription());
+
+        assert!(opml.outlines[1].is_folder());
+        assert!(opml.outlines[1].children.is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Err(Error::Unsupported), parse(""));
+        assert_eq!(
+            Err(Error::Unsupported),
+            parse("<rss version=\"2.0\"><channel/></rss>")
+        );
+        assert_eq!(
+            Err(Error::MissingElement("body")),
+            parse("<opml version=\"2.0\"><head/></opml>")
+        );
+        assert_eq!(Err(Error::Unsupported), parse("<!-"));
+
+        // A truncated document is parsed up to the truncated markup.
+        let opml = parse("<opml><body><outline text=\"a\"/><!-").unwrap();
+        assert_eq!(1, opml.outlines.len());
+    }
+
+    #[test]
+    fn round_trip() {
+        let opml = Opml {
+            title: Some("A & B".to_string()),
+            date_created: "2021-02-23T09:08:10Z".parse().ok(),
+            date_modified: None,
+            owner_name: Some("Jane <Doe>".to_string()),
+            owner_email: Some("jane@example.com".to_string()),
+            outlines: vec![
+                Outline {
+                    text: Some("Folder \"1\"".to_string()),
+                    children: vec![Outline {
+                        text: Some("Feed".to_string()),
+                        title: Some("Feed".to_string()),
+                        ty: Some("rss".to_string()),
+                        xml_url: Some("https://example.com/feed?a=1&b=2".to_string()),
+                        html_url: Some("https://example.com/".to_string()),
+                        categories: vec!["rust".to_string(), "/Tech/News".to_string()],
+                        children: Vec::new(),
+                    }],
+                    ..Outline::default()
+                },
+                Outline {
+                    text: Some("https://example.com/atom.xml".to_string()),
+                    ty: Some("rss".to_string()),
+                    xml_url: Some("https://example.com/atom.xml".to_string()),
+                    ..Outline::default()
+                },
+            ],
+        };
+
+        let mut output = String::new();
+        write(&mut output, &opml).unwrap();
+        assert_eq!(opml, parse(&output).unwrap());
+    }
+}
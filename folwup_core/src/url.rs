@@ -0,0 +1,250 @@
+//! Resolution of relative URL references.
+//!
+//! See [RFC 3986 section 5][rfc3986].
+//!
+//! [rfc3986]: https://datatracker.ietf.org/doc/html/rfc3986#section-5
+
+use alloc::string::String;
+
+/// The components of a URI reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Components<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> Components<'a> {
+    #[must_use]
+    fn parse(value: &'a str) -> Self {
+        let (value, fragment) = match value.split_once('#') {
+            Some((value, fragment)) => (value, Some(fragment)),
+            None => (value, None),
+        };
+        let (value, query) = match value.split_once('?') {
+            Some((value, query)) => (value, Some(query)),
+            None => (value, None),
+        };
+
+        let (scheme, value) = match value.split_once(':') {
+            Some((scheme, rest)) if is_scheme(scheme) => (Some(scheme), rest),
+            _ => (None, value),
+        };
+
+        let (authority, path) = if let Some(rest) = value.strip_prefix("//") {
+            let end = rest.find('/').unwrap_or(rest.len());
+            (Some(&rest[..end]), &rest[end..])
+        } else {
+            (None, value)
+        };
+
+        Self {
+            scheme,
+            authority,
+            path,
+            query,
+            fragment,
+        }
+    }
+}
+
+#[must_use]
+fn is_scheme(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Returns true if the value is an absolute URI with a scheme.
+#[must_use]
+pub fn is_absolute(value: &str) -> bool {
+    Components::parse(value.trim()).scheme.is_some()
+}
+
+/// Returns true if the value is an absolute `http` or `https` URL.
+#[must_use]
+pub fn is_http(value: &str) -> bool {
+    Components::parse(value.trim())
+        .scheme
+        .is_some_and(|scheme| {
+            scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+        })
+}
+
+/// Removes `.` and `..` segments from a path.
+fn remove_dot_segments(output: &mut String, mut input: &str) {
+    let start = output.len();
+
+    while !input.is_empty() {
+        if let Some(rest) = input
+            .strip_prefix("../")
+            .or_else(|| input.strip_prefix("./"))
+        {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") || input == "/.." {
+            input = if input == "/.." { "/" } else { &input[3..] };
+            let segment_start = output[start..].rfind('/').map_or(start, |idx| start + idx);
+            output.truncate(segment_start);
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let segment_end = input
+                .bytes()
+                .skip(1)
+                .position(|b| b == b'/')
+                .map_or(input.len(), |idx| idx + 1);
+            output.push_str(&input[..segment_end]);
+            input = &input[segment_end..];
+        }
+    }
+}
+
+/// Resolves a URI reference against an absolute base URI.
+///
+/// Returns `None` if the base URI does not have a scheme.
+#[must_use]
+pub fn resolve(base: &str, reference: &str) -> Option<String> {
+    let base = Components::parse(base.trim());
+    let reference = Components::parse(reference.trim());
+
+    let base_scheme = base.scheme?;
+
+    let mut output = String::new();
+
+    let scheme = reference.scheme.unwrap_or(base_scheme);
+    output.push_str(scheme);
+    output.push(':');
+
+    let query = if reference.scheme.is_some() || reference.authority.is_some() {
+        if let Some(authority) = reference.authority {
+            output.push_str("//");
+            output.push_str(authority);
+        }
+        remove_dot_segments(&mut output, reference.path);
+        reference.query
+    } else {
+        if let Some(authority) = base.authority {
+            output.push_str("//");
+            output.push_str(authority);
+        }
+
+        if reference.path.is_empty() {
+            output.push_str(base.path);
+            reference.query.or(base.query)
+        } else {
+            if reference.path.starts_with('/') {
+                remove_dot_segments(&mut output, reference.path);
+            } else {
+                // Merge the paths
+                let mut merged = String::new();
+                if base.authority.is_some() && base.path.is_empty() {
+                    merged.push('/');
+                } else if let Some(idx) = base.path.rfind('/') {
+                    merged.push_str(&base.path[..=idx]);
+                }
+                merged.push_str(reference.path);
+                remove_dot_segments(&mut output, &merged);
+            }
+            reference.query
+        }
+    };
+
+    if let Some(query) = query {
+        output.push('?');
+        output.push_str(query);
+    }
+    if let Some(fragment) = reference.fragment {
+        output.push('#');
+        output.push_str(fragment);
+    }
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rfc_3986_examples() {
+        const BASE: &str = "http://a/b/c/d;p?q";
+
+        for (reference, expected) in [
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            ("g?y#s", "http://a/b/c/g?y#s"),
+            (";x", "http://a/b/c/;x"),
+            ("g;x", "http://a/b/c/g;x"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../", "http://a/"),
+            ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            (".g", "http://a/b/c/.g"),
+            ("g..", "http://a/b/c/g.."),
+            ("..g", "http://a/b/c/..g"),
+            ("./../g", "http://a/b/g"),
+            ("./g/.", "http://a/b/c/g/"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+            ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+            ("g;x=1/../y", "http://a/b/c/y"),
+            ("g?y/./x", "http://a/b/c/g?y/./x"),
+            ("g#s/../x", "http://a/b/c/g#s/../x"),
+        ] {
+            assert_eq!(
+                Some(expected),
+                resolve(BASE, reference).as_deref(),
+                "{reference}"
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_feed_urls() {
+        assert_eq!(
+            Some("https://example.com/posts/1"),
+            resolve("https://example.com/feed.xml", " /posts/1 ").as_deref()
+        );
+        assert_eq!(
+            Some("https://example.com/"),
+            resolve("https://example.com", "/").as_deref()
+        );
+        assert_eq!(
+            Some("https://example.com/a"),
+            resolve("https://example.com", "a").as_deref()
+        );
+        assert_eq!(None, resolve("/relative/base", "a"));
+        assert_eq!(
+            Some("mailto:ölaf@example.com"),
+            resolve("https://example.com/", "mailto:ölaf@example.com").as_deref()
+        );
+        assert_eq!(Some("urn:é"), resolve("urn:x", "é").as_deref());
+        assert_eq!(
+            Some("https://example.com/ü/ö"),
+            resolve("https://example.com/ü/a", "ö").as_deref()
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Minimal element reader for XML documents which are not handled by
+//! `readfeed`.
+
+use maybe_xml::{
+    token::{
+        self,
+        prop::{Attributes, TagName},
+    },
+    Reader,
+};
+
+/// Removes a truncated `<!-` from the end of the input.
+///
+/// The tokenizer panics instead of returning `None` when the input ends with
+/// the truncated markup, so every input must be trimmed before it is
+/// tokenized.
+#[must_use]
+pub(crate) fn trim_truncated(mut input: &str) -> &str {
+    while let Some(rest) = input.strip_suffix("<!-") {
+        input = rest;
+    }
+    input
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Element<'a> {
+    name: TagName<'a>,
+    attributes: Option<Attributes<'a>>,
+    content: &'a str,
+}
+
+impl<'a> Element<'a> {
+    #[inline]
+    #[must_use]
+    pub(crate) fn local_name(&self) -> &'a str {
+        self.name.local().as_str()
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) const fn content(&self) -> &'a str {
+        self.content
+    }
+
+    #[inline]
+    #[must_use]
+    pub(crate) fn children(&self) -> Iter<'a> {
+        Iter::new(self.content)
+    }
+
+    /// Finds an attribute value by its local name.
+    #[inline]
+    #[must_use]
+    pub(crate) fn find_attribute(&self, local_name: &str) -> Option<&'a str> {
+        find_attribute(self.attributes, local_name)
+    }
+}
+
+/// Finds an attribute value by its local name.
+#[must_use]
+pub(crate) fn find_attribute<'a>(
+    attributes: Option<Attributes<'a>>,
+    local_name: &str,
+) -> Option<&'a str> {
+    attributes?.into_iter().find_map(|attr| {
+        attr.name()
+            .local()
+            .as_str()
+            .eq_ignore_ascii_case(local_name)
+            .then(|| attr.value().map(|v| v.as_str()))
+            .flatten()
+    })
+}
+
+#[must_use]
+fn collect_until_end_tag<'a>(name: TagName<'a>, reader: &Reader<'a>, pos: &mut usize) -> &'a str {
+    let begin = *pos;
+    let mut end = *pos;
+    let mut depth = 1;
+    let name = name.as_str();
+
+    while let Some(token) = reader.tokenize(pos) {
+        match token.ty() {
+            token::Ty::EndTag(tag) => {
+                if tag.name().as_str().eq_ignore_ascii_case(name) {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+            }
+            token::Ty::StartTag(tag) => {
+                if tag.name().as_str().eq_ignore_ascii_case(name) {
+                    depth += 1;
+                }
+            }
+            token::Ty::EmptyElementTag(_)
+            | token::Ty::Characters(_)
+            | token::Ty::ProcessingInstruction(_)
+            | token::Ty::Declaration(_)
+            | token::Ty::Comment(_)
+            | token::Ty::Cdata(_) => {}
+        }
+
+        end = *pos;
+    }
+
+    &reader.into_inner()[begin..end]
+}
+
+/// Iterates over the child elements in the input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Iter<'a> {
+    reader: Reader<'a>,
+    pos: usize,
+}
+
+impl<'a> Iter<'a> {
+    #[inline]
+    #[must_use]
+    pub(crate) fn new(input: &'a str) -> Self {
+        Self {
+            reader: Reader::from_str(trim_truncated(input)),
+            pos: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Element<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(token) = self.reader.tokenize(&mut self.pos) {
+            match token.ty() {
+                token::Ty::StartTag(tag) => {
+                    let content = collect_until_end_tag(tag.name(), &self.reader, &mut self.pos);
+                    return Some(Element {
+                        name: tag.name(),
+                        attributes: tag.attributes(),
+                        content,
+                    });
+                }
+                token::Ty::EmptyElementTag(tag) => {
+                    return Some(Element {
+                        name: tag.name(),
+                        attributes: tag.attributes(),
+                        content: "",
+                    });
+                }
+                token::Ty::EndTag(_)
+                | token::Ty::Characters(_)
+                | token::Ty::ProcessingInstruction(_)
+                | token::Ty::Declaration(_)
+                | token::Ty::Comment(_)
+                | token::Ty::Cdata(_) => {}
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,566 @@
+//! Fetches feeds over HTTP.
+//!
+//! Requests are conditional if the validators of a previous response are
+//! given. Compressed responses are decompressed and redirects are followed.
+//!
+//! ```no_run
+//! use folwup_core::feed;
+//! use folwup_http::{Config, Fetcher, Validators};
+//!
+//! let fetcher = Fetcher::new(Config::default());
+//! let fetched = fetcher.fetch("https://example.com/feed.xml", &Validators::default())?;
+//! if let Some(text) = fetched.text() {
+//!     for item in feed::Iter::with_str_and_base(&text, &fetched.url)? {
+//!         println!("{:?}", item.title);
+//!     }
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    rust_2018_idioms,
+    unused_lifetimes,
+    unused_qualifications
+)]
+
+use std::{
+    borrow::Cow,
+    fmt,
+    io::{self, BufRead, BufReader, Read},
+    time::{Duration, Instant},
+};
+
+use flate2::read::{DeflateDecoder, MultiGzDecoder, ZlibDecoder};
+use folwup_core::{encoding, url};
+use ureq::{http::HeaderMap, Agent};
+
+const ACCEPT: &str = "application/rss+xml, application/atom+xml, application/feed+json, \
+     application/xml;q=0.9, text/xml;q=0.9, application/json;q=0.8, */*;q=0.5";
+
+const ACCEPT_ENCODING: &str = "gzip, deflate, br";
+
+/// Error when a feed cannot be fetched.
+#[derive(Debug)]
+pub enum Error {
+    /// The request failed or timed out.
+    Http(ureq::Error),
+    /// The body could not be read or decompressed.
+    Io(io::Error),
+    /// The response has an unsuccessful status code.
+    Status(u16),
+    /// A redirect does not have a valid `Location`.
+    InvalidRedirect,
+    /// There were more redirects than allowed.
+    TooManyRedirects,
+    /// The body is compressed with an unknown encoding.
+    UnsupportedEncoding(String),
+    /// The decompressed body is larger than allowed.
+    BodyTooLarge,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Http(error) => write!(f, "request failed: {error}"),
+            Error::Io(error) => write!(f, "reading the response failed: {error}"),
+            Error::Status(status) => write!(f, "unexpected response status {status}"),
+            Error::InvalidRedirect => f.write_str("redirect has an invalid location"),
+            Error::TooManyRedirects => f.write_str("too many redirects"),
+            Error::UnsupportedEncoding(encoding) => {
+                write!(f, "unsupported content encoding `{encoding}`")
+            }
+            Error::BodyTooLarge => f.write_str("response body is too large"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Http(error) => Some(error),
+            Error::Io(error) => Some(error),
+            Error::Status(_)
+            | Error::InvalidRedirect
+            | Error::TooManyRedirects
+            | Error::UnsupportedEncoding(_)
+            | Error::BodyTooLarge => None,
+        }
+    }
+}
+
+impl From<ureq::Error> for Error {
+    fn from(value: ureq::Error) -> Self {
+        Self::Http(value)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Limits and settings for fetching.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Config {
+    pub user_agent: String,
+    /// The maximum time for a fetch including redirects and reading the
+    /// body.
+    pub timeout: Duration,
+    /// The maximum size of the body after it is decompressed.
+    pub max_body_size: u64,
+    pub max_redirects: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            user_agent: format!("folwup/{}", env!("CARGO_PKG_VERSION")),
+            timeout: Duration::from_secs(30),
+            max_body_size: 10 * 1024 * 1024,
+            max_redirects: 5,
+        }
+    }
+}
+
+/// The `ETag` and `Last-Modified` values of a previous response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Validators<'a> {
+    pub etag: Option<&'a str>,
+    pub last_modified: Option<&'a str>,
+}
+
+/// A fetched feed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Fetched {
+    /// The URL of the response after following redirects.
+    pub url: String,
+    /// The URL which the feed permanently moved to.
+    ///
+    /// Only leading `301` and `308` redirects are permanent. A permanent
+    /// redirect after a temporary redirect is not recorded.
+    pub moved_to: Option<String>,
+    /// The `ETag` of the response or else the given value.
+    pub etag: Option<String>,
+    /// The `Last-Modified` value of the response or else the given value.
+    pub last_modified: Option<String>,
+    pub content_type: Option<String>,
+    /// The decompressed body or `None` if the feed was not modified.
+    pub body: Option<Vec<u8>>,
+}
+
+impl Fetched {
+    /// Returns true unless the server responded with `304 Not Modified`.
+    #[inline]
+    #[must_use]
+    pub const fn is_modified(&self) -> bool {
+        self.body.is_some()
+    }
+
+    /// Returns the body decoded with the charset of the `Content-Type` or
+    /// document.
+    #[must_use]
+    pub fn text(&self) -> Option<Cow<'_, str>> {
+        self.body
+            .as_deref()
+            .map(|body| encoding::decode(body, self.content_type.as_deref()))
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Wraps the reader with a decoder for a `Content-Encoding`.
+fn decoder<'a>(reader: Box<dyn Read + 'a>, encoding: &str) -> Result<Box<dyn Read + 'a>> {
+    let encoding = encoding.trim();
+    if encoding.is_empty() || encoding.eq_ignore_ascii_case("identity") {
+        Ok(reader)
+    } else if encoding.eq_ignore_ascii_case("gzip") || encoding.eq_ignore_ascii_case("x-gzip") {
+        Ok(Box::new(MultiGzDecoder::new(reader)))
+    } else if encoding.eq_ignore_ascii_case("deflate") {
+        // Some servers send a raw deflate stream instead of the zlib format.
+        let mut reader = BufReader::new(reader);
+        let is_zlib = match reader.fill_buf()? {
+            [cmf, flg, ..] => cmf & 0x0f == 8 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0,
+            _ => true,
+        };
+        if is_zlib {
+            Ok(Box::new(ZlibDecoder::new(reader)))
+        } else {
+            Ok(Box::new(DeflateDecoder::new(reader)))
+        }
+    } else if encoding.eq_ignore_ascii_case("br") {
+        Ok(Box::new(brotli_decompressor::Decompressor::new(
+            reader, 4096,
+        )))
+    } else {
+        Err(Error::UnsupportedEncoding(encoding.to_string()))
+    }
+}
+
+/// Reads and decompresses a body which is at most `limit` bytes.
+fn read_body(reader: impl Read, encodings: &[&str], limit: u64) -> Result<Vec<u8>> {
+    let mut reader: Box<dyn Read + '_> = Box::new(reader);
+    // Encodings are listed in the order they were applied.
+    for encoding in encodings.iter().rev() {
+        reader = decoder(reader, encoding)?;
+    }
+
+    let mut body = Vec::new();
+    reader
+        .take(limit.saturating_add(1))
+        .read_to_end(&mut body)?;
+    if body.len() as u64 > limit {
+        return Err(Error::BodyTooLarge);
+    }
+    Ok(body)
+}
+
+/// Fetches feeds.
+#[derive(Debug, Clone)]
+pub struct Fetcher {
+    agent: Agent,
+    config: Config,
+}
+
+impl Default for Fetcher {
+    fn default() -> Self {
+        Self::new(Config::default())
+    }
+}
+
+impl Fetcher {
+    #[must_use]
+    pub fn new(config: Config) -> Self {
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .max_redirects(0)
+            .user_agent(config.user_agent.as_str())
+            .build()
+            .into();
+        Self { agent, config }
+    }
+
+    /// Fetches a feed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request fails, the status is not successful,
+    /// a limit is exceeded, or the body cannot be decompressed.
+    pub fn fetch(&self, url: &str, validators: &Validators<'_>) -> Result<Fetched> {
+        let deadline = Instant::now() + self.config.timeout;
+        let mut fetched = Fetched {
+            url: url.trim().to_string(),
+            ..Fetched::default()
+        };
+        let mut is_permanent = true;
+
+        for _ in 0..=self.config.max_redirects {
+            let mut request = self
+                .agent
+                .get(&fetched.url)
+                .config()
+                .timeout_global(Some(deadline.saturating_duration_since(Instant::now())))
+                .build()
+                .header("Accept", ACCEPT)
+                .header("Accept-Encoding", ACCEPT_ENCODING);
+            if let Some(etag) = validators.etag {
+                request = request.header("If-None-Match", etag);
+            }
+            if let Some(last_modified) = validators.last_modified {
+                request = request.header("If-Modified-Since", last_modified);
+            }
+
+            let (parts, body) = request.call()?.into_parts();
+            let status = parts.status.as_u16();
+
+            match status {
+                301 | 302 | 303 | 307 | 308 => {
+                    let location = header(&parts.headers, "location")
+                        .and_then(|location| url::resolve(&fetched.url, location))
+                        .ok_or(Error::InvalidRedirect)?;
+                    is_permanent &= matches!(status, 301 | 308);
+                    if is_permanent {
+                        fetched.moved_to = Some(location.clone());
+                    }
+                    fetched.url = location;
+                    continue;
+                }
+                200..=299 | 304 => {}
+                _ => return Err(Error::Status(status)),
+            }
+
+            fetched.etag = header(&parts.headers, "etag")
+                .or(validators.etag)
+                .map(String::from);
+            fetched.last_modified = header(&parts.headers, "last-modified")
+                .or(validators.last_modified)
+                .map(String::from);
+            if status == 304 {
+                return Ok(fetched);
+            }
+
+            fetched.content_type = header(&parts.headers, "content-type").map(String::from);
+            let encodings = parts
+                .headers
+                .get_all("content-encoding")
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .collect::<Vec<_>>();
+            fetched.body = Some(read_body(
+                body.into_reader(),
+                &encodings,
+                self.config.max_body_size,
+            )?);
+            return Ok(fetched);
+        }
+
+        Err(Error::TooManyRedirects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        net::{TcpListener, TcpStream},
+        thread,
+    };
+
+    use flate2::{
+        write::{DeflateEncoder, GzEncoder, ZlibEncoder},
+        Compression,
+    };
+    use folwup_core::feed;
+
+    use super::*;
+
+    const FEED: &[u8] = b"<rss version=\"2.0\"><channel><title>Caf\xe9</title>\
+        <item><title>One</title></item></channel></rss>";
+
+    #[derive(Debug)]
+    struct Request {
+        path: String,
+        headers: Vec<(String, String)>,
+    }
+
+    impl Request {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+        }
+    }
+
+    fn read_request(stream: &TcpStream) -> Request {
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let path = line.split(' ').nth(1).unwrap().to_string();
+
+        let mut headers = Vec::new();
+        loop {
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+            let Some((name, value)) = line.trim_end().split_once(':') else {
+                break;
+            };
+            headers.push((name.to_string(), value.trim().to_string()));
+        }
+        Request { path, headers }
+    }
+
+    /// Starts a server which responds to each request with the handler's
+    /// response and returns the server's URL.
+    fn serve<F>(handler: F) -> String
+    where
+        F: Fn(&Request) -> Vec<u8> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let request = read_request(&stream);
+                let _ = stream.write_all(&handler(&request));
+            }
+        });
+        url
+    }
+
+    fn response(status: &str, headers: &[(&str, &str)], body: &[u8]) -> Vec<u8> {
+        let mut output = Vec::new();
+        write!(output, "HTTP/1.1 {status}\r\nConnection: close\r\n").unwrap();
+        for (name, value) in headers {
+            write!(output, "{name}: {value}\r\n").unwrap();
+        }
+        write!(output, "Content-Length: {}\r\n\r\n", body.len()).unwrap();
+        output.extend_from_slice(body);
+        output
+    }
+
+    fn compress<W: Write>(mut encoder: W, body: &[u8]) -> W {
+        encoder.write_all(body).unwrap();
+        encoder
+    }
+
+    fn gzip(body: &[u8]) -> Vec<u8> {
+        compress(GzEncoder::new(Vec::new(), Compression::default()), body)
+            .finish()
+            .unwrap()
+    }
+
+    fn brotli(body: &[u8]) -> Vec<u8> {
+        compress(brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22), body).into_inner()
+    }
+
+    #[test]
+    fn conditional() {
+        let url = serve(|request| {
+            if request.header("If-None-Match") == Some("\"v1\"")
+                && request.header("If-Modified-Since") == Some("Mon, 01 Feb 2021 00:00:00 GMT")
+            {
+                return response("304 Not Modified", &[], b"");
+            }
+            response(
+                "200 OK",
+                &[
+                    ("Content-Type", "application/rss+xml; charset=iso-8859-1"),
+                    ("ETag", "\"v1\""),
+                    ("Last-Modified", "Mon, 01 Feb 2021 00:00:00 GMT"),
+                ],
+                FEED,
+            )
+        });
+        let http = Fetcher::default();
+
+        let fetched = http.fetch(&url, &Validators::default()).unwrap();
+        assert!(fetched.is_modified());
+        assert_eq!(url, fetched.url);
+        assert_eq!(None, fetched.moved_to);
+        let text = fetched.text().unwrap();
+        let title = feed::parse_feed(&text).unwrap().title.unwrap();
+        assert_eq!("Café", title.decode());
+        assert_eq!(1, feed::Iter::with_str(&text).unwrap().count());
+
+        let validators = Validators {
+            etag: fetched.etag.as_deref(),
+            last_modified: fetched.last_modified.as_deref(),
+        };
+        let not_modified = http.fetch(&url, &validators).unwrap();
+        assert!(!not_modified.is_modified());
+        assert_eq!(None, not_modified.text());
+        assert_eq!(fetched.etag, not_modified.etag);
+        assert_eq!(fetched.last_modified, not_modified.last_modified);
+    }
+
+    #[test]
+    fn compression() {
+        let url = serve(|request| {
+            let (encoding, body) = match request.path.as_str() {
+                "/gzip" => ("gzip", gzip(FEED)),
+                "/deflate" => (
+                    "deflate",
+                    compress(ZlibEncoder::new(Vec::new(), Compression::default()), FEED)
+                        .finish()
+                        .unwrap(),
+                ),
+                "/raw-deflate" => (
+                    "deflate",
+                    compress(
+                        DeflateEncoder::new(Vec::new(), Compression::default()),
+                        FEED,
+                    )
+                    .finish()
+                    .unwrap(),
+                ),
+                "/br" => ("br", brotli(FEED)),
+                "/gzip-br" => ("gzip, br", brotli(&gzip(FEED))),
+                _ => ("zstd", FEED.to_vec()),
+            };
+            assert_eq!(Some(ACCEPT_ENCODING), request.header("Accept-Encoding"));
+            response("200 OK", &[("Content-Encoding", encoding)], &body)
+        });
+        let http = Fetcher::default();
+
+        for path in ["/gzip", "/deflate", "/raw-deflate", "/br", "/gzip-br"] {
+            let fetched = http
+                .fetch(&format!("{url}{path}"), &Validators::default())
+                .unwrap();
+            assert_eq!(Some(FEED), fetched.body.as_deref(), "{path}");
+        }
+        assert!(matches!(
+            http.fetch(&format!("{url}/zstd"), &Validators::default()),
+            Err(Error::UnsupportedEncoding(encoding)) if encoding == "zstd"
+        ));
+    }
+
+    #[test]
+    fn redirects() {
+        let url = serve(|request| {
+            let redirect = |status, location| response(status, &[("Location", location)], b"");
+            match request.path.as_str() {
+                "/a" => redirect("301 Moved Permanently", "/b"),
+                "/b" => redirect("308 Permanent Redirect", "c"),
+                "/c" => redirect("302 Found", "/d"),
+                "/d" => redirect("301 Moved Permanently", "/e"),
+                "/e" => response("200 OK", &[], FEED),
+                "/loop" => redirect("307 Temporary Redirect", "/loop"),
+                "/invalid" => response("301 Moved Permanently", &[], b""),
+                _ => response("404 Not Found", &[], b""),
+            }
+        });
+        let http = Fetcher::default();
+
+        let fetched = http
+            .fetch(&format!("{url}/a"), &Validators::default())
+            .unwrap();
+        assert_eq!(format!("{url}/e"), fetched.url);
+        assert_eq!(Some(format!("{url}/c")), fetched.moved_to);
+        assert_eq!(Some(FEED), fetched.body.as_deref());
+
+        let fetch = |path| http.fetch(&format!("{url}{path}"), &Validators::default());
+        assert!(matches!(fetch("/loop"), Err(Error::TooManyRedirects)));
+        assert!(matches!(fetch("/invalid"), Err(Error::InvalidRedirect)));
+        assert!(matches!(fetch("/missing"), Err(Error::Status(404))));
+    }
+
+    #[test]
+    fn limits() {
+        let url = serve(|request| match request.path.as_str() {
+            "/large" => response("200 OK", &[], &[b' '; 2048]),
+            "/bomb" => response(
+                "200 OK",
+                &[("Content-Encoding", "gzip")],
+                &gzip(&vec![0; 1024 * 1024]),
+            ),
+            "/slow" => {
+                thread::sleep(Duration::from_secs(2));
+                response("200 OK", &[], FEED)
+            }
+            _ => response("200 OK", &[], &[b' '; 1024]),
+        });
+        let http = Fetcher::new(Config {
+            timeout: Duration::from_millis(200),
+            max_body_size: 1024,
+            ..Config::default()
+        });
+        let fetch = |path| http.fetch(&format!("{url}{path}"), &Validators::default());
+
+        assert_eq!(1024, fetch("/limit").unwrap().body.unwrap().len());
+        assert!(matches!(fetch("/large"), Err(Error::BodyTooLarge)));
+        assert!(matches!(fetch("/bomb"), Err(Error::BodyTooLarge)));
+        assert!(matches!(fetch("/slow"), Err(Error::Http(_) | Error::Io(_))));
+    }
+}
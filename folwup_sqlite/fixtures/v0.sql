@@ -0,0 +1,75 @@
+-- A database created before the schema was versioned.
+PRAGMA foreign_keys=OFF;
+BEGIN TRANSACTION;
+CREATE TABLE folders (
+    id INTEGER PRIMARY KEY,
+    parent_id INTEGER REFERENCES folders (id) ON DELETE CASCADE,
+    name TEXT NOT NULL
+);
+INSERT INTO folders VALUES(1,NULL,'Tech');
+INSERT INTO folders VALUES(2,1,'Rust');
+CREATE TABLE subscriptions (
+    id INTEGER PRIMARY KEY,
+    url TEXT NOT NULL UNIQUE,
+    title TEXT,
+    html_url TEXT,
+    folder_id INTEGER REFERENCES folders (id) ON DELETE SET NULL,
+    created_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+INSERT INTO subscriptions VALUES(1,'https://this-week-in-rust.org/rss.xml','This Week in Rust','https://this-week-in-rust.org/',2,1700000000);
+INSERT INTO subscriptions VALUES(2,'https://example.com/feed.xml',NULL,NULL,NULL,1700000000);
+CREATE TABLE feeds (
+    subscription_id INTEGER PRIMARY KEY REFERENCES subscriptions (id) ON DELETE CASCADE,
+    title TEXT,
+    title_ty TEXT,
+    description TEXT,
+    description_ty TEXT,
+    home_page_url TEXT,
+    feed_url TEXT,
+    alternate_url TEXT,
+    authors TEXT NOT NULL DEFAULT '[]',
+    contributors TEXT NOT NULL DEFAULT '[]',
+    categories TEXT NOT NULL DEFAULT '[]',
+    language TEXT,
+    icon TEXT,
+    logo TEXT,
+    modified_at INTEGER,
+    generator TEXT NOT NULL DEFAULT 'null',
+    rights TEXT,
+    rights_ty TEXT,
+    ttl INTEGER,
+    skip_hours INTEGER NOT NULL DEFAULT 0,
+    skip_days INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL DEFAULT (unixepoch()),
+    updated_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+INSERT INTO feeds VALUES(1,'This Week in Rust','text',NULL,NULL,'https://this-week-in-rust.org/',NULL,NULL,'[]','[]','[]',NULL,NULL,NULL,NULL,'null',NULL,NULL,3600,0,0,1700000000,1700000000);
+CREATE TABLE items (
+    id INTEGER PRIMARY KEY,
+    subscription_id INTEGER NOT NULL REFERENCES subscriptions (id) ON DELETE CASCADE,
+    key TEXT NOT NULL,
+    source_id TEXT,
+    id_is_permalink INTEGER NOT NULL DEFAULT 0,
+    title TEXT,
+    title_ty TEXT,
+    content TEXT,
+    content_ty TEXT,
+    summary TEXT,
+    summary_ty TEXT,
+    url TEXT,
+    external_url TEXT,
+    published_at INTEGER,
+    modified_at INTEGER,
+    authors TEXT NOT NULL DEFAULT '[]',
+    contributors TEXT NOT NULL DEFAULT '[]',
+    categories TEXT NOT NULL DEFAULT '[]',
+    attachments TEXT NOT NULL DEFAULT '[]',
+    podcast TEXT NOT NULL DEFAULT '{}',
+    created_at INTEGER NOT NULL DEFAULT (unixepoch()),
+    updated_at INTEGER NOT NULL DEFAULT (unixepoch()),
+    UNIQUE (subscription_id, key)
+);
+INSERT INTO items VALUES(1,1,'url:https://this-week-in-rust.org/blog/2023/11/15/this-week-in-rust-521','https://this-week-in-rust.org/blog/2023/11/15/this-week-in-rust-521/',1,'This Week in Rust 521','text',NULL,NULL,'<p>Hello Rust!</p>','html','https://this-week-in-rust.org/blog/2023/11/15/this-week-in-rust-521/',NULL,1700006400,NULL,'[{"name":"TWiR Contributors","email":null,"url":null,"avatar":null}]','[]','[{"term":"Rust","scheme":null,"label":null}]','[]','{}',1700000000,1700000000);
+CREATE UNIQUE INDEX folders_parent_id_name
+    ON folders (ifnull(parent_id, 0), name);
+COMMIT;
@@ -0,0 +1,24 @@
+-- A database at schema version 1.
+PRAGMA foreign_keys=OFF;
+BEGIN TRANSACTION;
+CREATE TABLE folders (
+    id INTEGER PRIMARY KEY,
+    parent_id INTEGER REFERENCES folders (id) ON DELETE CASCADE,
+    name TEXT NOT NULL
+);
+INSERT INTO folders VALUES(1,NULL,'Tech');
+INSERT INTO folders VALUES(2,1,'Rust');
+CREATE TABLE subscriptions (
+    id INTEGER PRIMARY KEY,
+    url TEXT NOT NULL UNIQUE,
+    title TEXT,
+    html_url TEXT,
+    folder_id INTEGER REFERENCES folders (id) ON DELETE SET NULL,
+    created_at INTEGER NOT NULL DEFAULT (unixepoch())
+);
+INSERT INTO subscriptions VALUES(1,'https://this-week-in-rust.org/rss.xml','This Week in Rust','https://this-week-in-rust.org/',2,1700000000);
+INSERT INTO subscriptions VALUES(2,'https://example.com/feed.xml',NULL,NULL,NULL,1700000000);
+CREATE UNIQUE INDEX folders_parent_id_name
+    ON folders (ifnull(parent_id, 0), name);
+COMMIT;
+PRAGMA user_version = 1;
@@ -0,0 +1,702 @@
+//! Feed metadata and items.
+
+use core::time::Duration;
+
+use chrono::{DateTime, Utc};
+use folwup_core::feed::{FeedBuf, ItemBuf, SkipDays, SkipHours, TextBuf, TextTy};
+use rusqlite::{
+    params_from_iter,
+    types::{Type, Value},
+    Connection, OptionalExtension, Row, ToSql,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{state::ItemState, Result, Store};
+
+/// The metadata of a subscription's feed document.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StoredFeed {
+    pub subscription_id: i64,
+    pub feed: FeedBuf,
+    /// When the metadata was first stored.
+    pub created_at: DateTime<Utc>,
+    /// When the metadata was last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// An item from a subscription's feed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StoredItem {
+    pub id: i64,
+    pub subscription_id: i64,
+    /// The item's [key][folwup_core::feed::ItemKey] which is unique per
+    /// subscription.
+    pub key: String,
+    pub item: ItemBuf,
+    /// When the item was first stored.
+    pub created_at: DateTime<Utc>,
+    /// When the item was last changed.
+    pub updated_at: DateTime<Utc>,
+    pub state: ItemState,
+}
+
+/// The number of items in a feed document which were inserted, updated or
+/// unchanged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Upserted {
+    pub inserted: usize,
+    pub updated: usize,
+    /// The number of items which were unchanged or which were removed by a
+    /// retention policy.
+    pub unchanged: usize,
+}
+
+fn text(value: Option<String>) -> Value {
+    Value::from(value)
+}
+
+fn timestamp(value: Option<DateTime<Utc>>) -> Value {
+    Value::from(value.map(|v| v.timestamp()))
+}
+
+fn json<T: Serialize>(value: &T) -> rusqlite::Result<Value> {
+    serde_json::to_string(value)
+        .map(Value::from)
+        .map_err(|error| rusqlite::Error::ToSqlConversionFailure(Box::new(error)))
+}
+
+const fn ty_name(ty: TextTy) -> &'static str {
+    match ty {
+        TextTy::Text => "text",
+        TextTy::Html => "html",
+        TextTy::Xhtml => "xhtml",
+    }
+}
+
+/// Splits a text value into the value and type columns.
+fn text_buf(value: Option<TextBuf>) -> [Value; 2] {
+    match value {
+        Some(value) => [
+            Value::from(value.value),
+            Value::from(String::from(ty_name(value.ty))),
+        ],
+        None => [Value::Null, Value::Null],
+    }
+}
+
+fn get_text_buf(row: &Row<'_>, idx: usize) -> rusqlite::Result<Option<TextBuf>> {
+    let Some(value) = row.get::<_, Option<String>>(idx)? else {
+        return Ok(None);
+    };
+    let ty = match row.get::<_, Option<String>>(idx + 1)?.as_deref() {
+        Some("html") => TextTy::Html,
+        Some("xhtml") => TextTy::Xhtml,
+        _ => TextTy::Text,
+    };
+    Ok(Some(TextBuf { value, ty }))
+}
+
+pub(crate) fn get_timestamp(row: &Row<'_>, idx: usize) -> rusqlite::Result<Option<DateTime<Utc>>> {
+    row.get::<_, Option<i64>>(idx)?
+        .map(|secs| {
+            DateTime::from_timestamp(secs, 0)
+                .ok_or(rusqlite::Error::IntegralValueOutOfRange(idx, secs))
+        })
+        .transpose()
+}
+
+fn get_json<T: DeserializeOwned>(row: &Row<'_>, idx: usize) -> rusqlite::Result<T> {
+    let value = row.get_ref(idx)?.as_str()?;
+    serde_json::from_str(value).map_err(|error| {
+        rusqlite::Error::FromSqlConversionFailure(idx, Type::Text, Box::new(error))
+    })
+}
+
+/// The columns which are copied from a [`FeedBuf`].
+const FEED_COLUMNS: &[&str] = &[
+    "title",
+    "title_ty",
+    "description",
+    "description_ty",
+    "home_page_url",
+    "feed_url",
+    "alternate_url",
+    "authors",
+    "contributors",
+    "categories",
+    "language",
+    "icon",
+    "logo",
+    "modified_at",
+    "generator",
+    "rights",
+    "rights_ty",
+    "ttl",
+    "skip_hours",
+    "skip_days",
+];
+
+fn feed_values(feed: &FeedBuf) -> rusqlite::Result<Vec<Value>> {
+    let feed = feed.clone();
+    let [title, title_ty] = text_buf(feed.title);
+    let [description, description_ty] = text_buf(feed.description);
+    let [rights, rights_ty] = text_buf(feed.rights);
+    Ok(vec![
+        title,
+        title_ty,
+        description,
+        description_ty,
+        text(feed.home_page_url),
+        text(feed.feed_url),
+        text(feed.alternate_url),
+        json(&feed.authors)?,
+        json(&feed.contributors)?,
+        json(&feed.categories)?,
+        text(feed.language),
+        text(feed.icon),
+        text(feed.logo),
+        timestamp(feed.modified_at),
+        json(&feed.generator)?,
+        rights,
+        rights_ty,
+        Value::from(
+            feed.ttl
+                .map(|ttl| i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX)),
+        ),
+        Value::from(feed.skip_hours.bits()),
+        Value::from(feed.skip_days.bits()),
+    ])
+}
+
+impl StoredFeed {
+    /// Reads the row from `SELECT subscription_id, FEED_COLUMNS, created_at,
+    /// updated_at`.
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            subscription_id: row.get(0)?,
+            feed: FeedBuf {
+                title: get_text_buf(row, 1)?,
+                description: get_text_buf(row, 3)?,
+                home_page_url: row.get(5)?,
+                feed_url: row.get(6)?,
+                alternate_url: row.get(7)?,
+                authors: get_json(row, 8)?,
+                contributors: get_json(row, 9)?,
+                categories: get_json(row, 10)?,
+                language: row.get(11)?,
+                icon: row.get(12)?,
+                logo: row.get(13)?,
+                modified_at: get_timestamp(row, 14)?,
+                generator: get_json(row, 15)?,
+                rights: get_text_buf(row, 16)?,
+                ttl: row
+                    .get::<_, Option<i64>>(18)?
+                    .map(|secs| Duration::from_secs(secs.unsigned_abs())),
+                skip_hours: SkipHours::from_bits(row.get(19)?),
+                skip_days: SkipDays::from_bits(row.get(20)?),
+                xml_base: None,
+            },
+            created_at: get_timestamp(row, 21)?.unwrap_or_default(),
+            updated_at: get_timestamp(row, 22)?.unwrap_or_default(),
+        })
+    }
+}
+
+/// The columns which are copied from an [`ItemBuf`].
+pub(crate) const ITEM_COLUMNS: &[&str] = &[
+    "source_id",
+    "id_is_permalink",
+    "title",
+    "title_ty",
+    "content",
+    "content_ty",
+    "summary",
+    "summary_ty",
+    "url",
+    "external_url",
+    "published_at",
+    "modified_at",
+    "authors",
+    "contributors",
+    "categories",
+    "attachments",
+    "podcast",
+];
+
+fn item_values(item: ItemBuf) -> rusqlite::Result<Vec<Value>> {
+    let [title, title_ty] = text_buf(item.title);
+    let [content, content_ty] = text_buf(item.content);
+    let [summary, summary_ty] = text_buf(item.summary);
+    Ok(vec![
+        text(item.id),
+        Value::from(item.id_is_permalink),
+        title,
+        title_ty,
+        content,
+        content_ty,
+        summary,
+        summary_ty,
+        text(item.url),
+        text(item.external_url),
+        timestamp(item.published_at),
+        timestamp(item.modified_at),
+        json(&item.authors)?,
+        json(&item.contributors)?,
+        json(&item.categories)?,
+        json(&item.attachments)?,
+        json(&item.podcast)?,
+    ])
+}
+
+impl StoredItem {
+    /// The columns to select for [`StoredItem::from_row()`].
+    pub(crate) fn columns() -> String {
+        format!(
+            "items.id, items.subscription_id, items.key, {}, items.created_at, items.updated_at, {}",
+            ITEM_COLUMNS
+                .iter()
+                .map(|column| format!("items.{column}"))
+                .collect::<Vec<_>>()
+                .join(", "),
+            ItemState::COLUMNS,
+        )
+    }
+
+    pub(crate) fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            subscription_id: row.get(1)?,
+            key: row.get(2)?,
+            item: ItemBuf {
+                id: row.get(3)?,
+                id_is_permalink: row.get(4)?,
+                title: get_text_buf(row, 5)?,
+                content: get_text_buf(row, 7)?,
+                summary: get_text_buf(row, 9)?,
+                url: row.get(11)?,
+                external_url: row.get(12)?,
+                published_at: get_timestamp(row, 13)?,
+                modified_at: get_timestamp(row, 14)?,
+                authors: get_json(row, 15)?,
+                contributors: get_json(row, 16)?,
+                categories: get_json(row, 17)?,
+                attachments: get_json(row, 18)?,
+                podcast: get_json(row, 19)?,
+                xml_base: None,
+            },
+            created_at: get_timestamp(row, 20)?.unwrap_or_default(),
+            updated_at: get_timestamp(row, 21)?.unwrap_or_default(),
+            state: ItemState::from_row(row, 22)?,
+        })
+    }
+}
+
+/// Builds `INSERT INTO {table} (owner, key, columns) VALUES (?1, ?2, ...)`.
+fn insert_sql(table: &str, owner: &str, key: Option<&str>, columns: &[&str]) -> String {
+    let mut names = vec![owner];
+    names.extend(key);
+    names.extend(columns);
+    let params = (1..=names.len())
+        .map(|idx| format!("?{idx}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "INSERT INTO {table} ({}) VALUES ({params})",
+        names.join(", ")
+    )
+}
+
+/// Builds an `UPDATE` of the columns (from parameter `?{offset + 1}`) for the
+/// row where `id_column = ?1` and any of the values changed.
+fn update_sql(table: &str, id_column: &str, columns: &[&str], offset: usize) -> String {
+    let set = columns
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| format!("{column} = ?{}", idx + offset + 1))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let changed = columns
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| format!("{column} IS NOT ?{}", idx + offset + 1))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+    format!(
+        "UPDATE {table} SET {set}, updated_at = unixepoch() WHERE {id_column} = ?1 AND ({changed})"
+    )
+}
+
+fn upsert_feed(conn: &Connection, subscription_id: i64, feed: &FeedBuf) -> Result<()> {
+    let values = feed_values(feed)?;
+    let params = || {
+        core::iter::once(&subscription_id as &dyn ToSql)
+            .chain(values.iter().map(|v| v as &dyn ToSql))
+    };
+
+    let updated = conn
+        .prepare_cached(&update_sql("feeds", "subscription_id", FEED_COLUMNS, 1))?
+        .execute(params_from_iter(params()))?;
+    if updated == 0 {
+        conn.prepare_cached(&format!(
+            "{} ON CONFLICT (subscription_id) DO NOTHING",
+            insert_sql("feeds", "subscription_id", None, FEED_COLUMNS)
+        ))?
+        .execute(params_from_iter(params()))?;
+    }
+
+    Ok(())
+}
+
+/// Inserts or updates an item and returns its key.
+fn upsert_item(
+    conn: &Connection,
+    subscription_id: i64,
+    mut item: ItemBuf,
+    upserted: &mut Upserted,
+) -> Result<String> {
+    let key = item.key().to_string();
+
+    let existing = conn
+        .prepare_cached(
+            "SELECT id, content_pruned_at IS NOT NULL FROM items
+             WHERE subscription_id = ?1 AND key = ?2",
+        )?
+        .query_row(rusqlite::params![subscription_id, key], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, bool>(1)?))
+        })
+        .optional()?;
+
+    if existing.is_none()
+        && conn
+            .prepare_cached("SELECT 1 FROM pruned_items WHERE subscription_id = ?1 AND key = ?2")?
+            .exists(rusqlite::params![subscription_id, key])?
+    {
+        upserted.unchanged += 1;
+        return Ok(key);
+    }
+    if let Some((_, true)) = existing {
+        item.content = None;
+    }
+
+    let values = item_values(item)?;
+
+    if let Some((id, _)) = existing {
+        let params =
+            core::iter::once(&id as &dyn ToSql).chain(values.iter().map(|v| v as &dyn ToSql));
+        let updated = conn
+            .prepare_cached(&update_sql("items", "id", ITEM_COLUMNS, 1))?
+            .execute(params_from_iter(params))?;
+        if updated == 0 {
+            upserted.unchanged += 1;
+        } else {
+            upserted.updated += 1;
+        }
+    } else {
+        let params = [&subscription_id as &dyn ToSql, &key]
+            .into_iter()
+            .chain(values.iter().map(|v| v as &dyn ToSql));
+        conn.prepare_cached(&insert_sql(
+            "items",
+            "subscription_id",
+            Some("key"),
+            ITEM_COLUMNS,
+        ))?
+        .execute(params_from_iter(params))?;
+        upserted.inserted += 1;
+    }
+
+    Ok(key)
+}
+
+impl Store {
+    /// Stores a subscription's feed metadata and items.
+    ///
+    /// Items are identified by their [key][ItemBuf::key()], so storing the
+    /// same document again does not change anything, and an edited item is
+    /// updated in place. Items which are no longer in the document are kept.
+    ///
+    /// Items removed by [`prune()`][Store::prune()] are not stored again
+    /// while they are in the document. Once they are no longer in the
+    /// document, they are forgotten.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subscription does not exist or if the
+    /// database returns an error. Nothing is stored if an error is returned.
+    pub fn upsert_feed<I>(
+        &mut self,
+        subscription_id: i64,
+        feed: &FeedBuf,
+        items: I,
+    ) -> Result<Upserted>
+    where
+        I: IntoIterator,
+        I::Item: Into<ItemBuf>,
+    {
+        let tx = self.conn.transaction()?;
+
+        upsert_feed(&tx, subscription_id, feed)?;
+
+        let mut upserted = Upserted::default();
+        let mut keys = Vec::new();
+        for item in items {
+            keys.push(upsert_item(
+                &tx,
+                subscription_id,
+                item.into(),
+                &mut upserted,
+            )?);
+        }
+
+        tx.execute(
+            "DELETE FROM pruned_items
+             WHERE subscription_id = ?1 AND key NOT IN (SELECT value FROM json_each(?2))",
+            rusqlite::params![subscription_id, json(&keys)?],
+        )?;
+
+        tx.commit()?;
+        Ok(upserted)
+    }
+
+    /// The stored feed metadata for a subscription.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn feed(&self, subscription_id: i64) -> Result<Option<StoredFeed>> {
+        let feed = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT subscription_id, {}, created_at, updated_at FROM feeds WHERE subscription_id = ?1",
+                    FEED_COLUMNS.join(", ")
+                ),
+                [subscription_id],
+                StoredFeed::from_row,
+            )
+            .optional()?;
+        Ok(feed)
+    }
+
+    /// The stored item with the key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn item_by_key(&self, subscription_id: i64, key: &str) -> Result<Option<StoredItem>> {
+        let item = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM items WHERE subscription_id = ?1 AND key = ?2",
+                    StoredItem::columns()
+                ),
+                rusqlite::params![subscription_id, key],
+                StoredItem::from_row,
+            )
+            .optional()?;
+        Ok(item)
+    }
+
+    /// A subscription's items with the most recently published first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn items(&self, subscription_id: i64) -> Result<Vec<StoredItem>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM items WHERE subscription_id = ?1
+             ORDER BY ifnull(published_at, created_at) DESC, id DESC",
+            StoredItem::columns()
+        ))?;
+        let items = stmt
+            .query_map([subscription_id], StoredItem::from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(items)
+    }
+
+    /// The items of the subscriptions in a folder and its descendant
+    /// folders with the most recently published first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn folder_items(&self, folder_id: i64) -> Result<Vec<StoredItem>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "WITH RECURSIVE descendants (id) AS (
+                SELECT ?1
+                UNION
+                SELECT folders.id FROM folders JOIN descendants ON folders.parent_id = descendants.id
+             )
+             SELECT {} FROM items
+             JOIN subscriptions ON subscriptions.id = items.subscription_id
+             WHERE subscriptions.folder_id IN descendants
+             ORDER BY ifnull(items.published_at, items.created_at) DESC, items.id DESC",
+            StoredItem::columns()
+        ))?;
+        let items = stmt
+            .query_map([folder_id], StoredItem::from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(items)
+    }
+
+    /// The items of the subscriptions with a tag with the most recently
+    /// published first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn tag_items(&self, tag: &str) -> Result<Vec<StoredItem>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM items
+             JOIN subscription_tags ON subscription_tags.subscription_id = items.subscription_id
+             JOIN tags ON tags.id = subscription_tags.tag_id
+             WHERE tags.name = ?1
+             ORDER BY ifnull(items.published_at, items.created_at) DESC, items.id DESC",
+            StoredItem::columns()
+        ))?;
+        let items = stmt
+            .query_map([tag.trim()], StoredItem::from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{store_with, upsert};
+
+    use super::*;
+
+    const INPUT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0">
+  <channel>
+    <title>Example</title>
+    <link>https://example.com/</link>
+    <description>An &lt;b&gt;example&lt;/b&gt;</description>
+    <ttl>60</ttl>
+    <item>
+      <title>Item 1</title>
+      <link>https://example.com/1</link>
+      <description>&lt;p&gt;First&lt;/p&gt;</description>
+      <pubDate>Tue, 23 Feb 2021 09:08:10 GMT</pubDate>
+      <author>john@example.com (John Doe)</author>
+      <category>Rust</category>
+      <enclosure url="https://example.com/1.mp3" length="1024" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Item 2</title>
+      <guid isPermaLink="false">item-2</guid>
+      <pubDate>Wed, 24 Feb 2021 09:08:10 GMT</pubDate>
+    </item>
+  </channel>
+</rss>
+"#;
+
+    #[test]
+    fn idempotent() {
+        let mut store = Store::open_in_memory().unwrap();
+        let id = store
+            .add_subscription("https://example.com/feed.xml", None, None)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            Upserted {
+                inserted: 2,
+                updated: 0,
+                unchanged: 0
+            },
+            upsert(&mut store, id, INPUT)
+        );
+        let feed = store.feed(id).unwrap().unwrap();
+        let items = store.items(id).unwrap();
+
+        assert_eq!(
+            Upserted {
+                inserted: 0,
+                updated: 0,
+                unchanged: 2
+            },
+            upsert(&mut store, id, INPUT)
+        );
+        assert_eq!(feed, store.feed(id).unwrap().unwrap());
+        assert_eq!(items, store.items(id).unwrap());
+
+        assert_eq!(
+            Some("An <b>example</b>"),
+            feed.feed.description.as_ref().map(|v| v.value.as_str())
+        );
+        assert_eq!(Some(3600), feed.feed.ttl.map(|ttl| ttl.as_secs()));
+
+        assert_eq!(2, items.len());
+        assert_eq!("id:item-2", items[0].key);
+        let item = &items[1].item;
+        assert_eq!("url:https://example.com/1", items[1].key);
+        assert_eq!(
+            Some(TextTy::Html),
+            item.summary.as_ref().map(|summary| summary.ty)
+        );
+        assert_eq!("2021-02-23T09:08:10Z".parse().ok(), item.published_at);
+        assert_eq!(Some("John Doe"), item.authors[0].name.as_deref());
+        assert_eq!("Rust", item.categories[0].term);
+        assert_eq!(Some(1024), item.attachments[0].size_in_bytes);
+    }
+
+    #[test]
+    fn update_in_place() {
+        let (mut store, id) = store_with(INPUT);
+        let before = store.item_by_key(id, "id:item-2").unwrap().unwrap();
+
+        let edited = INPUT
+            .replace("<title>Item 2</title>", "<title>Item 2 (edited)</title>")
+            .replace("<category>Rust</category>", "<category>Go</category>");
+        assert_eq!(
+            Upserted {
+                inserted: 0,
+                updated: 2,
+                unchanged: 0
+            },
+            upsert(&mut store, id, &edited)
+        );
+
+        let items = store.items(id).unwrap();
+        assert_eq!(2, items.len());
+        assert_eq!(before.id, items[0].id);
+        assert_eq!(
+            Some("Item 2 (edited)"),
+            items[0].item.title.as_ref().map(|v| v.value.as_str())
+        );
+        assert_eq!("Go", items[1].item.categories[0].term);
+    }
+
+    #[test]
+    fn grouped_items() {
+        let mut store = Store::open_in_memory().unwrap();
+        let tech = store.folder_id(None, "Tech").unwrap();
+        let rust = store.folder_id(Some(tech), "Rust").unwrap();
+        let a = store
+            .add_subscription("https://example.com/feed.xml", None, Some(rust))
+            .unwrap()
+            .unwrap();
+        let b = store
+            .add_subscription("https://example.org/feed.xml", None, None)
+            .unwrap()
+            .unwrap();
+        upsert(&mut store, a, INPUT);
+        upsert(&mut store, b, INPUT);
+        store.add_tag(b, "news").unwrap();
+
+        assert_eq!(store.items(a).unwrap(), store.folder_items(tech).unwrap());
+        assert_eq!(store.items(a).unwrap(), store.folder_items(rust).unwrap());
+        assert_eq!(store.items(b).unwrap(), store.tag_items("News").unwrap());
+        assert!(store.tag_items("rust").unwrap().is_empty());
+    }
+
+    #[test]
+    fn missing_subscription() {
+        let mut store = Store::open_in_memory().unwrap();
+        let feed = FeedBuf::default();
+        assert!(store.upsert_feed(1, &feed, Vec::<ItemBuf>::new()).is_err());
+    }
+}
@@ -1,14 +1,148 @@
-pub fn add(left: usize, right: usize) -> usize {
-    left + right
+//! SQLite storage for subscriptions, feeds and items.
+
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    rust_2018_idioms,
+    unused_lifetimes,
+    unused_qualifications
+)]
+
+use std::{fmt, path::Path};
+
+use rusqlite::Connection;
+
+mod feed;
+mod migrations;
+mod opml;
+mod retention;
+mod search;
+mod state;
+mod subscription;
+mod tag;
+
+pub use feed::{StoredFeed, StoredItem, Upserted};
+pub use migrations::SCHEMA_VERSION;
+pub use retention::{Pruned, RetentionPolicy};
+pub use search::{Search, SearchResult};
+pub use state::ItemState;
+pub use subscription::{Folder, Subscription};
+
+/// Error when accessing the database.
+#[derive(Debug)]
+pub enum Error {
+    /// An error returned by SQLite.
+    Sqlite(rusqlite::Error),
+    /// The database was created by a newer version with a schema which is
+    /// not supported.
+    UnsupportedVersion { version: u32, supported: u32 },
+    /// A migration to the version would violate a foreign key constraint.
+    ForeignKeyViolation { version: u32 },
+    /// The folder cannot be moved into itself or one of its descendants.
+    FolderCycle { folder_id: i64 },
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Sqlite(error) => write!(f, "database error: {error}"),
+            Error::UnsupportedVersion { version, supported } => write!(
+                f,
+                "database schema version {version} is newer than the supported version {supported}"
+            ),
+            Error::ForeignKeyViolation { version } => write!(
+                f,
+                "migrating the database to schema version {version} violates a foreign key constraint"
+            ),
+            Error::FolderCycle { folder_id } => write!(
+                f,
+                "folder {folder_id} cannot be moved into itself or one of its descendants"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Sqlite(error) => Some(error),
+            Error::UnsupportedVersion { .. }
+            | Error::ForeignKeyViolation { .. }
+            | Error::FolderCycle { .. } => None,
+        }
+    }
+}
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+impl From<rusqlite::Error> for Error {
+    fn from(value: rusqlite::Error) -> Self {
+        Self::Sqlite(value)
     }
 }
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// A connection to a folwup database.
+#[derive(Debug)]
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    /// Opens or creates the database at the path.
+    ///
+    /// The schema is upgraded if the database was created by an older
+    /// version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or migrated, or if
+    /// the database was created by a newer version.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Opens a new in-memory database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be initialized.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(mut conn: Connection) -> Result<Self> {
+        search::register_functions(&conn)?;
+        // The vacuum mode can only be changed without a `VACUUM` before any
+        // tables are created.
+        let is_empty = !conn.prepare("SELECT 1 FROM sqlite_schema")?.exists([])?;
+        if is_empty {
+            conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")?;
+        }
+        migrations::migrate(&mut conn)?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        Ok(Self { conn })
+    }
+}
+
+/// Creates an in-memory store with a subscription to
+/// `https://example.com/feed.xml` which has the feed document's items.
+#[cfg(test)]
+fn store_with(input: &str) -> (Store, i64) {
+    let mut store = Store::open_in_memory().unwrap();
+    let id = store
+        .add_subscription("https://example.com/feed.xml", None, None)
+        .unwrap()
+        .unwrap();
+    upsert(&mut store, id, input);
+    (store, id)
+}
+
+/// Stores the feed document for the subscription.
+#[cfg(test)]
+fn upsert(store: &mut Store, id: i64, input: &str) -> Upserted {
+    use folwup_core::feed;
+
+    let feed = feed::parse_feed_with_base(input, "https://example.com/feed.xml").unwrap();
+    let items = feed::Iter::with_str_and_base(input, "https://example.com/feed.xml").unwrap();
+    store.upsert_feed(id, &feed, items).unwrap()
+}
@@ -0,0 +1,257 @@
+//! Versioned changes to the schema.
+//!
+//! The schema version is stored in `PRAGMA user_version`. Each migration
+//! upgrades the schema from the version before it and runs in its own
+//! transaction.
+//!
+//! Migrations must never be edited once released. Add a new migration and a
+//! fixture of a database at the previous version instead.
+
+use rusqlite::Connection;
+
+use crate::{Error, Result};
+
+/// The migrations in order. Migration `n` upgrades version `n` to `n + 1`.
+const MIGRATIONS: &[&str] = &[
+    include_str!("migrations/0001_subscriptions.sql"),
+    include_str!("migrations/0002_feeds_and_items.sql"),
+    include_str!("migrations/0003_item_state.sql"),
+    include_str!("migrations/0004_item_search.sql"),
+    include_str!("migrations/0005_tags.sql"),
+    include_str!("migrations/0006_retention.sql"),
+    include_str!("migrations/0007_fetch_state.sql"),
+];
+
+/// The schema version which this version of the crate uses.
+#[allow(clippy::cast_possible_truncation)]
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+/// Returns the schema version of the database.
+pub(crate) fn version(conn: &Connection) -> Result<u32> {
+    Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+}
+
+/// Upgrades the schema to the [`SCHEMA_VERSION`].
+///
+/// Returns an error without changing the database if the database's
+/// version is newer.
+pub(crate) fn migrate(conn: &mut Connection) -> Result<()> {
+    let version = version(conn)?;
+    if version > SCHEMA_VERSION {
+        return Err(Error::UnsupportedVersion {
+            version,
+            supported: SCHEMA_VERSION,
+        });
+    }
+    if version == SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    // Migrations which rebuild a table need the foreign key constraints to be
+    // disabled. The pragma cannot be changed within a transaction so the
+    // constraints are checked before each migration is committed.
+    conn.pragma_update(None, "foreign_keys", false)?;
+    let result = (version..SCHEMA_VERSION).try_for_each(|from| {
+        let tx = conn.transaction()?;
+        tx.execute_batch(MIGRATIONS[from as usize])?;
+        let has_violations = tx.prepare("PRAGMA foreign_key_check")?.exists([])?;
+        if has_violations {
+            return Err(Error::ForeignKeyViolation { version: from + 1 });
+        }
+        tx.pragma_update(None, "user_version", from + 1)?;
+        tx.commit()?;
+        Ok(())
+    });
+    conn.pragma_update(None, "foreign_keys", true)?;
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Search, Store};
+
+    use super::*;
+
+    /// The tables and columns in the schema.
+    fn columns(conn: &Connection) -> Vec<(String, String, String, bool, Option<String>)> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT m.name, p.name, p.type, p.\"notnull\", p.dflt_value
+                 FROM sqlite_schema AS m JOIN pragma_table_info(m.name) AS p
+                 WHERE m.type = 'table'
+                 ORDER BY m.name, p.cid",
+            )
+            .unwrap();
+        stmt.query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })
+        .unwrap()
+        .collect::<rusqlite::Result<_>>()
+        .unwrap()
+    }
+
+    fn indexes(conn: &Connection) -> Vec<(String, Option<String>)> {
+        let mut stmt = conn
+            .prepare(
+                "SELECT name, sql FROM sqlite_schema
+                 WHERE type IN ('index', 'trigger', 'view') ORDER BY name",
+            )
+            .unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    /// Opens a fixture of a database created by a previous version.
+    fn open_fixture(sql: &str) -> Store {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(sql).unwrap();
+        Store::with_connection(conn).unwrap()
+    }
+
+    fn assert_current_schema(store: &Store) {
+        let new = Store::open_in_memory().unwrap();
+        assert_eq!(SCHEMA_VERSION, version(&store.conn).unwrap());
+        assert_eq!(columns(&new.conn), columns(&store.conn));
+        assert_eq!(indexes(&new.conn), indexes(&store.conn));
+    }
+
+    fn assert_subscriptions(store: &Store) {
+        let subscriptions = store.subscriptions().unwrap();
+        assert_eq!(2, subscriptions.len());
+        assert_eq!("https://example.com/feed.xml", subscriptions[0].url);
+        assert_eq!(Some("This Week in Rust"), subscriptions[1].title.as_deref());
+
+        let folders = store.folders().unwrap();
+        assert_eq!(2, folders.len());
+        assert_eq!(subscriptions[1].folder_id, Some(folders[0].id));
+        assert_eq!("Rust", folders[0].name);
+    }
+
+    #[test]
+    fn new_database() {
+        let store = Store::open_in_memory().unwrap();
+        assert_eq!(SCHEMA_VERSION, version(&store.conn).unwrap());
+        let is_enabled: bool = store
+            .conn
+            .pragma_query_value(None, "foreign_keys", |row| row.get(0))
+            .unwrap();
+        assert!(is_enabled);
+        let auto_vacuum: i64 = store
+            .conn
+            .pragma_query_value(None, "auto_vacuum", |row| row.get(0))
+            .unwrap();
+        assert_eq!(2, auto_vacuum);
+    }
+
+    #[test]
+    fn from_unversioned() {
+        let store = open_fixture(include_str!("../fixtures/v0.sql"));
+        assert_current_schema(&store);
+        assert_subscriptions(&store);
+
+        let subscription_id = store.subscriptions().unwrap()[1].id;
+        let feed = store.feed(subscription_id).unwrap().unwrap();
+        assert_eq!(
+            Some("This Week in Rust"),
+            feed.feed.title.map(|title| title.value).as_deref()
+        );
+        let items = store.items(subscription_id).unwrap();
+        assert_eq!(1, items.len());
+        assert_eq!(
+            Some("TWiR Contributors"),
+            items[0].item.authors[0].name.as_deref()
+        );
+    }
+
+    #[test]
+    fn from_version_1() {
+        let store = open_fixture(include_str!("../fixtures/v1.sql"));
+        assert_current_schema(&store);
+        assert_subscriptions(&store);
+    }
+
+    #[test]
+    fn from_version_2() {
+        let store = open_fixture(include_str!("../fixtures/v2.sql"));
+        assert_current_schema(&store);
+        assert_subscriptions(&store);
+
+        let subscription_id = store.subscriptions().unwrap()[1].id;
+        let items = store.items(subscription_id).unwrap();
+        assert_eq!(1, items.len());
+        assert!(!items[0].state.is_read());
+    }
+
+    #[test]
+    fn from_version_3() {
+        let store = open_fixture(include_str!("../fixtures/v3.sql"));
+        assert_current_schema(&store);
+        assert_subscriptions(&store);
+
+        let results = store.search(&Search::new("hello")).unwrap();
+        assert_eq!(1, results.len());
+        assert_eq!("[Hello] Rust!", results[0].snippet);
+    }
+
+    #[test]
+    fn from_version_4() {
+        let store = open_fixture(include_str!("../fixtures/v4.sql"));
+        assert_current_schema(&store);
+        assert_subscriptions(&store);
+        assert!(store.tags().unwrap().is_empty());
+
+        let results = store.search(&Search::new("contributors")).unwrap();
+        assert_eq!(1, results.len());
+    }
+
+    #[test]
+    fn from_version_5() {
+        let store = open_fixture(include_str!("../fixtures/v5.sql"));
+        assert_current_schema(&store);
+        assert_subscriptions(&store);
+
+        let subscription_id = store.subscriptions().unwrap()[1].id;
+        assert_eq!(
+            vec!["weekly"],
+            store.subscription_tags(subscription_id).unwrap()
+        );
+        assert_eq!(None, store.retention_policy(None).unwrap());
+    }
+
+    #[test]
+    fn from_version_6() {
+        let store = open_fixture(include_str!("../fixtures/v6.sql"));
+        assert_current_schema(&store);
+        assert_subscriptions(&store);
+
+        let policy = store.retention_policy(None).unwrap().unwrap();
+        assert_eq!(Some(100), policy.max_items);
+        assert!(store
+            .subscriptions()
+            .unwrap()
+            .iter()
+            .all(|s| s.etag.is_none() && s.fetched_at.is_none()));
+    }
+
+    #[test]
+    fn newer_version() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+
+        assert!(matches!(
+            Store::with_connection(conn),
+            Err(Error::UnsupportedVersion { version, supported })
+                if version == SCHEMA_VERSION + 1 && supported == SCHEMA_VERSION
+        ));
+    }
+}
@@ -0,0 +1,11 @@
+-- Read, starred and archived state of items.
+--
+-- An item is read if `read_at` is not null.
+
+ALTER TABLE items ADD COLUMN read_at INTEGER;
+ALTER TABLE items ADD COLUMN is_starred INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE items ADD COLUMN is_archived INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX items_subscription_id_read_at ON items (subscription_id, read_at);
+
+CREATE INDEX items_is_starred ON items (is_starred) WHERE is_starred;
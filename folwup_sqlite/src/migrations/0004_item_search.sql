@@ -0,0 +1,46 @@
+-- Full-text search over items.
+--
+-- The triggers copy the text of an item into the index. Markup is removed
+-- with the `strip_html` function which is registered on each connection, so
+-- items cannot be changed by connections without the function.
+
+CREATE VIRTUAL TABLE items_search USING fts5 (
+    title,
+    summary,
+    content,
+    authors,
+    tokenize = 'porter unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER items_search_insert AFTER INSERT ON items BEGIN
+    INSERT INTO items_search (rowid, title, summary, content, authors) VALUES (
+        new.id,
+        strip_html(new.title, new.title_ty),
+        strip_html(new.summary, new.summary_ty),
+        strip_html(new.content, new.content_ty),
+        (SELECT group_concat(value ->> 'name', ' ') FROM json_each(new.authors))
+    );
+END;
+
+CREATE TRIGGER items_search_update
+AFTER UPDATE OF title, title_ty, summary, summary_ty, content, content_ty, authors ON items BEGIN
+    UPDATE items_search SET
+        title = strip_html(new.title, new.title_ty),
+        summary = strip_html(new.summary, new.summary_ty),
+        content = strip_html(new.content, new.content_ty),
+        authors = (SELECT group_concat(value ->> 'name', ' ') FROM json_each(new.authors))
+    WHERE rowid = new.id;
+END;
+
+CREATE TRIGGER items_search_delete AFTER DELETE ON items BEGIN
+    DELETE FROM items_search WHERE rowid = old.id;
+END;
+
+INSERT INTO items_search (rowid, title, summary, content, authors)
+SELECT
+    id,
+    strip_html(title, title_ty),
+    strip_html(summary, summary_ty),
+    strip_html(content, content_ty),
+    (SELECT group_concat(value ->> 'name', ' ') FROM json_each(authors))
+FROM items;
@@ -0,0 +1,16 @@
+-- Free-form tags for subscriptions.
+
+CREATE TABLE tags (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE
+);
+
+CREATE TABLE subscription_tags (
+    subscription_id INTEGER NOT NULL REFERENCES subscriptions (id) ON DELETE CASCADE,
+    tag_id INTEGER NOT NULL REFERENCES tags (id) ON DELETE CASCADE,
+    PRIMARY KEY (subscription_id, tag_id)
+) WITHOUT ROWID;
+
+CREATE INDEX subscription_tags_tag_id ON subscription_tags (tag_id);
+
+CREATE INDEX subscriptions_folder_id ON subscriptions (folder_id);
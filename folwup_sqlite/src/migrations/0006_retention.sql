@@ -0,0 +1,26 @@
+-- Retention policies and the items which they removed.
+--
+-- A policy without a subscription applies to every subscription without its
+-- own policy.
+
+CREATE TABLE retention_policies (
+    id INTEGER PRIMARY KEY,
+    subscription_id INTEGER REFERENCES subscriptions (id) ON DELETE CASCADE,
+    max_items INTEGER,
+    max_age_days INTEGER,
+    keep_starred INTEGER NOT NULL DEFAULT 1,
+    content_max_age_days INTEGER
+);
+
+CREATE UNIQUE INDEX retention_policies_subscription_id
+    ON retention_policies (ifnull(subscription_id, 0));
+
+-- Removed items are remembered so they are not stored again while they are
+-- still in the feed.
+CREATE TABLE pruned_items (
+    subscription_id INTEGER NOT NULL REFERENCES subscriptions (id) ON DELETE CASCADE,
+    key TEXT NOT NULL,
+    PRIMARY KEY (subscription_id, key)
+) WITHOUT ROWID;
+
+ALTER TABLE items ADD COLUMN content_pruned_at INTEGER;
@@ -0,0 +1,5 @@
+-- The cache validators of the last response for each subscription.
+
+ALTER TABLE subscriptions ADD COLUMN etag TEXT;
+ALTER TABLE subscriptions ADD COLUMN last_modified TEXT;
+ALTER TABLE subscriptions ADD COLUMN fetched_at INTEGER;
@@ -0,0 +1,224 @@
+//! Import and export of subscriptions as OPML.
+
+use std::collections::HashMap;
+
+use folwup_core::opml::{Opml, Outline};
+use rusqlite::Connection;
+
+use crate::{
+    subscription::{self, Folder, Subscription},
+    tag, Result, Store,
+};
+
+/// Inserts the subscriptions in the outlines and returns the number of new
+/// subscriptions.
+fn import_outlines(
+    conn: &Connection,
+    folder_id: Option<i64>,
+    outlines: &[Outline],
+) -> Result<usize> {
+    let mut added = 0;
+
+    for outline in outlines {
+        if let Some(url) = outline.xml_url.as_deref() {
+            // An untitled subscription is exported with its URL as the text.
+            let title = outline
+                .title
+                .as_deref()
+                .or(outline.text.as_deref())
+                .filter(|title| title.trim() != url.trim());
+            if let Some(id) = subscription::insert_subscription(
+                conn,
+                url.trim(),
+                title,
+                outline.html_url.as_deref(),
+                folder_id,
+            )? {
+                // Categories with a `/` are paths instead of tags.
+                for category in outline.categories.iter().filter(|c| !c.contains('/')) {
+                    tag::insert_tag(conn, id, category)?;
+                }
+                added += 1;
+            }
+            // Some exporters nest other subscriptions in a subscription.
+            added += import_outlines(conn, folder_id, &outline.children)?;
+        } else if let Some(name) = outline.name() {
+            let child_id = subscription::folder_id(conn, folder_id, name)?;
+            added += import_outlines(conn, Some(child_id), &outline.children)?;
+        } else {
+            added += import_outlines(conn, folder_id, &outline.children)?;
+        }
+    }
+
+    Ok(added)
+}
+
+fn subscription_outline(
+    subscription: Subscription,
+    tags: &mut HashMap<i64, Vec<String>>,
+) -> Outline {
+    Outline {
+        categories: tags.remove(&subscription.id).unwrap_or_default(),
+        text: Some(
+            subscription
+                .title
+                .clone()
+                .unwrap_or_else(|| subscription.url.clone()),
+        ),
+        title: subscription.title,
+        ty: Some(String::from("rss")),
+        xml_url: Some(subscription.url),
+        html_url: subscription.html_url,
+        children: Vec::new(),
+    }
+}
+
+/// Builds the outlines for a folder's child folders and then its
+/// subscriptions.
+fn folder_outlines(
+    folder_id: Option<i64>,
+    folders: &HashMap<Option<i64>, Vec<Folder>>,
+    subscriptions: &mut HashMap<Option<i64>, Vec<Subscription>>,
+    tags: &mut HashMap<i64, Vec<String>>,
+) -> Vec<Outline> {
+    let mut outlines = Vec::new();
+
+    for folder in folders.get(&folder_id).into_iter().flatten() {
+        outlines.push(Outline {
+            text: Some(folder.name.clone()),
+            title: Some(folder.name.clone()),
+            children: folder_outlines(Some(folder.id), folders, subscriptions, tags),
+            ..Outline::default()
+        });
+    }
+
+    outlines.extend(
+        subscriptions
+            .remove(&folder_id)
+            .into_iter()
+            .flatten()
+            .map(|subscription| subscription_outline(subscription, tags)),
+    );
+
+    outlines
+}
+
+impl Store {
+    /// Subscribes to the feeds in an OPML document.
+    ///
+    /// Outlines without an `xmlUrl` are imported as folders and categories
+    /// without a `/` are imported as tags. Feeds which are already subscribed
+    /// to are not changed.
+    ///
+    /// Returns the number of new subscriptions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error. No subscriptions
+    /// are imported if an error is returned.
+    pub fn import_opml(&mut self, opml: &Opml) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let added = import_outlines(&tx, None, &opml.outlines)?;
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// Exports the subscriptions as an OPML document.
+    ///
+    /// Folders are exported as outlines which contain their subscriptions.
+    /// Tags are exported as categories.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn export_opml(&self) -> Result<Opml> {
+        let mut folders = HashMap::<_, Vec<_>>::new();
+        for folder in self.folders()? {
+            folders.entry(folder.parent_id).or_default().push(folder);
+        }
+        let mut subscriptions = HashMap::<_, Vec<_>>::new();
+        for subscription in self.subscriptions()? {
+            subscriptions
+                .entry(subscription.folder_id)
+                .or_default()
+                .push(subscription);
+        }
+
+        let mut tags = tag::subscription_tags(&self.conn)?;
+
+        Ok(Opml {
+            title: Some(String::from("folwup subscriptions")),
+            outlines: folder_outlines(None, &folders, &mut subscriptions, &mut tags),
+            ..Opml::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use folwup_core::opml;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let input = r#"<?xml version="1.0" encoding="utf-8"?>
+<opml version="2.0">
+  <head><title>Subscriptions</title></head>
+  <body>
+    <outline text="Tech">
+      <outline text="Rust">
+        <outline text="This Week in Rust" type="rss" xmlUrl="https://this-week-in-rust.org/rss.xml" htmlUrl="https://this-week-in-rust.org/" category="weekly,rust,/Tech/Rust"/>
+      </outline>
+      <outline text="Hacker News" type="rss" xmlUrl="https://news.ycombinator.com/rss"/>
+    </outline>
+    <outline text="Empty"/>
+    <outline text="Example" type="rss" xmlUrl="https://example.com/feed.xml"/>
+    <outline text="Duplicate" type="rss" xmlUrl="https://news.ycombinator.com/rss"/>
+    <outline text="https://example.org/feed.xml" type="rss" xmlUrl="https://example.org/feed.xml"/>
+  </body>
+</opml>"#;
+
+        let mut store = Store::open_in_memory().unwrap();
+        let imported = opml::parse(input).unwrap();
+        assert_eq!(4, store.import_opml(&imported).unwrap());
+        assert_eq!(0, store.import_opml(&imported).unwrap());
+
+        let exported = store.export_opml().unwrap();
+        let names = |outlines: &[Outline]| {
+            outlines
+                .iter()
+                .map(|o| o.name().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            vec!["Empty", "Tech", "Example", "https://example.org/feed.xml"],
+            names(&exported.outlines)
+        );
+
+        let tech = &exported.outlines[1];
+        assert!(tech.is_folder());
+        assert_eq!(vec!["Rust", "Hacker News"], names(&tech.children));
+        let rust = &tech.children[0].children[0];
+        assert_eq!(
+            Some("https://this-week-in-rust.org/rss.xml"),
+            rust.xml_url.as_deref()
+        );
+        assert_eq!(
+            Some("https://this-week-in-rust.org/"),
+            rust.html_url.as_deref()
+        );
+        assert_eq!(vec!["rust", "weekly"], rust.categories);
+
+        let mut output = String::new();
+        opml::write(&mut output, &exported).unwrap();
+        let mut copy = Store::open_in_memory().unwrap();
+        assert_eq!(4, copy.import_opml(&opml::parse(&output).unwrap()).unwrap());
+        assert_eq!(exported, copy.export_opml().unwrap());
+        let untitled = copy
+            .subscription_by_url("https://example.org/feed.xml")
+            .unwrap()
+            .unwrap();
+        assert_eq!(None, untitled.title);
+    }
+}
@@ -0,0 +1,433 @@
+//! Retention policies which limit how many items are stored.
+//!
+//! A subscription's own policy replaces the global policy. Removed items are
+//! remembered, so an item is not stored again if it is still in the feed.
+
+use chrono::{DateTime, Days, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::{Result, Store};
+
+/// Rules for which items of a subscription are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RetentionPolicy {
+    /// Only keep the newest items.
+    pub max_items: Option<u32>,
+    /// Only keep items published (or else first stored) within the number
+    /// of days.
+    pub max_age_days: Option<u32>,
+    /// Never remove starred items or their content.
+    pub keep_starred: bool,
+    /// Remove the content but keep the rest of items older than the number
+    /// of days.
+    pub content_max_age_days: Option<u32>,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_items: None,
+            max_age_days: None,
+            keep_starred: true,
+            content_max_age_days: None,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    const COLUMNS: &'static str = "max_items, max_age_days, keep_starred, content_max_age_days";
+
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            max_items: row.get(0)?,
+            max_age_days: row.get(1)?,
+            keep_starred: row.get(2)?,
+            content_max_age_days: row.get(3)?,
+        })
+    }
+}
+
+/// The number of a subscription's items which were removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pruned {
+    pub subscription_id: i64,
+    /// The number of deleted items.
+    pub deleted: usize,
+    /// The number of items which had their content removed.
+    pub content_removed: usize,
+}
+
+/// Returns the time which is the number of days before `now`.
+fn cutoff(now: DateTime<Utc>, days: Option<u32>) -> Option<i64> {
+    days.map(|days| {
+        now.checked_sub_days(Days::new(u64::from(days)))
+            .unwrap_or(DateTime::<Utc>::MIN_UTC)
+            .timestamp()
+    })
+}
+
+/// Applies a policy to a subscription's items.
+fn prune_subscription(
+    conn: &Connection,
+    subscription_id: i64,
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+) -> Result<Pruned> {
+    // ?1 subscription, ?2 keep starred, ?3 max items, ?4 max age cutoff
+    const EXPIRED: &str = "subscription_id = ?1 AND NOT (?2 AND is_starred) AND (
+            id NOT IN (
+                SELECT id FROM items WHERE subscription_id = ?1
+                ORDER BY ifnull(published_at, created_at) DESC, id DESC
+                LIMIT ifnull(?3, -1)
+            )
+            OR ifnull(published_at, created_at) < ?4
+        )";
+
+    let params = params![
+        subscription_id,
+        policy.keep_starred,
+        policy.max_items,
+        cutoff(now, policy.max_age_days),
+    ];
+    conn.execute(
+        &format!(
+            "INSERT INTO pruned_items (subscription_id, key)
+             SELECT subscription_id, key FROM items WHERE {EXPIRED}
+             ON CONFLICT DO NOTHING"
+        ),
+        params,
+    )?;
+    let deleted = conn.execute(&format!("DELETE FROM items WHERE {EXPIRED}"), params)?;
+
+    let content_removed = conn.execute(
+        "UPDATE items SET content = NULL, content_ty = NULL, content_pruned_at = unixepoch()
+         WHERE subscription_id = ?1 AND NOT (?2 AND is_starred)
+            AND content IS NOT NULL AND ifnull(published_at, created_at) < ?3",
+        params![
+            subscription_id,
+            policy.keep_starred,
+            cutoff(now, policy.content_max_age_days),
+        ],
+    )?;
+
+    Ok(Pruned {
+        subscription_id,
+        deleted,
+        content_removed,
+    })
+}
+
+impl Store {
+    /// Sets the retention policy for a subscription, or the global policy if
+    /// the subscription is `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subscription does not exist or if the
+    /// database returns an error.
+    pub fn set_retention_policy(
+        &self,
+        subscription_id: Option<i64>,
+        policy: &RetentionPolicy,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO retention_policies
+                (subscription_id, max_items, max_age_days, keep_starred, content_max_age_days)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (ifnull(subscription_id, 0)) DO UPDATE SET
+                max_items = excluded.max_items,
+                max_age_days = excluded.max_age_days,
+                keep_starred = excluded.keep_starred,
+                content_max_age_days = excluded.content_max_age_days",
+            params![
+                subscription_id,
+                policy.max_items,
+                policy.max_age_days,
+                policy.keep_starred,
+                policy.content_max_age_days,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Removes the retention policy for a subscription, or the global policy
+    /// if the subscription is `None`.
+    ///
+    /// Returns false if there was no policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn remove_retention_policy(&self, subscription_id: Option<i64>) -> Result<bool> {
+        let deleted = self.conn.execute(
+            "DELETE FROM retention_policies WHERE subscription_id IS ?1",
+            [subscription_id],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// The retention policy for a subscription, or the global policy if the
+    /// subscription is `None`.
+    ///
+    /// The global policy is not returned for a subscription without its own
+    /// policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn retention_policy(
+        &self,
+        subscription_id: Option<i64>,
+    ) -> Result<Option<RetentionPolicy>> {
+        let policy = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM retention_policies WHERE subscription_id IS ?1",
+                    RetentionPolicy::COLUMNS
+                ),
+                [subscription_id],
+                RetentionPolicy::from_row,
+            )
+            .optional()?;
+        Ok(policy)
+    }
+
+    /// Removes items according to the retention policies.
+    ///
+    /// If `dry_run` is true, nothing is changed and the items which would be
+    /// removed are counted. Space is not reclaimed until
+    /// [`Store::reclaim_space()`] is called.
+    ///
+    /// Returns the subscriptions which had items removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error. Nothing is removed
+    /// if an error is returned.
+    pub fn prune(&mut self, now: DateTime<Utc>, dry_run: bool) -> Result<Vec<Pruned>> {
+        let tx = self.conn.transaction()?;
+
+        let policies = {
+            let mut stmt = tx.prepare(&format!(
+                "SELECT {}, subscriptions.id
+                 FROM subscriptions JOIN retention_policies
+                 ON retention_policies.id = ifnull(
+                    (SELECT id FROM retention_policies WHERE subscription_id = subscriptions.id),
+                    (SELECT id FROM retention_policies WHERE subscription_id IS NULL)
+                 )
+                 ORDER BY subscriptions.id",
+                RetentionPolicy::COLUMNS
+            ))?;
+            let policies = stmt
+                .query_map([], |row| {
+                    Ok((row.get::<_, i64>(4)?, RetentionPolicy::from_row(row)?))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            policies
+        };
+
+        let mut pruned = Vec::new();
+        for (subscription_id, policy) in policies {
+            let result = prune_subscription(&tx, subscription_id, &policy, now)?;
+            if result.deleted > 0 || result.content_removed > 0 {
+                pruned.push(result);
+            }
+        }
+
+        if !dry_run {
+            tx.commit()?;
+        }
+        Ok(pruned)
+    }
+
+    /// Returns unused space in the database file to the file system.
+    ///
+    /// A database created by an older version is converted to incremental
+    /// vacuuming the first time which rewrites the whole database.
+    ///
+    /// Returns the number of bytes reclaimed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn reclaim_space(&self) -> Result<u64> {
+        let pages = |conn: &Connection| -> Result<i64> {
+            Ok(conn.pragma_query_value(None, "page_count", |row| row.get(0))?)
+        };
+        let before = pages(&self.conn)?;
+
+        let auto_vacuum: i64 = self
+            .conn
+            .pragma_query_value(None, "auto_vacuum", |row| row.get(0))?;
+        // 2 is `INCREMENTAL`.
+        if auto_vacuum == 2 {
+            self.conn.execute_batch("PRAGMA incremental_vacuum")?;
+        } else {
+            self.conn
+                .pragma_update(None, "auto_vacuum", "INCREMENTAL")?;
+            self.conn.execute_batch("VACUUM")?;
+        }
+
+        let page_size: i64 = self
+            .conn
+            .pragma_query_value(None, "page_size", |row| row.get(0))?;
+        Ok(((before - pages(&self.conn)?) * page_size)
+            .try_into()
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{store_with, upsert};
+
+    use super::*;
+
+    const INPUT: &str = r#"<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+<channel><title>Example</title>
+<item><guid>1</guid><description>One</description><content:encoded>One</content:encoded><pubDate>Fri, 01 Jan 2021 00:00:00 GMT</pubDate></item>
+<item><guid>2</guid><description>Two</description><content:encoded>Two</content:encoded><pubDate>Mon, 01 Feb 2021 00:00:00 GMT</pubDate></item>
+<item><guid>3</guid><description>Three</description><content:encoded>Three</content:encoded><pubDate>Mon, 01 Mar 2021 00:00:00 GMT</pubDate></item>
+<item><guid>4</guid><description>Four</description><content:encoded>Four</content:encoded><pubDate>Thu, 01 Apr 2021 00:00:00 GMT</pubDate></item>
+</channel></rss>"#;
+
+    fn store() -> (Store, i64, i64) {
+        let (mut store, a) = store_with(INPUT);
+        let b = store
+            .add_subscription("https://example.org/feed.xml", None, None)
+            .unwrap()
+            .unwrap();
+        upsert(&mut store, b, INPUT);
+        (store, a, b)
+    }
+
+    fn keys(store: &Store, id: i64) -> Vec<String> {
+        store
+            .items(id)
+            .unwrap()
+            .into_iter()
+            .map(|item| item.key)
+            .collect()
+    }
+
+    #[test]
+    fn policies() {
+        let store = Store::open_in_memory().unwrap();
+        let id = store
+            .add_subscription("https://example.com/feed.xml", None, None)
+            .unwrap()
+            .unwrap();
+        let global = RetentionPolicy {
+            max_items: Some(100),
+            ..RetentionPolicy::default()
+        };
+        let own = RetentionPolicy {
+            max_age_days: Some(30),
+            keep_starred: false,
+            ..RetentionPolicy::default()
+        };
+
+        store.set_retention_policy(None, &global).unwrap();
+        store.set_retention_policy(Some(id), &global).unwrap();
+        store.set_retention_policy(Some(id), &own).unwrap();
+        assert_eq!(Some(global), store.retention_policy(None).unwrap());
+        assert_eq!(Some(own), store.retention_policy(Some(id)).unwrap());
+        assert!(store.set_retention_policy(Some(-1), &own).is_err());
+
+        assert!(store.remove_retention_policy(Some(id)).unwrap());
+        assert!(!store.remove_retention_policy(Some(id)).unwrap());
+        assert_eq!(None, store.retention_policy(Some(id)).unwrap());
+        assert_eq!(Some(global), store.retention_policy(None).unwrap());
+    }
+
+    #[test]
+    fn prune() {
+        let (mut store, a, b) = store();
+        let now = "2021-04-15T00:00:00Z".parse().unwrap();
+        let starred = store.item_by_key(a, "id:1").unwrap().unwrap().id;
+        store.set_starred(starred, true).unwrap();
+
+        store
+            .set_retention_policy(
+                None,
+                &RetentionPolicy {
+                    max_items: Some(2),
+                    ..RetentionPolicy::default()
+                },
+            )
+            .unwrap();
+        store
+            .set_retention_policy(
+                Some(b),
+                &RetentionPolicy {
+                    max_age_days: Some(50),
+                    content_max_age_days: Some(20),
+                    ..RetentionPolicy::default()
+                },
+            )
+            .unwrap();
+
+        let expected = vec![
+            Pruned {
+                subscription_id: a,
+                deleted: 1,
+                content_removed: 0,
+            },
+            Pruned {
+                subscription_id: b,
+                deleted: 2,
+                content_removed: 1,
+            },
+        ];
+        assert_eq!(expected, store.prune(now, true).unwrap());
+        assert_eq!(4, keys(&store, a).len());
+        assert_eq!(expected, store.prune(now, false).unwrap());
+        assert!(store.prune(now, false).unwrap().is_empty());
+
+        assert_eq!(vec!["id:4", "id:3", "id:1"], keys(&store, a));
+        assert_eq!(vec!["id:4", "id:3"], keys(&store, b));
+        let three = store.item_by_key(b, "id:3").unwrap().unwrap();
+        assert_eq!(None, three.item.content);
+        assert!(store
+            .item_by_key(b, "id:4")
+            .unwrap()
+            .unwrap()
+            .item
+            .content
+            .is_some());
+
+        // Removed items and content are not stored again.
+        let upserted = upsert(&mut store, b, &INPUT.replace("Three", "Tres"));
+        assert_eq!(0, upserted.inserted);
+        assert_eq!(1, upserted.updated);
+        assert_eq!(vec!["id:4", "id:3"], keys(&store, b));
+        let three = store.item_by_key(b, "id:3").unwrap().unwrap();
+        assert_eq!(None, three.item.content);
+        assert_eq!(
+            Some("Tres"),
+            three.item.summary.as_ref().map(|v| v.value.as_str())
+        );
+
+        // Removed items are forgotten once they are no longer in the feed.
+        upsert(
+            &mut store,
+            b,
+            &INPUT.replace("<guid>1</guid>", "<guid>5</guid>"),
+        );
+        let pruned: i64 = store
+            .conn
+            .query_row(
+                "SELECT count(*) FROM pruned_items WHERE subscription_id = ?1",
+                [b],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(1, pruned);
+        assert_eq!(1, upsert(&mut store, b, INPUT).inserted);
+        assert!(store.item_by_key(b, "id:1").unwrap().is_some());
+        assert!(store.item_by_key(b, "id:2").unwrap().is_none());
+
+        assert!(store.reclaim_space().is_ok());
+    }
+}
@@ -0,0 +1,326 @@
+//! Full-text search over items.
+//!
+//! The title, summary, content and author names of every item are indexed
+//! with an FTS5 table. HTML markup is removed before the text is indexed.
+
+use rusqlite::{functions::FunctionFlags, params, Connection};
+
+use crate::{feed::StoredItem, Result, Store};
+
+/// A full-text search for items.
+///
+/// The query is a list of terms which must all be found in an item. A term
+/// in double quotes is a phrase and a term ending with `*` is a prefix.
+///
+/// ```text
+/// "lock-free queue" rust*
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Search<'a> {
+    pub query: &'a str,
+    /// Only search the items of the subscription.
+    pub subscription_id: Option<i64>,
+    /// The maximum number of results.
+    pub limit: usize,
+    /// The text inserted before and after each match in a snippet.
+    pub highlight: (&'a str, &'a str),
+}
+
+impl<'a> Search<'a> {
+    #[must_use]
+    pub const fn new(query: &'a str) -> Self {
+        Self {
+            query,
+            subscription_id: None,
+            limit: 50,
+            highlight: ("[", "]"),
+        }
+    }
+}
+
+/// An item which matched a search.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub item: StoredItem,
+    /// An excerpt of the text around the matches with the matches
+    /// highlighted.
+    pub snippet: String,
+    /// The relevance of the item. Higher values are more relevant.
+    pub score: f64,
+}
+
+/// The maximum length of a character reference which is decoded.
+const MAX_REFERENCE_LEN: usize = 32;
+
+/// Returns the position of the needle ignoring ASCII case.
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    haystack
+        .as_bytes()
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+}
+
+/// Returns the text of HTML markup.
+///
+/// Tags, comments and the contents of `script` and `style` elements are
+/// removed. Common character references are decoded.
+fn strip_html(html: &str) -> String {
+    let mut output = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find(['<', '&']) {
+        output.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if rest.starts_with('&') {
+            if let Some((c, end)) = rest
+                .bytes()
+                .take(MAX_REFERENCE_LEN)
+                .position(|b| b == b';')
+                .and_then(|end| Some((decode_reference(&rest[1..end])?, end)))
+            {
+                output.push(c);
+                rest = &rest[end + 1..];
+            } else {
+                output.push('&');
+                rest = &rest[1..];
+            }
+            continue;
+        }
+
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->").map(|end| end + 3)
+        } else if let Some(name) = ["script", "style"].into_iter().find(|name| {
+            rest.get(1..=name.len())
+                .is_some_and(|v| v.eq_ignore_ascii_case(name))
+                && rest
+                    .as_bytes()
+                    .get(name.len() + 1)
+                    .is_some_and(|&b| b.is_ascii_whitespace() || b == b'>' || b == b'/')
+        }) {
+            find_ignore_ascii_case(rest, &format!("</{name}"))
+                .and_then(|idx| rest[idx..].find('>').map(|end| idx + end + 1))
+        } else {
+            rest.find('>').map(|end| end + 1)
+        };
+        // Tags usually separate words.
+        output.push(' ');
+        rest = end.map_or("", |end| &rest[end..]);
+    }
+    output.push_str(rest);
+
+    output.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn decode_reference(name: &str) -> Option<char> {
+    if let Some(number) = name.strip_prefix('#') {
+        let value = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(value);
+    }
+
+    Some(match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        _ => return None,
+    })
+}
+
+/// Registers the `strip_html(value, ty)` function used to index items.
+pub(crate) fn register_functions(conn: &Connection) -> Result<()> {
+    conn.create_scalar_function(
+        "strip_html",
+        2,
+        FunctionFlags::SQLITE_UTF8
+            | FunctionFlags::SQLITE_DETERMINISTIC
+            | FunctionFlags::SQLITE_INNOCUOUS,
+        |ctx| {
+            let Some(value) = ctx.get::<Option<String>>(0)? else {
+                return Ok(None);
+            };
+            let ty = ctx.get::<Option<String>>(1)?;
+            Ok(Some(match ty.as_deref() {
+                Some("html" | "xhtml") => strip_html(&value),
+                _ => value,
+            }))
+        },
+    )?;
+    Ok(())
+}
+
+/// Converts a search query into an FTS5 query.
+///
+/// Every term is quoted so the query cannot contain FTS5 operators. Returns
+/// `None` if there are no terms.
+fn match_query(query: &str) -> Option<String> {
+    let mut terms = Vec::new();
+    let mut rest = query.trim_start();
+    while !rest.is_empty() {
+        let (term, after) = if let Some(phrase) = rest.strip_prefix('"') {
+            let end = phrase.find('"').unwrap_or(phrase.len());
+            (&phrase[..end], phrase.get(end + 1..).unwrap_or_default())
+        } else {
+            rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len()))
+        };
+        let (term, is_prefix, after) = if let Some(after) = after.strip_prefix('*') {
+            (term, true, after)
+        } else if let Some(term) = term.strip_suffix('*') {
+            (term, true, after)
+        } else {
+            (term, false, after)
+        };
+
+        // Terms without any letters or numbers have no tokens to match.
+        if term.chars().any(char::is_alphanumeric) {
+            let quoted = format!("\"{}\"", term.replace('"', "\"\""));
+            terms.push(if is_prefix { quoted + " *" } else { quoted });
+        }
+        rest = after.trim_start();
+    }
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+impl Store {
+    /// Finds the items which match a search with the most relevant first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn search(&self, search: &Search<'_>) -> Result<Vec<SearchResult>> {
+        let Some(query) = match_query(search.query) else {
+            return Ok(Vec::new());
+        };
+
+        // Matches in the title are the most relevant, followed by the
+        // summary, the authors and the content.
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {}, snippet(items_search, -1, ?3, ?4, '…', 16),
+                 bm25(items_search, 10.0, 5.0, 1.0, 2.0) AS rank
+             FROM items_search JOIN items ON items.id = items_search.rowid
+             WHERE items_search MATCH ?1 AND (?2 IS NULL OR items.subscription_id = ?2)
+             ORDER BY rank
+             LIMIT ?5",
+            StoredItem::columns()
+        ))?;
+        let results = stmt
+            .query_map(
+                params![
+                    query,
+                    search.subscription_id,
+                    search.highlight.0,
+                    search.highlight.1,
+                    i64::try_from(search.limit).unwrap_or(i64::MAX),
+                ],
+                |row| {
+                    Ok(SearchResult {
+                        item: StoredItem::from_row(row)?,
+                        snippet: row.get(25)?,
+                        score: -row.get::<_, f64>(26)?,
+                    })
+                },
+            )?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{store_with, upsert};
+
+    use super::*;
+
+    const INPUT: &str = r#"<rss version="2.0"><channel><title>Example</title>
+<item><guid>1</guid><title>Lock-free queues in Rust</title>
+<description>&lt;p&gt;Building a &lt;em&gt;lock-free&lt;/em&gt; queue &amp;amp; more.&lt;/p&gt;&lt;script&gt;tracking()&lt;/script&gt;</description>
+<author>jane@example.com (Jane Doe)</author></item>
+<item><guid>2</guid><title>Queue theory</title><description>Waiting in line.</description></item>
+</channel></rss>"#;
+
+    fn keys(store: &Store, search: &Search<'_>) -> Vec<String> {
+        store
+            .search(search)
+            .unwrap()
+            .into_iter()
+            .map(|result| result.item.key)
+            .collect()
+    }
+
+    #[test]
+    fn html() {
+        assert_eq!(
+            "Building a lock-free queue & more. “Quoted”",
+            strip_html(
+                "<p>Building a <em>lock-free</em> queue &amp; more.</p>\n\
+                 <!-- <b>comment</b> --><SCRIPT>x < y</script>&#8220;Quoted&#x201D;"
+            )
+        );
+        assert_eq!("a &unknown; b", strip_html("a &unknown; b<br"));
+        assert_eq!(
+            "a b c",
+            strip_html("a<Script type=\"x\">x</sCRIPT>b<style>y</STYLE >c")
+        );
+        assert_eq!(
+            "See the guide. Notes",
+            strip_html("<styleguide>See the guide.</styleguide><scripts>Notes</scripts>")
+        );
+        let long = format!("&{};", "a".repeat(MAX_REFERENCE_LEN));
+        assert_eq!(long, strip_html(&long));
+    }
+
+    #[test]
+    fn query() {
+        assert_eq!(None, match_query("  - \"\" "));
+        assert_eq!(
+            Some(r#""lock free" "queue" * "rust" * "a""b""#.to_string()),
+            match_query(r#""lock free" queue* "rust"* a"b"#)
+        );
+    }
+
+    #[test]
+    fn search() {
+        let (mut store, id) = store_with(INPUT);
+
+        assert_eq!(vec!["id:2", "id:1"], keys(&store, &Search::new("queue")));
+        assert_eq!(vec!["id:1"], keys(&store, &Search::new("\"lock free\"")));
+        assert_eq!(vec!["id:1"], keys(&store, &Search::new("jan*")));
+        assert_eq!(vec!["id:1"], keys(&store, &Search::new("jane queue")));
+        assert!(keys(&store, &Search::new("tracking")).is_empty());
+        assert!(keys(&store, &Search::new("NEAR(")).is_empty());
+        assert!(keys(
+            &store,
+            &Search {
+                subscription_id: Some(id + 1),
+                ..Search::new("queue")
+            }
+        )
+        .is_empty());
+
+        let results = store.search(&Search::new("rust")).unwrap();
+        assert_eq!(1, results.len());
+        assert_eq!("Lock-free queues in [Rust]", results[0].snippet);
+        assert!(results[0].score > 0.0);
+
+        let edited = INPUT.replace("Waiting in line", "Waiting in Rust");
+        upsert(&mut store, id, &edited);
+        assert_eq!(vec!["id:1", "id:2"], keys(&store, &Search::new("rust")));
+
+        store
+            .conn
+            .execute("DELETE FROM items WHERE key = 'id:1'", [])
+            .unwrap();
+        assert_eq!(vec!["id:2"], keys(&store, &Search::new("rust")));
+    }
+}
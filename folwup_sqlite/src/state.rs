@@ -0,0 +1,227 @@
+//! Read, starred and archived state of items.
+//!
+//! The state is only changed by the user. Storing a re-fetched item with
+//! [`Store::upsert_feed()`] keeps its state.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Row};
+
+use crate::{
+    feed::{get_timestamp, StoredItem},
+    Result, Store,
+};
+
+/// The state of an item.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ItemState {
+    /// When the item was marked as read, or `None` if the item is unread.
+    pub read_at: Option<DateTime<Utc>>,
+    pub is_starred: bool,
+    pub is_archived: bool,
+}
+
+impl ItemState {
+    /// The columns to select for [`ItemState::from_row()`].
+    pub(crate) const COLUMNS: &'static str = "items.read_at, items.is_starred, items.is_archived";
+
+    /// Reads the state from the columns starting at `idx`.
+    pub(crate) fn from_row(row: &Row<'_>, idx: usize) -> rusqlite::Result<Self> {
+        Ok(Self {
+            read_at: get_timestamp(row, idx)?,
+            is_starred: row.get(idx + 1)?,
+            is_archived: row.get(idx + 2)?,
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn is_read(&self) -> bool {
+        self.read_at.is_some()
+    }
+}
+
+impl Store {
+    /// Marks an item as read or unread.
+    ///
+    /// The time an item was read is kept if it is already read.
+    ///
+    /// Returns false if the item does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn set_read(&self, item_id: i64, is_read: bool) -> Result<bool> {
+        let sql = if is_read {
+            "UPDATE items SET read_at = ifnull(read_at, unixepoch()) WHERE id = ?1"
+        } else {
+            "UPDATE items SET read_at = NULL WHERE id = ?1"
+        };
+        Ok(self.conn.execute(sql, [item_id])? > 0)
+    }
+
+    /// Stars or unstars an item.
+    ///
+    /// Returns false if the item does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn set_starred(&self, item_id: i64, is_starred: bool) -> Result<bool> {
+        Ok(self.conn.execute(
+            "UPDATE items SET is_starred = ?2 WHERE id = ?1",
+            params![item_id, is_starred],
+        )? > 0)
+    }
+
+    /// Archives or unarchives an item.
+    ///
+    /// Returns false if the item does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn set_archived(&self, item_id: i64, is_archived: bool) -> Result<bool> {
+        Ok(self.conn.execute(
+            "UPDATE items SET is_archived = ?2 WHERE id = ?1",
+            params![item_id, is_archived],
+        )? > 0)
+    }
+
+    /// Marks a subscription's unread items as read.
+    ///
+    /// If `older_than` is given, only items published (or else first stored)
+    /// before the time are marked as read.
+    ///
+    /// Returns the number of items which were marked as read.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn mark_read(
+        &self,
+        subscription_id: i64,
+        older_than: Option<DateTime<Utc>>,
+    ) -> Result<usize> {
+        Ok(self.conn.execute(
+            "UPDATE items SET read_at = unixepoch()
+             WHERE subscription_id = ?1 AND read_at IS NULL
+                AND (?2 IS NULL OR ifnull(published_at, created_at) < ?2)",
+            params![subscription_id, older_than.map(|v| v.timestamp())],
+        )?)
+    }
+
+    /// The number of a subscription's unread items which are not archived.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn unread_count(&self, subscription_id: i64) -> Result<u64> {
+        let count: i64 = self.conn.query_row(
+            "SELECT count(*) FROM items
+             WHERE subscription_id = ?1 AND read_at IS NULL AND NOT is_archived",
+            [subscription_id],
+            |row| row.get(0),
+        )?;
+        Ok(count.unsigned_abs())
+    }
+
+    /// The starred items of all subscriptions with the most recently
+    /// published first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn starred_items(&self) -> Result<Vec<StoredItem>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM items WHERE is_starred
+             ORDER BY ifnull(published_at, created_at) DESC, id DESC",
+            StoredItem::columns()
+        ))?;
+        let items = stmt
+            .query_map([], StoredItem::from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{store_with, upsert};
+
+    use super::*;
+
+    const INPUT: &str = r#"<rss version="2.0"><channel><title>Example</title>
+<item><guid>1</guid><title>One</title><pubDate>Mon, 01 Feb 2021 00:00:00 GMT</pubDate></item>
+<item><guid>2</guid><title>Two</title><pubDate>Mon, 01 Mar 2021 00:00:00 GMT</pubDate></item>
+<item><guid>3</guid><title>Three</title><pubDate>Thu, 01 Apr 2021 00:00:00 GMT</pubDate></item>
+</channel></rss>"#;
+
+    fn item(store: &Store, id: i64, key: &str) -> StoredItem {
+        store.item_by_key(id, key).unwrap().unwrap()
+    }
+
+    #[test]
+    fn set_state() {
+        let (store, id) = store_with(INPUT);
+        let one = item(&store, id, "id:1");
+        assert_eq!(ItemState::default(), one.state);
+        assert_eq!(3, store.unread_count(id).unwrap());
+
+        assert!(store.set_read(one.id, true).unwrap());
+        assert!(store.set_starred(one.id, true).unwrap());
+        let state = item(&store, id, "id:1").state;
+        assert!(state.is_read());
+        assert!(state.is_starred);
+        assert!(!state.is_archived);
+        assert_eq!(2, store.unread_count(id).unwrap());
+
+        let two = item(&store, id, "id:2");
+        assert!(store.set_archived(two.id, true).unwrap());
+        assert_eq!(1, store.unread_count(id).unwrap());
+
+        assert!(store.set_read(one.id, false).unwrap());
+        assert!(!item(&store, id, "id:1").state.is_read());
+        assert!(!store.set_read(-1, true).unwrap());
+
+        let starred = store.starred_items().unwrap();
+        assert_eq!(1, starred.len());
+        assert_eq!(one.id, starred[0].id);
+    }
+
+    #[test]
+    fn mark_read() {
+        let (store, id) = store_with(INPUT);
+        assert_eq!(
+            2,
+            store
+                .mark_read(id, "2021-03-15T00:00:00Z".parse().ok())
+                .unwrap()
+        );
+        assert!(item(&store, id, "id:2").state.is_read());
+        assert!(!item(&store, id, "id:3").state.is_read());
+
+        assert_eq!(1, store.mark_read(id, None).unwrap());
+        assert_eq!(0, store.unread_count(id).unwrap());
+        assert_eq!(0, store.mark_read(id, None).unwrap());
+    }
+
+    #[test]
+    fn upsert_keeps_state() {
+        let (mut store, id) = store_with(INPUT);
+        let one = item(&store, id, "id:1");
+        store.set_read(one.id, true).unwrap();
+        store.set_starred(one.id, true).unwrap();
+        store.set_archived(one.id, true).unwrap();
+        let state = item(&store, id, "id:1").state;
+
+        let edited = INPUT.replace("<title>One</title>", "<title>One (edited)</title>");
+        assert_eq!(1, upsert(&mut store, id, &edited).updated);
+
+        let one = item(&store, id, "id:1");
+        assert_eq!(
+            Some("One (edited)"),
+            one.item.title.as_ref().map(|v| v.value.as_str())
+        );
+        assert_eq!(state, one.state);
+    }
+}
@@ -0,0 +1,400 @@
+//! Subscriptions and the folders which group them.
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::{feed::get_timestamp, Error, Result, Store};
+
+/// A subscribed feed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Subscription {
+    pub id: i64,
+    /// The URL of the feed.
+    pub url: String,
+    /// The name given to the subscription.
+    pub title: Option<String>,
+    /// The URL of the website.
+    pub html_url: Option<String>,
+    pub folder_id: Option<i64>,
+    /// The `ETag` of the last response.
+    pub etag: Option<String>,
+    /// The `Last-Modified` value of the last response.
+    pub last_modified: Option<String>,
+    /// When the feed was last fetched.
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+impl Subscription {
+    const COLUMNS: &'static str =
+        "id, url, title, html_url, folder_id, etag, last_modified, fetched_at";
+
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            url: row.get(1)?,
+            title: row.get(2)?,
+            html_url: row.get(3)?,
+            folder_id: row.get(4)?,
+            etag: row.get(5)?,
+            last_modified: row.get(6)?,
+            fetched_at: get_timestamp(row, 7)?,
+        })
+    }
+}
+
+/// A folder of subscriptions which may be nested in another folder.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Folder {
+    pub id: i64,
+    pub parent_id: Option<i64>,
+    pub name: String,
+}
+
+impl Folder {
+    const COLUMNS: &'static str = "id, parent_id, name";
+
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            parent_id: row.get(1)?,
+            name: row.get(2)?,
+        })
+    }
+}
+
+/// Inserts a subscription unless the URL is already subscribed to.
+///
+/// Returns the ID of the new subscription.
+pub(crate) fn insert_subscription(
+    conn: &Connection,
+    url: &str,
+    title: Option<&str>,
+    html_url: Option<&str>,
+    folder_id: Option<i64>,
+) -> Result<Option<i64>> {
+    let inserted = conn.execute(
+        "INSERT INTO subscriptions (url, title, html_url, folder_id) VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (url) DO NOTHING",
+        params![url, title, html_url, folder_id],
+    )?;
+    Ok((inserted > 0).then(|| conn.last_insert_rowid()))
+}
+
+/// Returns the ID of the folder with the name, creating it if necessary.
+pub(crate) fn folder_id(conn: &Connection, parent_id: Option<i64>, name: &str) -> Result<i64> {
+    let id = conn
+        .query_row(
+            "SELECT id FROM folders WHERE parent_id IS ?1 AND name = ?2",
+            params![parent_id, name],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if let Some(id) = id {
+        return Ok(id);
+    }
+
+    conn.execute(
+        "INSERT INTO folders (parent_id, name) VALUES (?1, ?2)",
+        params![parent_id, name],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub(crate) fn subscriptions(conn: &Connection) -> Result<Vec<Subscription>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM subscriptions ORDER BY ifnull(title, url) COLLATE NOCASE, id",
+        Subscription::COLUMNS
+    ))?;
+    let subscriptions = stmt
+        .query_map([], Subscription::from_row)?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(subscriptions)
+}
+
+pub(crate) fn folders(conn: &Connection) -> Result<Vec<Folder>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM folders ORDER BY name COLLATE NOCASE, id",
+        Folder::COLUMNS
+    ))?;
+    let folders = stmt
+        .query_map([], Folder::from_row)?
+        .collect::<rusqlite::Result<_>>()?;
+    Ok(folders)
+}
+
+impl Store {
+    /// Subscribes to a feed.
+    ///
+    /// Returns the ID of the new subscription, or `None` if the URL is
+    /// already subscribed to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the folder does not exist or if the database
+    /// returns an error.
+    pub fn add_subscription(
+        &self,
+        url: &str,
+        title: Option<&str>,
+        folder_id: Option<i64>,
+    ) -> Result<Option<i64>> {
+        insert_subscription(&self.conn, url.trim(), title, None, folder_id)
+    }
+
+    /// Finds the subscription for a feed URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn subscription_by_url(&self, url: &str) -> Result<Option<Subscription>> {
+        let subscription = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM subscriptions WHERE url = ?1",
+                    Subscription::COLUMNS
+                ),
+                [url.trim()],
+                Subscription::from_row,
+            )
+            .optional()?;
+        Ok(subscription)
+    }
+
+    /// All subscriptions ordered by title.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn subscriptions(&self) -> Result<Vec<Subscription>> {
+        subscriptions(&self.conn)
+    }
+
+    /// Returns the ID of the folder with the name in the parent folder,
+    /// creating it if necessary.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the parent folder does not exist or if the
+    /// database returns an error.
+    pub fn folder_id(&self, parent_id: Option<i64>, name: &str) -> Result<i64> {
+        folder_id(&self.conn, parent_id, name)
+    }
+
+    /// All folders ordered by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn folders(&self) -> Result<Vec<Folder>> {
+        folders(&self.conn)
+    }
+
+    /// Changes the URL of a subscription such as after the feed permanently
+    /// moved.
+    ///
+    /// Returns false if the subscription does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if another subscription has the URL or if the
+    /// database returns an error.
+    pub fn set_subscription_url(&self, subscription_id: i64, url: &str) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE subscriptions SET url = ?2 WHERE id = ?1",
+            params![subscription_id, url.trim()],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Records that a subscription's feed was fetched and the cache
+    /// validators of the response.
+    ///
+    /// Returns false if the subscription does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn set_fetched(
+        &self,
+        subscription_id: i64,
+        etag: Option<&str>,
+        last_modified: Option<&str>,
+    ) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE subscriptions SET etag = ?2, last_modified = ?3, fetched_at = unixepoch()
+             WHERE id = ?1",
+            params![subscription_id, etag, last_modified],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Moves a subscription into a folder or to the top level if the folder
+    /// is `None`.
+    ///
+    /// Returns false if the subscription does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the folder does not exist or if the database
+    /// returns an error.
+    pub fn move_subscription(&self, subscription_id: i64, folder_id: Option<i64>) -> Result<bool> {
+        let updated = self.conn.execute(
+            "UPDATE subscriptions SET folder_id = ?2 WHERE id = ?1",
+            params![subscription_id, folder_id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Moves a folder into another folder or to the top level if the parent
+    /// is `None`.
+    ///
+    /// Returns false if the folder does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::FolderCycle`] if the parent is the folder or one of
+    /// its descendants. Returns an error if the parent already has a folder
+    /// with the same name or if the database returns an error.
+    pub fn move_folder(&self, folder_id: i64, parent_id: Option<i64>) -> Result<bool> {
+        let is_cycle = self.conn.query_row(
+            "WITH RECURSIVE ancestors (id) AS (
+                SELECT ?2
+                UNION
+                SELECT folders.parent_id FROM folders JOIN ancestors ON folders.id = ancestors.id
+             )
+             SELECT EXISTS (SELECT 1 FROM ancestors WHERE id = ?1)",
+            params![folder_id, parent_id],
+            |row| row.get::<_, bool>(0),
+        )?;
+        if is_cycle {
+            return Err(Error::FolderCycle { folder_id });
+        }
+
+        let updated = self.conn.execute(
+            "UPDATE folders SET parent_id = ?2 WHERE id = ?1",
+            params![folder_id, parent_id],
+        )?;
+        Ok(updated > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscribe() {
+        let store = Store::open_in_memory().unwrap();
+
+        let rust = store.folder_id(None, "Rust").unwrap();
+        assert_eq!(rust, store.folder_id(None, "Rust").unwrap());
+        let nested = store.folder_id(Some(rust), "Rust").unwrap();
+        assert_ne!(rust, nested);
+
+        let id = store
+            .add_subscription("https://example.com/feed.xml", Some("Example"), Some(rust))
+            .unwrap();
+        assert!(id.is_some());
+        assert_eq!(
+            None,
+            store
+                .add_subscription(" https://example.com/feed.xml ", None, None)
+                .unwrap()
+        );
+        store
+            .add_subscription("https://example.org/atom.xml", None, None)
+            .unwrap();
+
+        let subscription = store
+            .subscription_by_url("https://example.com/feed.xml")
+            .unwrap()
+            .unwrap();
+        assert_eq!(id, Some(subscription.id));
+        assert_eq!(Some("Example"), subscription.title.as_deref());
+        assert_eq!(Some(rust), subscription.folder_id);
+
+        let urls = store
+            .subscriptions()
+            .unwrap()
+            .into_iter()
+            .map(|s| s.url)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                "https://example.com/feed.xml",
+                "https://example.org/atom.xml"
+            ],
+            urls
+        );
+
+        assert!(store
+            .add_subscription("https://example.net/", None, Some(-1))
+            .is_err());
+        assert_eq!(2, store.folders().unwrap().len());
+    }
+
+    #[test]
+    fn move_folders() {
+        let store = Store::open_in_memory().unwrap();
+        let tech = store.folder_id(None, "Tech").unwrap();
+        let rust = store.folder_id(Some(tech), "Rust").unwrap();
+        let news = store.folder_id(None, "News").unwrap();
+        let id = store
+            .add_subscription("https://example.com/feed.xml", None, Some(tech))
+            .unwrap()
+            .unwrap();
+
+        assert!(store.move_subscription(id, Some(rust)).unwrap());
+        assert_eq!(Some(rust), store.subscriptions().unwrap()[0].folder_id);
+        assert!(store.move_subscription(id, None).unwrap());
+        assert_eq!(None, store.subscriptions().unwrap()[0].folder_id);
+        assert!(!store.move_subscription(-1, None).unwrap());
+        assert!(store.move_subscription(id, Some(-1)).is_err());
+
+        assert!(store.move_folder(tech, Some(news)).unwrap());
+        assert!(matches!(
+            store.move_folder(news, Some(rust)),
+            Err(Error::FolderCycle { folder_id }) if folder_id == news
+        ));
+        assert!(matches!(
+            store.move_folder(news, Some(news)),
+            Err(Error::FolderCycle { .. })
+        ));
+        assert!(store.move_folder(rust, None).unwrap());
+        assert!(store.move_folder(news, Some(rust)).unwrap());
+    }
+
+    #[test]
+    fn fetched() {
+        let store = Store::open_in_memory().unwrap();
+        let id = store
+            .add_subscription("https://example.com/feed.xml", None, None)
+            .unwrap()
+            .unwrap();
+        store
+            .add_subscription("https://example.org/feed.xml", None, None)
+            .unwrap();
+
+        assert!(store
+            .set_fetched(id, Some("\"v1\""), Some("Mon, 01 Feb 2021 00:00:00 GMT"))
+            .unwrap());
+        assert!(store
+            .set_subscription_url(id, "https://example.com/moved.xml")
+            .unwrap());
+        assert!(store
+            .set_subscription_url(id, "https://example.org/feed.xml")
+            .is_err());
+
+        let subscription = store
+            .subscription_by_url("https://example.com/moved.xml")
+            .unwrap()
+            .unwrap();
+        assert_eq!(Some("\"v1\""), subscription.etag.as_deref());
+        assert_eq!(
+            Some("Mon, 01 Feb 2021 00:00:00 GMT"),
+            subscription.last_modified.as_deref()
+        );
+        assert!(subscription.fetched_at.is_some());
+    }
+}
@@ -0,0 +1,148 @@
+//! Free-form tags for subscriptions.
+
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection};
+
+use crate::{Result, Store};
+
+/// Tags a subscription.
+///
+/// Returns false if the subscription already has the tag.
+pub(crate) fn insert_tag(conn: &Connection, subscription_id: i64, name: &str) -> Result<bool> {
+    conn.execute(
+        "INSERT INTO tags (name) VALUES (?1) ON CONFLICT (name) DO NOTHING",
+        [name],
+    )?;
+    let inserted = conn.execute(
+        "INSERT INTO subscription_tags (subscription_id, tag_id)
+         SELECT ?1, id FROM tags WHERE name = ?2
+         ON CONFLICT DO NOTHING",
+        params![subscription_id, name],
+    )?;
+    Ok(inserted > 0)
+}
+
+/// The tags of every subscription by subscription ID.
+pub(crate) fn subscription_tags(conn: &Connection) -> Result<HashMap<i64, Vec<String>>> {
+    let mut stmt = conn.prepare(
+        "SELECT subscription_tags.subscription_id, tags.name
+         FROM subscription_tags JOIN tags ON tags.id = subscription_tags.tag_id
+         ORDER BY tags.name",
+    )?;
+    let mut tags = HashMap::<_, Vec<_>>::new();
+    for row in stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))? {
+        let (subscription_id, name) = row?;
+        tags.entry(subscription_id).or_default().push(name);
+    }
+    Ok(tags)
+}
+
+impl Store {
+    /// Tags a subscription.
+    ///
+    /// Tag names are compared case-insensitively.
+    ///
+    /// Returns false if the subscription already has the tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subscription does not exist or if the
+    /// database returns an error.
+    pub fn add_tag(&mut self, subscription_id: i64, name: &str) -> Result<bool> {
+        let tx = self.conn.transaction()?;
+        let inserted = insert_tag(&tx, subscription_id, name.trim())?;
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// Removes a tag from a subscription.
+    ///
+    /// Returns false if the subscription did not have the tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn remove_tag(&mut self, subscription_id: i64, name: &str) -> Result<bool> {
+        let name = name.trim();
+        let tx = self.conn.transaction()?;
+        let deleted = tx.execute(
+            "DELETE FROM subscription_tags
+             WHERE subscription_id = ?1 AND tag_id = (SELECT id FROM tags WHERE name = ?2)",
+            params![subscription_id, name],
+        )?;
+        // Tags only exist while a subscription has them.
+        tx.execute(
+            "DELETE FROM tags WHERE name = ?1
+                AND NOT EXISTS (SELECT 1 FROM subscription_tags WHERE tag_id = tags.id)",
+            [name],
+        )?;
+        tx.commit()?;
+        Ok(deleted > 0)
+    }
+
+    /// A subscription's tags ordered by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn subscription_tags(&self, subscription_id: i64) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tags.name
+             FROM subscription_tags JOIN tags ON tags.id = subscription_tags.tag_id
+             WHERE subscription_tags.subscription_id = ?1
+             ORDER BY tags.name",
+        )?;
+        let tags = stmt
+            .query_map([subscription_id], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(tags)
+    }
+
+    /// All tags ordered by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database returns an error.
+    pub fn tags(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare("SELECT name FROM tags ORDER BY name")?;
+        let tags = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags() {
+        let mut store = Store::open_in_memory().unwrap();
+        let a = store
+            .add_subscription("https://example.com/feed.xml", None, None)
+            .unwrap()
+            .unwrap();
+        let b = store
+            .add_subscription("https://example.org/feed.xml", None, None)
+            .unwrap()
+            .unwrap();
+
+        assert!(store.add_tag(a, "rust").unwrap());
+        assert!(!store.add_tag(a, " Rust ").unwrap());
+        assert!(store.add_tag(a, "news").unwrap());
+        assert!(store.add_tag(b, "RUST").unwrap());
+        assert!(store.add_tag(-1, "news").is_err());
+
+        assert_eq!(vec!["news", "rust"], store.subscription_tags(a).unwrap());
+        assert_eq!(vec!["rust"], store.subscription_tags(b).unwrap());
+        assert_eq!(vec!["news", "rust"], store.tags().unwrap());
+
+        assert!(store.remove_tag(a, "news").unwrap());
+        assert!(!store.remove_tag(a, "news").unwrap());
+        assert!(store.remove_tag(a, "rust").unwrap());
+        assert_eq!(vec!["rust"], store.tags().unwrap());
+        assert!(store.subscription_tags(a).unwrap().is_empty());
+    }
+}